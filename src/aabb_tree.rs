@@ -0,0 +1,109 @@
+use glam::Vec3;
+use crate::geometry::{Point3, Ray3};
+
+/// Boxes per leaf; small leaves keep the per-box tests after a leaf is reached cheap
+const LEAF_SIZE: usize = 4;
+
+/// Bounding volume hierarchy over axis-aligned boxes, each tagged with a caller-chosen item
+/// (e.g. the index of a render instance). Built once, then queried with rays.
+#[derive(Debug, Clone, Default)]
+pub struct AabbTree {
+    nodes: Vec<AabbNode>,
+    boxes: Vec<(Vec3, Vec3, usize)>,  // (min, max, item), reordered so each leaf's boxes are contiguous
+}
+
+#[derive(Debug, Clone)]
+struct AabbNode {
+    min: Vec3,
+    max: Vec3,
+    // Leaves cover `boxes[first..first + count]`; inner nodes (count 0) have their
+    // children at `nodes[first]` and `nodes[first + 1]`
+    first: usize,
+    count: usize,
+}
+
+impl AabbTree {
+    /// Build the tree by splitting at the median box center along the longest axis
+    pub fn build(boxes: Vec<(Point3, Point3, usize)>) -> Self {
+        let mut tree = AabbTree {
+            nodes: Vec::new(),
+            boxes: boxes.into_iter().map(|(min, max, item)| (min.into(), max.into(), item)).collect(),
+        };
+        if !tree.boxes.is_empty() {
+            tree.nodes.push(AabbNode { min: Vec3::ZERO, max: Vec3::ZERO, first: 0, count: 0 });
+            tree.build_node(0, 0, tree.boxes.len());
+        }
+        tree
+    }
+
+    fn build_node(&mut self, node: usize, start: usize, end: usize) {
+        let slice = &mut self.boxes[start..end];
+        let (min, max) = slice.iter().fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), b| (min.min(b.0), max.max(b.1)));
+        self.nodes[node].min = min;
+        self.nodes[node].max = max;
+
+        if slice.len() <= LEAF_SIZE {
+            self.nodes[node].first = start;
+            self.nodes[node].count = slice.len();
+            return;
+        }
+
+        let (center_min, center_max) = slice.iter()
+            .map(|b| b.0 + b.1)
+            .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), c| (min.min(c), max.max(c)));
+        let extent = center_max - center_min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z { 0 } else if extent.y >= extent.z { 1 } else { 2 };
+        let mid = slice.len() / 2;
+        slice.select_nth_unstable_by(mid, |a, b| (a.0 + a.1)[axis].total_cmp(&(b.0 + b.1)[axis]));
+
+        let left = self.nodes.len();
+        for _ in 0..2 {
+            self.nodes.push(AabbNode { min: Vec3::ZERO, max: Vec3::ZERO, first: 0, count: 0 });
+        }
+        self.nodes[node].first = left;
+        self.build_node(left, start, start + mid);
+        self.build_node(left + 1, start + mid, end);
+    }
+
+    /// Items whose boxes the ray passes through, with the distance along the ray at which
+    /// it enters each box (0 if it starts inside), nearest first
+    pub fn ray_candidates(&self, ray: &Ray3) -> Vec<(f32, usize)> {
+        let mut candidates = Vec::new();
+        if self.nodes.is_empty() {
+            return candidates;
+        }
+        let origin: Vec3 = ray.origin.into();
+        let inv_direction = Vec3::from(ray.direction().vec3).recip();
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if ray_box_entry(origin, inv_direction, node.min, node.max).is_none() {
+                continue;
+            }
+            if node.count == 0 {
+                stack.extend([node.first, node.first + 1]);
+                continue;
+            }
+            for &(min, max, item) in &self.boxes[node.first..node.first + node.count] {
+                if let Some(entry) = ray_box_entry(origin, inv_direction, min, max) {
+                    candidates.push((entry, item));
+                }
+            }
+        }
+
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+        candidates
+    }
+}
+
+/// Distance at which a ray enters a box (slab test), or None if it misses it or the box
+/// is entirely behind the origin
+fn ray_box_entry(origin: Vec3, inv_direction: Vec3, min: Vec3, max: Vec3) -> Option<f32> {
+    let t1 = (min - origin) * inv_direction;
+    let t2 = (max - origin) * inv_direction;
+    // `f32::min`/`max` skip the NaN of a ray lying exactly in a slab plane
+    let entry = t1.x.min(t2.x).max(t1.y.min(t2.y)).max(t1.z.min(t2.z));
+    let exit = t1.x.max(t2.x).min(t1.y.max(t2.y)).min(t1.z.max(t2.z));
+    (exit >= entry.max(0.0)).then_some(entry.max(0.0))
+}
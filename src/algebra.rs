@@ -125,11 +125,14 @@ impl BitXor<Vec3> for Bivec3 {
     }
 }
 
+// Only the exterior-algebra reference raycast in the `algorithms` tests uses this so far
+#[cfg(test)]
 pub trait InnerProduct<Rhs = Self> {
     type Output;
     fn inner(self, rhs: Rhs) -> Self::Output;
 }
 
+#[cfg(test)]
 impl InnerProduct<Vec3> for Vec3 {
     type Output = f32;
     #[inline]
@@ -204,28 +207,42 @@ impl Vec3 {
     }
 }
 
+impl From<glam::Vec3> for Vec3 {
+    fn from(v: glam::Vec3) -> Self {
+        Vec3 { x: v.x, y: v.y, z: v.z }
+    }
+}
+
+impl From<Vec3> for glam::Vec3 {
+    fn from(v: Vec3) -> Self {
+        glam::Vec3::new(v.x, v.y, v.z)
+    }
+}
+
 // Implement Transformable for Vec3
 impl crate::Transformable for Vec3 {
     /// Apply transform to a vector (no translation, just rotation and scale)
     fn transform(&self, transform: &crate::Transform) -> Self {
-        let glam_vec = glam::Vec3::new(self.x, self.y, self.z);
-        let transformed = transform.transform_vector(glam_vec);
-        Vec3 {
-            x: transformed.x,
-            y: transformed.y,
-            z: transformed.z,
-        }
+        transform.transform_vector((*self).into()).into()
     }
 
     /// Apply inverse transform
     fn inverse_transform(&self, transform: &crate::Transform) -> Self {
-        let glam_vec = glam::Vec3::new(self.x, self.y, self.z);
-        let inverse_transform = transform.inverse();
-        let transformed = inverse_transform.transform_vector(glam_vec);
-        Vec3 {
-            x: transformed.x,
-            y: transformed.y,
-            z: transformed.z,
-        }
+        transform.inverse().transform_vector((*self).into()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vec3_round_trips_through_glam() {
+        let glam_vec = glam::Vec3::new(1.5, -2.0, 3.25);
+
+        let vec: Vec3 = glam_vec.into();
+
+        assert_eq!([vec.x, vec.y, vec.z], [1.5, -2.0, 3.25]);
+        assert_eq!(glam::Vec3::from(vec), glam_vec);
     }
 }
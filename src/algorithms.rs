@@ -1,110 +1,506 @@
-use crate::Point3;
-use crate::algebra::{Dual, InnerProduct};
-use crate::geometry::{Ray3, Direction3, HitResponse};
-
-// The Möller–Trumbore intersection algorithm, implementation using some exterior algebra
-pub fn moller_trumbore_intersection_exterior_algebra(ray: Ray3, a: Point3, b: Point3, c: Point3) -> Option<HitResponse> {
-    let origin_vec3 = ray.origin.vec3;
-    let direction_vec3 = ray.direction().vec3;
-    
-    
-    let edge1 = (b - a).vec3;
-    let edge2 = (c - a).vec3;
-
-    let ray_edge2_plane = direction_vec3 ^ edge2;
-    let det = edge1.inner(ray_edge2_plane.dual());
-    if det > -f32::EPSILON && det < f32::EPSILON {
-        return None; // The three vectors are not suitably linearly independent
-    }
-
-    let resize = 1.0 / det;
-    let s = origin_vec3 - a.vec3;
-    // TODO: This may be optimizable
-    let u = resize * s.inner(ray_edge2_plane.dual());
-    
-    if u < 0.0 || u > 1.0 {
-        return None;
-    }
-
-    let s_edge1_plane = s ^ edge1;
-    // TODO: This may be optimizable
-    let v = resize * direction_vec3.inner(s_edge1_plane.dual());
-    if v < 0.0 || u + v > 1.0 {
-        return None;
-    }
-
-    // Calculate distance from origin to hit point
-    let t = resize * (edge2 ^ s_edge1_plane).xyz;
-
-    if t > f32::EPSILON {
-        // Ray intersection
-        let scaled_direction_vec3 = direction_vec3 * t;
-        let intersection = origin_vec3 + scaled_direction_vec3;
-        Some(
-            HitResponse {
-                hit_position: Point3 {
-                    vec3: intersection
-                },
-                hit_direction: Direction3 {
-                    vec3: scaled_direction_vec3
-                }})
-    } else {
-        // Line intersection but no ray intersection
-        None
+use crate::{Mesh, Point3, Vec3};
+use crate::geometry::Ray3;
+
+// Four triangles in structure-of-arrays layout, so one ray can be tested against all of them
+// with the same instructions. Lanes that are never set hold degenerate triangles, which miss.
+#[derive(Clone, Copy, Default)]
+pub struct TrianglePacket {
+    corner: [[f32; 4]; 3],  // First corner, per axis
+    edge1: [[f32; 4]; 3],   // Second corner minus first
+    edge2: [[f32; 4]; 3],   // Third corner minus first
+}
+
+impl TrianglePacket {
+    pub fn set_lane(&mut self, lane: usize, a: Point3, b: Point3, c: Point3) {
+        let edge1 = (b - a).vec3;
+        let edge2 = (c - a).vec3;
+        for (axis, (a, (e1, e2))) in [a.vec3.x, a.vec3.y, a.vec3.z].into_iter()
+            .zip([edge1.x, edge1.y, edge1.z].into_iter().zip([edge2.x, edge2.y, edge2.z]))
+            .enumerate()
+        {
+            self.corner[axis][lane] = a;
+            self.edge1[axis][lane] = e1;
+            self.edge2[axis][lane] = e2;
+        }
+    }
+}
+
+// Möller–Trumbore against the four triangles of a packet, with the same tolerances as the
+// single-triangle reference versions in the tests. Returns the distance along the
+// (normalized) ray to each triangle, or infinity where it misses.
+#[cfg(not(feature = "simd"))]
+pub fn moller_trumbore_packet(ray: Ray3, packet: &TrianglePacket) -> [f32; 4] {
+    let origin = ray.origin.vec3;
+    let direction = ray.direction().vec3;
+    let [ax, ay, az] = packet.corner;
+    let [e1x, e1y, e1z] = packet.edge1;
+    let [e2x, e2y, e2z] = packet.edge2;
+
+    // Plain per-lane arithmetic over the arrays, which the compiler can vectorize
+    std::array::from_fn(|lane| {
+        let px = direction.y * e2z[lane] - direction.z * e2y[lane];
+        let py = direction.z * e2x[lane] - direction.x * e2z[lane];
+        let pz = direction.x * e2y[lane] - direction.y * e2x[lane];
+        let det = e1x[lane] * px + e1y[lane] * py + e1z[lane] * pz;
+        let inv_det = 1.0 / det;
+
+        let sx = origin.x - ax[lane];
+        let sy = origin.y - ay[lane];
+        let sz = origin.z - az[lane];
+        let u = (sx * px + sy * py + sz * pz) * inv_det;
+
+        let qx = sy * e1z[lane] - sz * e1y[lane];
+        let qy = sz * e1x[lane] - sx * e1z[lane];
+        let qz = sx * e1y[lane] - sy * e1x[lane];
+        let v = (direction.x * qx + direction.y * qy + direction.z * qz) * inv_det;
+        let t = (e2x[lane] * qx + e2y[lane] * qy + e2z[lane] * qz) * inv_det;
+
+        let hit = det.abs() >= f32::EPSILON && (0.0..=1.0).contains(&u) && v >= 0.0 && u + v <= 1.0 && t > f32::EPSILON;
+        if hit { t } else { f32::INFINITY }
+    })
+}
+
+// Same as above using portable SIMD, one triangle per lane (needs a nightly compiler)
+#[cfg(feature = "simd")]
+pub fn moller_trumbore_packet(ray: Ray3, packet: &TrianglePacket) -> [f32; 4] {
+    use std::simd::{cmp::SimdPartialOrd, f32x4, num::SimdFloat, Select};
+
+    let origin = ray.origin.vec3;
+    let direction = ray.direction().vec3;
+    let [dx, dy, dz] = [direction.x, direction.y, direction.z].map(f32x4::splat);
+    let [ax, ay, az] = packet.corner.map(f32x4::from_array);
+    let [e1x, e1y, e1z] = packet.edge1.map(f32x4::from_array);
+    let [e2x, e2y, e2z] = packet.edge2.map(f32x4::from_array);
+    let zero = f32x4::splat(0.0);
+    let one = f32x4::splat(1.0);
+    let epsilon = f32x4::splat(f32::EPSILON);
+
+    let px = dy * e2z - dz * e2y;
+    let py = dz * e2x - dx * e2z;
+    let pz = dx * e2y - dy * e2x;
+    let det = e1x * px + e1y * py + e1z * pz;
+    let inv_det = one / det;
+
+    let sx = f32x4::splat(origin.x) - ax;
+    let sy = f32x4::splat(origin.y) - ay;
+    let sz = f32x4::splat(origin.z) - az;
+    let u = (sx * px + sy * py + sz * pz) * inv_det;
+
+    let qx = sy * e1z - sz * e1y;
+    let qy = sz * e1x - sx * e1z;
+    let qz = sx * e1y - sy * e1x;
+    let v = (dx * qx + dy * qy + dz * qz) * inv_det;
+    let t = (e2x * qx + e2y * qy + e2z * qz) * inv_det;
+
+    let hit = det.abs().simd_ge(epsilon)
+        & u.simd_ge(zero) & u.simd_le(one)
+        & v.simd_ge(zero) & (u + v).simd_le(one)
+        & t.simd_gt(epsilon);
+    hit.select(t, f32x4::splat(f32::INFINITY)).to_array()
+}
+
+
+
+// Small, fast deterministic pseudo-random generator (SplitMix64) for seeded geometry
+// operations such as surface sampling. Not suitable for anything security related.
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1), from the top 24 bits so every value is exactly representable
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+// Version 4 UUID whose random bits come from a seed instead of the system RNG, for ids that
+// must be the same on every run (golden files, caches). Distinct seeds practically never collide.
+pub fn seeded_uuid(seed: u64) -> uuid::Uuid {
+    let mut rng = SplitMix64::new(seed);
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&rng.next_u64().to_le_bytes());
+    bytes[8..].copy_from_slice(&rng.next_u64().to_le_bytes());
+    uuid::Builder::from_random_bytes(bytes).into_uuid()
+}
+
+
+
+// Polygon triangulation shared by importers that produce n-gon faces.
+// Convex polygons are fanned; polygons with reflex corners are ear-clipped.
+// Triangles keep the polygon's winding. Polygons with fewer than 3 corners or
+// out-of-range indices are skipped.
+pub fn triangulate_polygon(vertex_coords: &[f32], polygon_indices: &[Vec<u32>]) -> Mesh {
+    let mut mesh = Mesh::new();
+    mesh.vertex_coords = vertex_coords.to_vec();
+    let vertex_count = mesh.vertex_count() as u32;
+
+    for polygon in polygon_indices {
+        if polygon.len() < 3 || polygon.iter().any(|&i| i >= vertex_count) {
+            continue;
+        }
+        for [a, b, c] in triangulate_loop(vertex_coords, polygon) {
+            mesh.add_triangle(a, b, c);
+        }
+    }
+
+    mesh
+}
+
+pub(crate) fn triangulate_loop(vertex_coords: &[f32], polygon: &[u32]) -> Vec<[u32; 3]> {
+    let p = |i: u32| {
+        let i = i as usize;
+        [vertex_coords[3 * i], vertex_coords[3 * i + 1], vertex_coords[3 * i + 2]]
+    };
+
+    // Newell normal, then project onto the plane of its dominant axis
+    let mut normal = [0.0f32; 3];
+    for (k, &i) in polygon.iter().enumerate() {
+        let (a, b) = (p(i), p(polygon[(k + 1) % polygon.len()]));
+        normal[0] += (a[1] - b[1]) * (a[2] + b[2]);
+        normal[1] += (a[2] - b[2]) * (a[0] + b[0]);
+        normal[2] += (a[0] - b[0]) * (a[1] + b[1]);
     }
+    let dominant = (0..3).max_by(|&a, &b| normal[a].abs().total_cmp(&normal[b].abs())).unwrap();
+    let (u, v) = ((dominant + 1) % 3, (dominant + 2) % 3);
+    let points: Vec<(f32, f32)> = polygon.iter().map(|&i| (p(i)[u], p(i)[v])).collect();
+
+    // Signed turn at b; positive when it agrees with the polygon's orientation
+    let orientation = normal[dominant].signum();
+    let turn = |a: (f32, f32), b: (f32, f32), c: (f32, f32)| {
+        orientation * ((b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0))
+    };
+
+    let n = polygon.len();
+    let is_convex = (0..n).all(|k| turn(points[(k + n - 1) % n], points[k], points[(k + 1) % n]) >= 0.0);
+    if is_convex {
+        return (1..n - 1).map(|k| [polygon[0], polygon[k], polygon[k + 1]]).collect();
+    }
+
+    // Ear clipping over the remaining corners (positions in `polygon`)
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut triangles = Vec::with_capacity(n - 2);
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let ear = (0..m).find(|&k| {
+            let (ia, ib, ic) = (remaining[(k + m - 1) % m], remaining[k], remaining[(k + 1) % m]);
+            let (a, b, c) = (points[ia], points[ib], points[ic]);
+            if turn(a, b, c) <= 0.0 {
+                return false; // Reflex or collinear corner
+            }
+            // No other corner may lie inside (or on) the candidate ear
+            !remaining.iter()
+                .filter(|&&other| other != ia && other != ib && other != ic)
+                .map(|&other| points[other])
+                .any(|q| q != a && q != b && q != c && turn(a, b, q) >= 0.0 && turn(b, c, q) >= 0.0 && turn(c, a, q) >= 0.0)
+        });
+
+        // Degenerate input with no valid ear: fan what is left rather than loop forever
+        let Some(k) = ear else { break };
+        triangles.push([polygon[remaining[(k + m - 1) % m]], polygon[remaining[k]], polygon[remaining[(k + 1) % m]]]);
+        remaining.remove(k);
+    }
+    for k in 1..remaining.len() - 1 {
+        triangles.push([polygon[remaining[0]], polygon[remaining[k]], polygon[remaining[k + 1]]]);
+    }
+
+    triangles
+}
+
+
+/// Eigen decomposition of a symmetric 3x3 matrix (row-major) by cyclic Jacobi rotations.
+/// Returns the eigenvalues in descending order with their unit eigenvectors.
+pub fn symmetric_eigen3(matrix: [[f32; 3]; 3]) -> ([f32; 3], [Vec3; 3]) {
+    let mut a = matrix.map(|row| row.map(f64::from));
+    let mut v = [[1.0f64, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    for _sweep in 0..32 {
+        let off_diagonal = a[0][1].abs() + a[0][2].abs() + a[1][2].abs();
+        if off_diagonal < 1e-12 * (a[0][0].abs() + a[1][1].abs() + a[2][2].abs()).max(f64::MIN_POSITIVE) {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q] == 0.0 {
+                continue;
+            }
+            // Rotation in the (p, q) plane that zeroes a[p][q]
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+            for row in &mut a {
+                let (akp, akq) = (row[p], row[q]);
+                row[p] = c * akp - s * akq;
+                row[q] = s * akp + c * akq;
+            }
+            let (upper, lower) = a.split_at_mut(q);  // p < q
+            for (apk, aqk) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                (*apk, *aqk) = (c * *apk - s * *aqk, s * *apk + c * *aqk);
+            }
+            for row in &mut v {
+                let (vkp, vkq) = (row[p], row[q]);
+                row[p] = c * vkp - s * vkq;
+                row[q] = s * vkp + c * vkq;
+            }
+        }
+    }
+
+    // Eigenvectors are the columns of v
+    let mut order = [0, 1, 2];
+    order.sort_by(|&i, &j| a[j][j].total_cmp(&a[i][i]));
+    let values = order.map(|i| a[i][i] as f32);
+    let vectors = order.map(|i| Vec3::new(v[0][i] as f32, v[1][i] as f32, v[2][i] as f32).normalize());
+    (values, vectors)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::{Dual, InnerProduct};
+    use crate::geometry::{Direction3, HitResponse};
+
+    // The Möller–Trumbore intersection algorithm, implementation using some exterior algebra.
+    // Raycasts use the packet version; this stays as the reference it must agree with.
+    fn moller_trumbore_intersection_exterior_algebra(ray: Ray3, a: Point3, b: Point3, c: Point3) -> Option<HitResponse> {
+        let origin_vec3 = ray.origin.vec3;
+        let direction_vec3 = ray.direction().vec3;
+
+        let edge1 = (b - a).vec3;
+        let edge2 = (c - a).vec3;
+
+        let ray_edge2_plane = direction_vec3 ^ edge2;
+        let det = edge1.inner(ray_edge2_plane.dual());
+        if det > -f32::EPSILON && det < f32::EPSILON {
+            return None; // The three vectors are not suitably linearly independent
+        }
+
+        let resize = 1.0 / det;
+        let s = origin_vec3 - a.vec3;
+        // TODO: This may be optimizable
+        let u = resize * s.inner(ray_edge2_plane.dual());
+
+        if u < 0.0 || u > 1.0 {
+            return None;
+        }
+
+        let s_edge1_plane = s ^ edge1;
+        // TODO: This may be optimizable
+        let v = resize * direction_vec3.inner(s_edge1_plane.dual());
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
 
+        // Calculate distance from origin to hit point
+        let t = resize * (edge2 ^ s_edge1_plane).xyz;
 
+        if t > f32::EPSILON {
+            // Ray intersection
+            let scaled_direction_vec3 = direction_vec3 * t;
+            let intersection = origin_vec3 + scaled_direction_vec3;
+            Some(
+                HitResponse {
+                    hit_position: Point3 {
+                        vec3: intersection
+                    },
+                    hit_direction: Direction3 {
+                        vec3: scaled_direction_vec3
+                    }})
+        } else {
+            // Line intersection but no ray intersection
+            None
+        }
+    }
+
+    // Moller Trumbore Intersection algorithm. Largely based on the Wikipedia implementation.
+    fn moller_trumbore_intersection(ray: Ray3, a: Point3, b: Point3, c: Point3) -> Option<HitResponse> {
+        let origin_vec3 = ray.origin.vec3;
+        let direction_vec3 = ray.direction().vec3;
+
+        // TODO: Not that cross and dot here take references, compared to the wikipedia implementation which
+        //       takes in the object directly (it seems)
+
+        let edge1 = (b - a).vec3;
+        let edge2 = (c - a).vec3;
+
+        let ray_cross_edge2 = direction_vec3.cross(&edge2);
+        let det = edge1.dot(&ray_cross_edge2);
+        if det > -f32::EPSILON && det < f32::EPSILON {
+            return None;
+        }
+
+        let inv_det = 1.0 / det;
+        let s = origin_vec3 - a.vec3;
+        let u = inv_det * s.dot(&ray_cross_edge2);
+        if u < 0.0 || u > 1.0 {
+            return None;
+        }
+
+        let s_cross_edge1 = s.cross(&edge1);
+        let v = inv_det * direction_vec3.dot(&s_cross_edge1);
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        // Calculate distance from origin to hit point
+        let t = inv_det * edge2.dot(&s_cross_edge1);
+
+        if t > f32::EPSILON {
+            // Ray intersection
+            let scaled_direction_vec3 = direction_vec3 * t;
+            let intersection = origin_vec3 + scaled_direction_vec3;
+            Some(
+                HitResponse {
+                    hit_position: Point3 {
+                        vec3: intersection
+                    },
+                    hit_direction: Direction3 {
+                        vec3: scaled_direction_vec3
+                    }})
+        } else {
+            // Line intersection but no ray intersection
+            None
+        }
+    }
 
-// Moller Trumbore Intersection algorithm. Largely based on the Wikipedia implementation.
-#[allow(dead_code)]
-pub fn moller_trumbore_intersection(ray: Ray3, a: Point3, b: Point3, c: Point3) -> Option<HitResponse> {
-    let origin_vec3 = ray.origin.vec3;
-    let direction_vec3 = ray.direction().vec3;
-    
-    // TODO: Not that cross and dot here take references, compared to the wikipedia implementation which
-    //       takes in the object directly (it seems)
-    
-    let edge1 = (b - a).vec3;
-    let edge2 = (c - a).vec3;
+    #[test]
+    fn dart_triangulates_without_overlap() {
+        // Arrowhead in the XZ plane, reflex at vertex 2
+        let coords = [0.0, 0.0, 0.0, 2.0, 0.0, -1.0, 0.5, 0.0, 0.0, 2.0, 0.0, 1.0];
+        let corner = |i: u32| (coords[3 * i as usize], coords[3 * i as usize + 2]);
+        let cross = |a: (f32, f32), b: (f32, f32), c: (f32, f32)| (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
 
-    let ray_cross_edge2 = direction_vec3.cross(&edge2);
-    let det = edge1.dot(&ray_cross_edge2);
-    if det > -f32::EPSILON && det < f32::EPSILON {
-        return None;
+        // Every starting corner and both windings
+        for polygon in [vec![0, 1, 2, 3], vec![1, 2, 3, 0], vec![2, 3, 0, 1], vec![3, 2, 1, 0], vec![0, 3, 2, 1]] {
+            let mesh = triangulate_polygon(&coords, std::slice::from_ref(&polygon));
+            assert_eq!(mesh.face_count(), 2);
+
+            // Shoelace area of the polygon
+            let polygon_area: f32 = (0..4)
+                .map(|k| {
+                    let (a, b) = (corner(polygon[k]), corner(polygon[(k + 1) % 4]));
+                    a.0 * b.1 - b.0 * a.1
+                })
+                .sum::<f32>() / 2.0;
+
+            // The triangles keep the polygon's winding and exactly cover it, so none overlap
+            let mut covered = 0.0;
+            for triangle in mesh.face_indices.chunks(3) {
+                let doubled = cross(corner(triangle[0]), corner(triangle[1]), corner(triangle[2]));
+                assert_eq!(doubled.signum(), polygon_area.signum(), "{polygon:?}");
+                covered += doubled.abs() / 2.0;
+            }
+            assert!((covered - polygon_area.abs()).abs() < 1e-5, "{polygon:?}");
+        }
+
+        // Convex polygons are fanned
+        let square = triangulate_polygon(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0], &[vec![0, 1, 2, 3]]);
+        assert_eq!(square.face_indices, vec![0, 1, 2, 0, 2, 3]);
+    }
+
+    fn random_point(rng: &mut SplitMix64, scale: f32) -> Point3 {
+        Point3::new(
+            (rng.next_f32() - 0.5) * scale,
+            (rng.next_f32() - 0.5) * scale,
+            (rng.next_f32() - 0.5) * scale,
+        )
     }
 
-    let inv_det = 1.0 / det;
-    let s = origin_vec3 - a.vec3;
-    let u = inv_det * s.dot(&ray_cross_edge2);
-    if u < 0.0 || u > 1.0 {
-        return None;
+    /// `count` random triangles in a 4-unit cube around the origin, four to a packet
+    fn random_packets(rng: &mut SplitMix64, count: usize) -> Vec<([[Point3; 3]; 4], TrianglePacket)> {
+        (0..count.div_ceil(4))
+            .map(|_| {
+                let triangles: [[Point3; 3]; 4] = std::array::from_fn(|_| std::array::from_fn(|_| random_point(rng, 4.0)));
+                let mut packet = TrianglePacket::default();
+                for (lane, [a, b, c]) in triangles.into_iter().enumerate() {
+                    packet.set_lane(lane, a, b, c);
+                }
+                (triangles, packet)
+            })
+            .collect()
     }
 
-    let s_cross_edge1 = s.cross(&edge1);
-    let v = inv_det * direction_vec3.dot(&s_cross_edge1);
-    if v < 0.0 || u + v > 1.0 {
-        return None;
+    #[test]
+    fn packet_matches_scalar_reference() {
+        let mut rng = SplitMix64::new(7);
+        let packets = random_packets(&mut rng, 400);
+        let mut hits = 0;
+        for _ in 0..200 {
+            // From outside the triangles' cube, aimed somewhere into it
+            let origin = random_point(&mut rng, 20.0);
+            let target = random_point(&mut rng, 2.0);
+            let ray = Ray3::new(origin, Direction3 { vec3: (target - origin).vec3 });
+
+            for (triangles, packet) in &packets {
+                let distances = moller_trumbore_packet(ray, packet);
+                for ([a, b, c], distance) in triangles.iter().zip(distances) {
+                    let reference = moller_trumbore_intersection_exterior_algebra(ray, *a, *b, *c);
+                    // Both reference versions agree with each other too
+                    let wikipedia = moller_trumbore_intersection(ray, *a, *b, *c);
+                    assert_eq!(reference.is_some(), wikipedia.is_some());
+                    match reference.zip(wikipedia) {
+                        Some((hit, other)) => {
+                            hits += 1;
+                            let expected = (hit.hit_position - ray.origin).length();
+                            assert!((other.hit_position - hit.hit_position).length() <= 1e-4 * expected.max(1.0));
+                            assert!((distance - expected).abs() <= 1e-4 * expected.max(1.0), "{distance} vs {expected}");
+                        }
+                        None => assert_eq!(distance, f32::INFINITY),
+                    }
+                }
+            }
+        }
+        assert!(hits > 100, "only {hits} hits");
+
+        // Unset lanes are degenerate and never hit
+        let mut packet = TrianglePacket::default();
+        packet.set_lane(0, Point3::new(-1.0, -1.0, 0.0), Point3::new(1.0, -1.0, 0.0), Point3::new(0.0, 1.0, 0.0));
+        let ray = Ray3::new(Point3::new(0.0, 0.0, 5.0), Direction3 { vec3: Vec3::new(0.0, 0.0, -1.0) });
+        assert_eq!(moller_trumbore_packet(ray, &packet), [5.0, f32::INFINITY, f32::INFINITY, f32::INFINITY]);
     }
 
-    // Calculate distance from origin to hit point
-    let t = inv_det * edge2.dot(&s_cross_edge1);
+    // Timing comparison rather than a check; run with `cargo test --release -- --ignored --nocapture`,
+    // with and without `--features simd`
+    #[test]
+    #[ignore]
+    fn bench_packet_against_scalar() {
+        let mut rng = SplitMix64::new(11);
+        let packets = random_packets(&mut rng, 4096);
+        let ray = Ray3::new(Point3::new(0.3, 0.2, 10.0), Direction3 { vec3: Vec3::new(0.01, 0.02, -1.0) });
+        let rounds = 500;
+
+        let start = std::time::Instant::now();
+        let mut scalar_hits = 0;
+        for _ in 0..rounds {
+            for (triangles, _) in &packets {
+                scalar_hits += triangles.iter()
+                    .filter(|[a, b, c]| moller_trumbore_intersection_exterior_algebra(ray, *a, *b, *c).is_some())
+                    .count();
+            }
+        }
+        let scalar = start.elapsed();
 
-    if t > f32::EPSILON {
-        // Ray intersection
-        let scaled_direction_vec3 = direction_vec3 * t;
-        let intersection = origin_vec3 + scaled_direction_vec3;
-        Some(
-            HitResponse {
-                hit_position: Point3 {
-                    vec3: intersection
-                },
-                hit_direction: Direction3 {
-                    vec3: scaled_direction_vec3
-                }})
-    } else {
-        // Line intersection but no ray intersection
-        None
+        let start = std::time::Instant::now();
+        let mut packet_hits = 0;
+        for _ in 0..rounds {
+            for (_, packet) in &packets {
+                packet_hits += moller_trumbore_packet(ray, packet).iter().filter(|distance| distance.is_finite()).count();
+            }
+        }
+        let packet = start.elapsed();
+
+        assert_eq!(scalar_hits, packet_hits);
+        println!("{} triangles x {rounds}: scalar {scalar:?}, packet {packet:?}", packets.len() * 4);
     }
-}
\ No newline at end of file
+}
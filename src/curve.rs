@@ -0,0 +1,149 @@
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+use crate::{Mesh, ToMesh, Transform, Transformable, geometry::Point3};
+
+/// A tube of constant radius swept along a Catmull-Rom spline through the control points.
+/// The spline is kept parametric: the tube is rebuilt from it whenever it changes, so control
+/// points can be edited like the vertices of a mesh.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatmullRomSpline {
+    pub control_points: Vec<Point3>,
+    pub radius: f32,
+    /// Corners around each ring of the tube (at least 3)
+    pub radial_segments: u32,
+    /// Rings per span between consecutive control points (at least 1)
+    pub samples_per_segment: u32,
+}
+
+impl CatmullRomSpline {
+    pub fn new(control_points: Vec<Point3>, radius: f32, radial_segments: u32, samples_per_segment: u32) -> Self {
+        CatmullRomSpline { control_points, radius, radial_segments, samples_per_segment }
+    }
+
+    /// Points along the spline: `samples_per_segment` per span plus the last control point.
+    /// The curve passes through every control point; the ends are extended by mirroring
+    /// their neighbours, so the curve leaves each end heading straight at the next point.
+    pub fn sample(&self) -> Vec<Vec3> {
+        let points: Vec<Vec3> = self.control_points.iter().map(|&p| p.into()).collect();
+        let [first, .., last] = points[..] else {
+            return points;
+        };
+        let samples = self.samples_per_segment.max(1);
+
+        // Control point i, with a mirrored phantom point before the first and after the last
+        let point = |i: isize| match i {
+            -1 => 2.0 * first - points[1],
+            i if i as usize >= points.len() => 2.0 * last - points[points.len() - 2],
+            i => points[i as usize],
+        };
+
+        let mut sampled = Vec::with_capacity((points.len() - 1) * samples as usize + 1);
+        for span in 0..points.len() as isize - 1 {
+            let (p0, p1, p2, p3) = (point(span - 1), point(span), point(span + 1), point(span + 2));
+            for step in 0..samples {
+                let t = step as f32 / samples as f32;
+                let (t2, t3) = (t * t, t * t * t);
+                sampled.push(0.5 * (
+                    2.0 * p1
+                    + (p2 - p0) * t
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                    + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3
+                ));
+            }
+        }
+        sampled.push(last);
+        sampled
+    }
+
+    /// Bake a transform into the control points. The radius is scaled by the transform's
+    /// average scale factor, since a round tube can't follow a non-uniform scale.
+    pub fn apply_transform(&mut self, transform: &Transform) {
+        for point in &mut self.control_points {
+            *point = point.transform(transform);
+        }
+        self.radius *= transform.matrix().determinant().abs().cbrt();
+    }
+}
+
+impl ToMesh for CatmullRomSpline {
+    /// One ring of `radial_segments` vertices per sample, oriented by parallel transport so the
+    /// tube doesn't twist, and a fan cap around a center vertex at each end. Fewer than two
+    /// distinct samples give an empty mesh.
+    fn to_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::new();
+        let mut centers = self.sample();
+        centers.dedup_by(|a, b| a.distance_squared(*b) <= f32::EPSILON * f32::EPSILON);
+        if centers.len() < 2 {
+            return mesh;
+        }
+        let radial_segments = self.radial_segments.max(3);
+
+        // Tangents from the neighbouring samples
+        let tangents: Vec<Vec3> = (0..centers.len())
+            .map(|i| (centers[(i + 1).min(centers.len() - 1)] - centers[i.saturating_sub(1)]).normalize_or_zero())
+            .collect();
+
+        // Carry the first ring's orientation along, turning it only as much as the tangent turns
+        let mut normal = tangents[0].any_orthonormal_vector();
+        for (i, (&center, &tangent)) in centers.iter().zip(&tangents).enumerate() {
+            if i > 0 && tangent != Vec3::ZERO && tangents[i - 1] != Vec3::ZERO {
+                normal = Quat::from_rotation_arc(tangents[i - 1], tangent) * normal;
+                normal = (normal - tangent * normal.dot(tangent)).normalize_or(normal);
+            }
+            let binormal = tangent.cross(normal);
+            for segment in 0..radial_segments {
+                let angle = 2.0 * std::f32::consts::PI * segment as f32 / radial_segments as f32;
+                let p = center + self.radius * (angle.cos() * normal + angle.sin() * binormal);
+                mesh.add_vertex(p.x, p.y, p.z);
+            }
+        }
+
+        // Vertex `segment` of ring `ring`, wrapping around the seam
+        let ring_vertex = |ring: usize, segment: u32| ring as u32 * radial_segments + segment % radial_segments;
+        for ring in 0..centers.len() - 1 {
+            for segment in 0..radial_segments {
+                let corner = ring_vertex(ring, segment);
+                let corner_around = ring_vertex(ring, segment + 1);
+                let corner_along = ring_vertex(ring + 1, segment);
+                let corner_both = ring_vertex(ring + 1, segment + 1);
+
+                mesh.add_triangle(corner, corner_around, corner_along);
+                mesh.add_triangle(corner_around, corner_both, corner_along);
+            }
+        }
+
+        let last_ring = centers.len() - 1;
+        let (start_center, end_center) = (mesh.vertex_count() as u32, mesh.vertex_count() as u32 + 1);
+        for center in [centers[0], centers[last_ring]] {
+            mesh.add_vertex(center.x, center.y, center.z);
+        }
+        for segment in 0..radial_segments {
+            mesh.add_triangle(start_center, ring_vertex(0, segment + 1), ring_vertex(0, segment));
+            mesh.add_triangle(end_center, ring_vertex(last_ring, segment), ring_vertex(last_ring, segment + 1));
+        }
+
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn straight_spline_is_a_capped_tube() {
+        let spline = CatmullRomSpline::new(vec![Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 4.0, 0.0)], 1.0, 16, 8);
+        let samples = spline.sample();
+        assert_eq!(samples.len(), 9);
+        assert!(samples[4].abs_diff_eq(Vec3::new(0.0, 2.0, 0.0), 1e-5));
+
+        // 9 rings of 16 plus a center for each cap; two triangles per quad of the side, a fan per cap
+        let tube = spline.to_mesh();
+        assert_eq!(tube.vertex_count(), 9 * 16 + 2);
+        assert_eq!(tube.face_count(), 8 * 16 * 2 + 2 * 16);
+        // Closed and facing out: the volume of a 16-sided prism of circumradius 1 and height 4
+        let expected = 16.0 * 0.5 * (2.0 * std::f32::consts::PI / 16.0).sin() * 4.0;
+        assert!((tube.signed_volume() - expected).abs() < 1e-3, "{}", tube.signed_volume());
+        assert!(crate::HalfEdgeMesh::from_mesh(&tube).is_closed());
+    }
+}
@@ -1,12 +1,39 @@
 use crate::{Transform, Transformable, Vec3, scene_graph::EdgeId};
+use serde::{Deserialize, Serialize};
 
 
 
-#[derive(Debug, Clone, Copy)]
+// Serialized as a plain `[x, y, z]` array
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(from = "[f32; 3]", into = "[f32; 3]")]
 pub struct Point3 {
     pub vec3: Vec3,
 }
 
+impl From<[f32; 3]> for Point3 {
+    fn from([x, y, z]: [f32; 3]) -> Self {
+        Point3::new(x, y, z)
+    }
+}
+
+impl From<Point3> for [f32; 3] {
+    fn from(point: Point3) -> Self {
+        [point.vec3.x, point.vec3.y, point.vec3.z]
+    }
+}
+
+impl From<glam::Vec3> for Point3 {
+    fn from(v: glam::Vec3) -> Self {
+        Point3 { vec3: v.into() }
+    }
+}
+
+impl From<Point3> for glam::Vec3 {
+    fn from(point: Point3) -> Self {
+        point.vec3.into()
+    }
+}
+
 impl Point3 {
     pub fn new(x: f32, y: f32, z: f32) -> Self {
         Point3 {
@@ -31,37 +58,127 @@ impl std::ops::Sub for Point3 {
 
 impl Transformable for Point3 {
     fn transform(&self, transform: &Transform) -> Self {
-        let glam_vec = glam::Vec3::new(self.vec3.x, self.vec3.y, self.vec3.z);
-        let transformed = transform.transform_point(glam_vec);
-        Point3 {
-            vec3: Vec3 {
-                x: transformed.x,
-                y: transformed.y,
-                z: transformed.z,
-            }
-        }
+        transform.transform_point((*self).into()).into()
     }
 
     fn inverse_transform(&self, transform: &Transform) -> Self {
-        let glam_vec = glam::Vec3::new(self.vec3.x, self.vec3.y, self.vec3.z);
-        let inverse_transform = transform.inverse();
-        let transformed = inverse_transform.transform_point(glam_vec);
-        Point3 {
-            vec3: Vec3 {
-                x: transformed.x,
-                y: transformed.y,
-                z: transformed.z,
-            }
+        transform.inverse().transform_point((*self).into()).into()
+    }
+}
+
+
+/// Axis-aligned box enclosing the given box after it has been transformed.
+/// All 8 corners are transformed, so rotations produce a (looser) enclosing box.
+pub fn transform_bounds(min: Point3, max: Point3, transform: &Transform) -> (Point3, Point3) {
+    let mut out_min = [f32::INFINITY; 3];
+    let mut out_max = [f32::NEG_INFINITY; 3];
+
+    for corner in 0..8 {
+        let p = Point3::new(
+            if corner & 1 == 0 { min.vec3.x } else { max.vec3.x },
+            if corner & 2 == 0 { min.vec3.y } else { max.vec3.y },
+            if corner & 4 == 0 { min.vec3.z } else { max.vec3.z },
+        ).transform(transform);
+
+        let coords = [p.vec3.x, p.vec3.y, p.vec3.z];
+        for axis in 0..3 {
+            out_min[axis] = out_min[axis].min(coords[axis]);
+            out_max[axis] = out_max[axis].max(coords[axis]);
         }
     }
+
+    (
+        Point3::new(out_min[0], out_min[1], out_min[2]),
+        Point3::new(out_max[0], out_max[1], out_max[2]),
+    )
 }
 
+/// Smallest axis-aligned box enclosing both boxes
+pub fn union_bounds(a: (Point3, Point3), b: (Point3, Point3)) -> (Point3, Point3) {
+    (
+        Point3::new(a.0.vec3.x.min(b.0.vec3.x), a.0.vec3.y.min(b.0.vec3.y), a.0.vec3.z.min(b.0.vec3.z)),
+        Point3::new(a.1.vec3.x.max(b.1.vec3.x), a.1.vec3.y.max(b.1.vec3.y), a.1.vec3.z.max(b.1.vec3.z)),
+    )
+}
+
+/// Whether a sphere overlaps an axis-aligned box (touching counts)
+pub fn bounds_intersect_sphere(min: Point3, max: Point3, center: Point3, radius: f32) -> bool {
+    // Distance from the center to the closest point of the box
+    let dx = center.vec3.x - center.vec3.x.clamp(min.vec3.x, max.vec3.x);
+    let dy = center.vec3.y - center.vec3.y.clamp(min.vec3.y, max.vec3.y);
+    let dz = center.vec3.z - center.vec3.z.clamp(min.vec3.z, max.vec3.z);
+    dx * dx + dy * dy + dz * dz <= radius * radius
+}
 
+/// How a volume relates to a frustum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intersection {
+    Inside,
+    Outside,
+    Intersecting,
+}
+
+/// A camera's view volume as six inward-facing planes (left, right, bottom, top, near, far)
 #[derive(Debug, Clone, Copy)]
+pub struct Frustum {
+    planes: [glam::Vec4; 6],  // (normal, offset): points p inside satisfy normal.p + offset >= 0
+}
+
+impl Frustum {
+    /// Extract the planes from a column-major view-projection matrix (Gribb-Hartmann),
+    /// using the OpenGL clip volume -w <= x, y, z <= w that three.js cameras produce
+    pub fn from_view_proj(m: [f32; 16]) -> Frustum {
+        let m = glam::Mat4::from_cols_array(&m);
+        let [row0, row1, row2, row3] = [0, 1, 2, 3].map(|i| m.row(i));
+        let planes = [row3 + row0, row3 - row0, row3 + row1, row3 - row1, row3 + row2, row3 - row2]
+            .map(|plane| {
+                let length = plane.truncate().length();
+                if length > f32::EPSILON { plane / length } else { plane }
+            });
+        Frustum { planes }
+    }
+
+    /// Classify an axis-aligned box against the frustum. Boxes near a frustum corner that
+    /// straddle two planes without entering may be reported as intersecting (conservative).
+    pub fn contains_aabb(&self, min: Point3, max: Point3) -> Intersection {
+        let (min, max) = (glam::Vec3::from(min), glam::Vec3::from(max));
+        let mut result = Intersection::Inside;
+        for plane in &self.planes {
+            let normal = plane.truncate();
+            // Box corners furthest along and against the plane normal
+            let positive = glam::Vec3::select(normal.cmpge(glam::Vec3::ZERO), max, min);
+            let negative = glam::Vec3::select(normal.cmpge(glam::Vec3::ZERO), min, max);
+            if normal.dot(positive) + plane.w < 0.0 {
+                return Intersection::Outside;
+            }
+            if normal.dot(negative) + plane.w < 0.0 {
+                result = Intersection::Intersecting;
+            }
+        }
+        result
+    }
+}
+
+
+// Serialized as a plain `[x, y, z]` array
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(from = "[f32; 3]", into = "[f32; 3]")]
 pub struct Direction3 {
     pub vec3: Vec3
 }
 
+impl From<[f32; 3]> for Direction3 {
+    fn from([x, y, z]: [f32; 3]) -> Self {
+        Direction3 { vec3: Vec3::new(x, y, z) }
+    }
+}
+
+impl From<Direction3> for [f32; 3] {
+    fn from(direction: Direction3) -> Self {
+        [direction.vec3.x, direction.vec3.y, direction.vec3.z]
+    }
+}
+
 impl Transformable for Direction3 {
     fn transform(&self, transform: &Transform) -> Self {
         Direction3 {
@@ -83,7 +200,11 @@ impl Direction3 {
 }
 
 
-#[derive(Debug, Clone, Copy)]
+/// Ray directions shorter than this are treated as having no direction
+const RAY_DIRECTION_EPSILON: f32 = 1e-12;
+
+// Serialized as `{ origin: [x, y, z], direction: [x, y, z] }`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Ray3 {
     pub origin: Point3,
     // Direction must be access through a getter, where it is normalized if necessary
@@ -108,6 +229,27 @@ impl Ray3 {
             self.direction
         }
     }
+
+    /// Whether the ray has no usable direction: near-zero length, or NaN/infinite components.
+    /// Such a ray can't hit anything, and normalizing it would feed NaNs into intersection tests.
+    pub fn is_degenerate(&self) -> bool {
+        let length = self.direction.length();
+        !length.is_finite() || length <= RAY_DIRECTION_EPSILON
+    }
+
+    /// Point at parameter `t` along the ray. `t` is measured along the normalized
+    /// direction, so it is the distance from the origin.
+    pub fn at(&self, t: f32) -> Point3 {
+        Point3 {
+            vec3: self.origin.vec3 + self.direction().vec3 * t
+        }
+    }
+
+    /// Parameter `t` (distance along the normalized direction) of the point on the ray
+    /// closest to `p`. Points behind the origin project to `t = 0`.
+    pub fn project_point(&self, p: Point3) -> f32 {
+        (p - self.origin).vec3.dot(&self.direction().vec3).max(0.0)
+    }
 }
 
 impl Transformable for Ray3 {
@@ -128,7 +270,7 @@ impl Transformable for Ray3 {
 
 
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct HitResponse {
     pub hit_position: Point3,
     pub hit_direction: Direction3,
@@ -159,4 +301,93 @@ pub struct WorldHitResponse {
     pub distance: f32,
     pub object_id: usize,
     pub selection_path: Vec<EdgeId>,  // Path of edge IDs from root to selected element
-}
\ No newline at end of file
+    pub triangle_index: usize,  // Triangle of the model's render mesh that was hit
+    pub normal: Direction3,  // World-space unit surface normal, smoothed if the mesh has vertex normals
+}
+
+
+/// Feature of the hit element a snapping raycast moves the hit point to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapMode {
+    None,
+    Vertex,
+    EdgeMidpoint,
+    FaceCenter,
+}
+
+
+/// Tuning for scene raycasts
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct RaycastOptions {
+    /// Hits closer than this world-space distance are ignored. Raise it when casting
+    /// secondary rays from a hit point so they don't re-hit the surface they start on.
+    pub t_min: f32,
+    /// Object ids (as reported by render instances and hits) the ray passes straight through,
+    /// e.g. the object being dragged when placing it on a surface
+    pub ignore: Vec<usize>,
+}
+
+impl Default for RaycastOptions {
+    fn default() -> Self {
+        RaycastOptions {
+            t_min: f32::EPSILON,
+            ignore: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_at_measures_distance_from_origin() {
+        let ray = Ray3::new(Point3::new(1.0, 2.0, 3.0), Direction3::from([0.0, 0.0, -2.0]));
+
+        let point = ray.at(5.0);
+        assert_eq!(<[f32; 3]>::from(point), [1.0, 2.0, -2.0]);
+        assert!(((point - ray.origin).vec3.length() - 5.0).abs() < 1e-6);
+
+        assert_eq!(ray.project_point(point), 5.0);
+        assert_eq!(ray.project_point(Point3::new(1.0, 2.0, 10.0)), 0.0);
+    }
+
+    #[test]
+    fn ray_round_trips_through_json() {
+        let ray = Ray3::new(Point3::new(1.0, 2.0, 3.0), Direction3::from([0.0, 0.0, -2.0]));
+
+        let json = serde_json::to_string(&ray).unwrap();
+        assert_eq!(json, r#"{"origin":[1.0,2.0,3.0],"direction":[0.0,0.0,-2.0]}"#);
+
+        let loaded: Ray3 = serde_json::from_str(&json).unwrap();
+        assert_eq!(<[f32; 3]>::from(loaded.origin), [1.0, 2.0, 3.0]);
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    }
+
+    #[test]
+    fn frustum_classifies_boxes() {
+        // Looking down -Z from z = 10
+        let projection = glam::Mat4::perspective_rh_gl(1.0, 1.5, 0.1, 100.0);
+        let view = glam::Mat4::look_at_rh(glam::Vec3::new(0.0, 0.0, 10.0), glam::Vec3::ZERO, glam::Vec3::Y);
+        let frustum = Frustum::from_view_proj((projection * view).to_cols_array());
+
+        // Off to the side, and behind the camera
+        for point in [Point3::new(50.0, 0.0, 0.0), Point3::new(0.0, 0.0, 20.0)] {
+            assert_eq!(frustum.contains_aabb(point, point), Intersection::Outside);
+        }
+        assert_eq!(frustum.contains_aabb(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0)), Intersection::Inside);
+        assert_eq!(frustum.contains_aabb(Point3::new(-100.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0)), Intersection::Intersecting);
+    }
+
+    #[test]
+    fn point_round_trips_through_glam() {
+        let glam_vec = glam::Vec3::new(1.5, -2.0, 3.25);
+
+        let point: Point3 = glam_vec.into();
+
+        assert_eq!(<[f32; 3]>::from(point), [1.5, -2.0, 3.25]);
+        assert_eq!(glam::Vec3::from(point), glam_vec);
+    }
+}
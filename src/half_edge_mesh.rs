@@ -1,5 +1,13 @@
-use std::collections::HashMap;
-use crate::{Mesh, ToMesh, geometry::Point3};
+use std::{cmp::Ordering, collections::{BinaryHeap, HashMap, HashSet}, ops::{Add, AddAssign, Mul}};
+use glam::{DMat3, DVec3};
+use crate::{Mesh, QuadMesh, ToMesh, Vec3, algebra::{Bivec3, Dual}, algorithms::triangulate_loop, geometry::Point3};
+
+/// How much more the planes along open borders count than face planes when simplifying,
+/// so borders are among the last edges to move
+const QEM_BOUNDARY_WEIGHT: f64 = 10.0;
+
+/// Elements a long-running operation works through between progress callbacks
+pub const PROGRESS_INTERVAL: usize = 1024;
 
 // Type-safe index wrappers (zero runtime cost)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -37,14 +45,69 @@ pub struct Face {
 
 
 
+/// Element type a scalar attribute channel is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttributeDomain {
+    Vertex,
+    Face,
+}
+
+
+/// Old -> new element indices from `HalfEdgeMesh::compact`, indexed by the old index.
+/// Elements that were dropped map to `None`.
+#[derive(Debug, Clone, Default)]
+pub struct CompactMap {
+    pub vertices: Vec<Option<VertexIndex>>,
+    pub half_edges: Vec<Option<HalfEdgeIndex>>,
+    pub faces: Vec<Option<FaceIndex>>,
+}
+
+impl CompactMap {
+    pub fn vertex(&self, old: VertexIndex) -> Option<VertexIndex> {
+        self.vertices.get(old.0).copied().flatten()
+    }
+
+    pub fn half_edge(&self, old: HalfEdgeIndex) -> Option<HalfEdgeIndex> {
+        self.half_edges.get(old.0).copied().flatten()
+    }
+
+    pub fn face(&self, old: FaceIndex) -> Option<FaceIndex> {
+        self.faces.get(old.0).copied().flatten()
+    }
+}
+
+
 #[derive(Debug, Clone)]
 pub struct HalfEdgeMesh {
     pub vertices: Vec<Vertex>,
     pub half_edges: Vec<HalfEdge>,
     pub faces: Vec<Face>,
+    // Named scalar channels, parallel to `vertices` / `faces`.
+    // Kept in sync by the element-adding helpers below.
+    vertex_attributes: HashMap<String, Vec<f32>>,
+    face_attributes: HashMap<String, Vec<f32>>,
+    // Tombstones: elements removed by editing operations. Their slots stay in the
+    // arrays so other indices remain stable until `compact` reclaims them.
+    removed_vertices: HashSet<VertexIndex>,
+    removed_half_edges: HashSet<HalfEdgeIndex>,
+    removed_faces: HashSet<FaceIndex>,
 }
 
 impl HalfEdgeMesh {
+    /// Assemble a mesh from already-connected elements, with no attribute channels
+    pub fn from_parts(vertices: Vec<Vertex>, half_edges: Vec<HalfEdge>, faces: Vec<Face>) -> Self {
+        HalfEdgeMesh {
+            vertices,
+            half_edges,
+            faces,
+            vertex_attributes: HashMap::new(),
+            face_attributes: HashMap::new(),
+            removed_vertices: HashSet::new(),
+            removed_half_edges: HashSet::new(),
+            removed_faces: HashSet::new(),
+        }
+    }
+
     /// Create a cube half-edge mesh directly with quad faces
     /// 8 vertices, 24 half-edges (4 per face), 6 quad faces
     pub fn create_cube(size: f32) -> Self {
@@ -54,12 +117,12 @@ impl HalfEdgeMesh {
         let vertices = vec![
             Vertex { position: Point3::new(-half, -half, -half), seed_half_edge: Some(HalfEdgeIndex(0)) },  // 0
             Vertex { position: Point3::new( half, -half, -half), seed_half_edge: Some(HalfEdgeIndex(4)) },  // 1
-            Vertex { position: Point3::new( half,  half, -half), seed_half_edge: Some(HalfEdgeIndex(8)) },  // 2
-            Vertex { position: Point3::new(-half,  half, -half), seed_half_edge: Some(HalfEdgeIndex(12)) }, // 3
-            Vertex { position: Point3::new(-half, -half,  half), seed_half_edge: Some(HalfEdgeIndex(16)) }, // 4
-            Vertex { position: Point3::new( half, -half,  half), seed_half_edge: Some(HalfEdgeIndex(20)) }, // 5
-            Vertex { position: Point3::new( half,  half,  half), seed_half_edge: Some(HalfEdgeIndex(5)) },  // 6
-            Vertex { position: Point3::new(-half,  half,  half), seed_half_edge: Some(HalfEdgeIndex(9)) },  // 7
+            Vertex { position: Point3::new( half,  half, -half), seed_half_edge: Some(HalfEdgeIndex(2)) },  // 2
+            Vertex { position: Point3::new(-half,  half, -half), seed_half_edge: Some(HalfEdgeIndex(3)) },  // 3
+            Vertex { position: Point3::new(-half, -half,  half), seed_half_edge: Some(HalfEdgeIndex(9)) },  // 4
+            Vertex { position: Point3::new( half, -half,  half), seed_half_edge: Some(HalfEdgeIndex(5)) },  // 5
+            Vertex { position: Point3::new( half,  half,  half), seed_half_edge: Some(HalfEdgeIndex(6)) },  // 6
+            Vertex { position: Point3::new(-half,  half,  half), seed_half_edge: Some(HalfEdgeIndex(10)) }, // 7
         ];
         
         // 6 quad faces (24 half-edges total, 4 per face)
@@ -74,47 +137,43 @@ impl HalfEdgeMesh {
         
         let half_edges = vec![
             // Face 0: Front face (-Z): 0 -> 1 -> 2 -> 3
-            HalfEdge { target_vertex_index: VertexIndex(1), twin_index: Some(HalfEdgeIndex(7)),  next_edge: HalfEdgeIndex(1),  prev_edge: HalfEdgeIndex(3),  face_index: Some(FaceIndex(0)) }, // 0
-            HalfEdge { target_vertex_index: VertexIndex(2), twin_index: Some(HalfEdgeIndex(11)), next_edge: HalfEdgeIndex(2),  prev_edge: HalfEdgeIndex(0),  face_index: Some(FaceIndex(0)) }, // 1
-            HalfEdge { target_vertex_index: VertexIndex(3), twin_index: Some(HalfEdgeIndex(15)), next_edge: HalfEdgeIndex(3),  prev_edge: HalfEdgeIndex(1),  face_index: Some(FaceIndex(0)) }, // 2
-            HalfEdge { target_vertex_index: VertexIndex(0), twin_index: Some(HalfEdgeIndex(19)), next_edge: HalfEdgeIndex(0),  prev_edge: HalfEdgeIndex(2),  face_index: Some(FaceIndex(0)) }, // 3
+            HalfEdge { target_vertex_index: VertexIndex(1), twin_index: Some(HalfEdgeIndex(19)), next_edge: HalfEdgeIndex(1),  prev_edge: HalfEdgeIndex(3),  face_index: Some(FaceIndex(0)) }, // 0
+            HalfEdge { target_vertex_index: VertexIndex(2), twin_index: Some(HalfEdgeIndex(7)),  next_edge: HalfEdgeIndex(2),  prev_edge: HalfEdgeIndex(0),  face_index: Some(FaceIndex(0)) }, // 1
+            HalfEdge { target_vertex_index: VertexIndex(3), twin_index: Some(HalfEdgeIndex(20)), next_edge: HalfEdgeIndex(3),  prev_edge: HalfEdgeIndex(1),  face_index: Some(FaceIndex(0)) }, // 2
+            HalfEdge { target_vertex_index: VertexIndex(0), twin_index: Some(HalfEdgeIndex(13)), next_edge: HalfEdgeIndex(0),  prev_edge: HalfEdgeIndex(2),  face_index: Some(FaceIndex(0)) }, // 3
             
             // Face 1: Right face (+X): 1 -> 5 -> 6 -> 2
-            HalfEdge { target_vertex_index: VertexIndex(5), twin_index: Some(HalfEdgeIndex(17)), next_edge: HalfEdgeIndex(5),  prev_edge: HalfEdgeIndex(7),  face_index: Some(FaceIndex(1)) }, // 4
-            HalfEdge { target_vertex_index: VertexIndex(6), twin_index: Some(HalfEdgeIndex(21)), next_edge: HalfEdgeIndex(6),  prev_edge: HalfEdgeIndex(4),  face_index: Some(FaceIndex(1)) }, // 5
-            HalfEdge { target_vertex_index: VertexIndex(2), twin_index: Some(HalfEdgeIndex(9)),  next_edge: HalfEdgeIndex(7),  prev_edge: HalfEdgeIndex(5),  face_index: Some(FaceIndex(1)) }, // 6
-            HalfEdge { target_vertex_index: VertexIndex(1), twin_index: Some(HalfEdgeIndex(0)),  next_edge: HalfEdgeIndex(4),  prev_edge: HalfEdgeIndex(6),  face_index: Some(FaceIndex(1)) }, // 7
+            HalfEdge { target_vertex_index: VertexIndex(5), twin_index: Some(HalfEdgeIndex(18)), next_edge: HalfEdgeIndex(5),  prev_edge: HalfEdgeIndex(7),  face_index: Some(FaceIndex(1)) }, // 4
+            HalfEdge { target_vertex_index: VertexIndex(6), twin_index: Some(HalfEdgeIndex(11)), next_edge: HalfEdgeIndex(6),  prev_edge: HalfEdgeIndex(4),  face_index: Some(FaceIndex(1)) }, // 5
+            HalfEdge { target_vertex_index: VertexIndex(2), twin_index: Some(HalfEdgeIndex(21)), next_edge: HalfEdgeIndex(7),  prev_edge: HalfEdgeIndex(5),  face_index: Some(FaceIndex(1)) }, // 6
+            HalfEdge { target_vertex_index: VertexIndex(1), twin_index: Some(HalfEdgeIndex(1)),  next_edge: HalfEdgeIndex(4),  prev_edge: HalfEdgeIndex(6),  face_index: Some(FaceIndex(1)) }, // 7
             
             // Face 2: Back face (+Z): 5 -> 4 -> 7 -> 6
-            HalfEdge { target_vertex_index: VertexIndex(4), twin_index: Some(HalfEdgeIndex(18)), next_edge: HalfEdgeIndex(9),  prev_edge: HalfEdgeIndex(11), face_index: Some(FaceIndex(2)) }, // 8
-            HalfEdge { target_vertex_index: VertexIndex(7), twin_index: Some(HalfEdgeIndex(22)), next_edge: HalfEdgeIndex(10), prev_edge: HalfEdgeIndex(8),  face_index: Some(FaceIndex(2)) }, // 9
-            HalfEdge { target_vertex_index: VertexIndex(6), twin_index: Some(HalfEdgeIndex(6)),  next_edge: HalfEdgeIndex(11), prev_edge: HalfEdgeIndex(9),  face_index: Some(FaceIndex(2)) }, // 10
-            HalfEdge { target_vertex_index: VertexIndex(5), twin_index: Some(HalfEdgeIndex(1)),  next_edge: HalfEdgeIndex(8),  prev_edge: HalfEdgeIndex(10), face_index: Some(FaceIndex(2)) }, // 11
+            HalfEdge { target_vertex_index: VertexIndex(4), twin_index: Some(HalfEdgeIndex(17)), next_edge: HalfEdgeIndex(9),  prev_edge: HalfEdgeIndex(11), face_index: Some(FaceIndex(2)) }, // 8
+            HalfEdge { target_vertex_index: VertexIndex(7), twin_index: Some(HalfEdgeIndex(15)), next_edge: HalfEdgeIndex(10), prev_edge: HalfEdgeIndex(8),  face_index: Some(FaceIndex(2)) }, // 9
+            HalfEdge { target_vertex_index: VertexIndex(6), twin_index: Some(HalfEdgeIndex(22)), next_edge: HalfEdgeIndex(11), prev_edge: HalfEdgeIndex(9),  face_index: Some(FaceIndex(2)) }, // 10
+            HalfEdge { target_vertex_index: VertexIndex(5), twin_index: Some(HalfEdgeIndex(5)),  next_edge: HalfEdgeIndex(8),  prev_edge: HalfEdgeIndex(10), face_index: Some(FaceIndex(2)) }, // 11
             
             // Face 3: Left face (-X): 4 -> 0 -> 3 -> 7
             HalfEdge { target_vertex_index: VertexIndex(0), twin_index: Some(HalfEdgeIndex(16)), next_edge: HalfEdgeIndex(13), prev_edge: HalfEdgeIndex(15), face_index: Some(FaceIndex(3)) }, // 12
-            HalfEdge { target_vertex_index: VertexIndex(3), twin_index: Some(HalfEdgeIndex(23)), next_edge: HalfEdgeIndex(14), prev_edge: HalfEdgeIndex(12), face_index: Some(FaceIndex(3)) }, // 13
-            HalfEdge { target_vertex_index: VertexIndex(7), twin_index: Some(HalfEdgeIndex(10)), next_edge: HalfEdgeIndex(15), prev_edge: HalfEdgeIndex(13), face_index: Some(FaceIndex(3)) }, // 14
-            HalfEdge { target_vertex_index: VertexIndex(4), twin_index: Some(HalfEdgeIndex(2)),  next_edge: HalfEdgeIndex(12), prev_edge: HalfEdgeIndex(14), face_index: Some(FaceIndex(3)) }, // 15
+            HalfEdge { target_vertex_index: VertexIndex(3), twin_index: Some(HalfEdgeIndex(3)),  next_edge: HalfEdgeIndex(14), prev_edge: HalfEdgeIndex(12), face_index: Some(FaceIndex(3)) }, // 13
+            HalfEdge { target_vertex_index: VertexIndex(7), twin_index: Some(HalfEdgeIndex(23)), next_edge: HalfEdgeIndex(15), prev_edge: HalfEdgeIndex(13), face_index: Some(FaceIndex(3)) }, // 14
+            HalfEdge { target_vertex_index: VertexIndex(4), twin_index: Some(HalfEdgeIndex(9)),  next_edge: HalfEdgeIndex(12), prev_edge: HalfEdgeIndex(14), face_index: Some(FaceIndex(3)) }, // 15
             
             // Face 4: Bottom face (-Y): 0 -> 4 -> 5 -> 1
             HalfEdge { target_vertex_index: VertexIndex(4), twin_index: Some(HalfEdgeIndex(12)), next_edge: HalfEdgeIndex(17), prev_edge: HalfEdgeIndex(19), face_index: Some(FaceIndex(4)) }, // 16
-            HalfEdge { target_vertex_index: VertexIndex(5), twin_index: Some(HalfEdgeIndex(4)),  next_edge: HalfEdgeIndex(18), prev_edge: HalfEdgeIndex(16), face_index: Some(FaceIndex(4)) }, // 17
-            HalfEdge { target_vertex_index: VertexIndex(1), twin_index: Some(HalfEdgeIndex(8)),  next_edge: HalfEdgeIndex(19), prev_edge: HalfEdgeIndex(17), face_index: Some(FaceIndex(4)) }, // 18
-            HalfEdge { target_vertex_index: VertexIndex(0), twin_index: Some(HalfEdgeIndex(3)),  next_edge: HalfEdgeIndex(16), prev_edge: HalfEdgeIndex(18), face_index: Some(FaceIndex(4)) }, // 19
+            HalfEdge { target_vertex_index: VertexIndex(5), twin_index: Some(HalfEdgeIndex(8)),  next_edge: HalfEdgeIndex(18), prev_edge: HalfEdgeIndex(16), face_index: Some(FaceIndex(4)) }, // 17
+            HalfEdge { target_vertex_index: VertexIndex(1), twin_index: Some(HalfEdgeIndex(4)),  next_edge: HalfEdgeIndex(19), prev_edge: HalfEdgeIndex(17), face_index: Some(FaceIndex(4)) }, // 18
+            HalfEdge { target_vertex_index: VertexIndex(0), twin_index: Some(HalfEdgeIndex(0)),  next_edge: HalfEdgeIndex(16), prev_edge: HalfEdgeIndex(18), face_index: Some(FaceIndex(4)) }, // 19
             
             // Face 5: Top face (+Y): 3 -> 2 -> 6 -> 7
-            HalfEdge { target_vertex_index: VertexIndex(2), twin_index: Some(HalfEdgeIndex(14)), next_edge: HalfEdgeIndex(21), prev_edge: HalfEdgeIndex(23), face_index: Some(FaceIndex(5)) }, // 20
-            HalfEdge { target_vertex_index: VertexIndex(6), twin_index: Some(HalfEdgeIndex(5)),  next_edge: HalfEdgeIndex(22), prev_edge: HalfEdgeIndex(20), face_index: Some(FaceIndex(5)) }, // 21
-            HalfEdge { target_vertex_index: VertexIndex(7), twin_index: Some(HalfEdgeIndex(9)),  next_edge: HalfEdgeIndex(23), prev_edge: HalfEdgeIndex(21), face_index: Some(FaceIndex(5)) }, // 22
-            HalfEdge { target_vertex_index: VertexIndex(3), twin_index: Some(HalfEdgeIndex(13)), next_edge: HalfEdgeIndex(20), prev_edge: HalfEdgeIndex(22), face_index: Some(FaceIndex(5)) }, // 23
+            HalfEdge { target_vertex_index: VertexIndex(2), twin_index: Some(HalfEdgeIndex(2)),  next_edge: HalfEdgeIndex(21), prev_edge: HalfEdgeIndex(23), face_index: Some(FaceIndex(5)) }, // 20
+            HalfEdge { target_vertex_index: VertexIndex(6), twin_index: Some(HalfEdgeIndex(6)),  next_edge: HalfEdgeIndex(22), prev_edge: HalfEdgeIndex(20), face_index: Some(FaceIndex(5)) }, // 21
+            HalfEdge { target_vertex_index: VertexIndex(7), twin_index: Some(HalfEdgeIndex(10)), next_edge: HalfEdgeIndex(23), prev_edge: HalfEdgeIndex(21), face_index: Some(FaceIndex(5)) }, // 22
+            HalfEdge { target_vertex_index: VertexIndex(3), twin_index: Some(HalfEdgeIndex(14)), next_edge: HalfEdgeIndex(20), prev_edge: HalfEdgeIndex(22), face_index: Some(FaceIndex(5)) }, // 23
         ];
         
-        HalfEdgeMesh {
-            vertices,
-            half_edges,
-            faces,
-        }
+        HalfEdgeMesh::from_parts(vertices, half_edges, faces)
     }
 
     // Create plane
@@ -147,11 +206,134 @@ impl HalfEdgeMesh {
             HalfEdge { target_vertex_index: VertexIndex(0), twin_index: None, next_edge: HalfEdgeIndex(0), prev_edge: HalfEdgeIndex(2), face_index: Some(FaceIndex(0)) }, // 3
         ];
         
-        HalfEdgeMesh {
-            vertices,
-            half_edges,
-            faces,
+        HalfEdgeMesh::from_parts(vertices, half_edges, faces)
+    }
+
+    /// Fan-triangulate a face into render indices, in the order `to_mesh` emits them.
+    /// Removed faces produce no triangles.
+    fn face_fan_indices(&self, face_index: FaceIndex) -> Vec<u32> {
+        if self.is_face_removed(face_index) {
+            return Vec::new();
+        }
+        let face = self.face(face_index);
+
+        // TODO: We know that a face will have at least 3 vertices. But,
+        //       maybe we can imprive efficiency if we know capacity beforehand
+        let mut indices = Vec::with_capacity(3);
+
+        // Have triangular faces made from a single source vertex on the face
+        
+        // The first half-edge simply points to our source vertexs
+        let pointing_half_edge = self.half_edge(face.seed_half_edge);
+        // Source vertex
+        let source_vertex_index = pointing_half_edge.target_vertex_index;
+
+        // The next half edge points to the first half-edge in our sequence of half-edges
+        // which represent the exterior edges of the sequence of triangles that make
+        // up the face. We skip this half-edge.
+        let mut current_half_edge_index = pointing_half_edge.next_edge;
+        let mut prev_vertex_index = self.half_edge(current_half_edge_index).target_vertex_index;
+
+        loop {
+            // Move to the next half edge
+            current_half_edge_index = self.half_edge(current_half_edge_index).next_edge;
+            
+            // Exit if we've looped back to the beginning
+            if current_half_edge_index.0 == face.seed_half_edge.0 {
+                break;
+            }
+
+            // Find next vertex (it won't be source!)
+            let next_vertex_index = self.half_edge(current_half_edge_index).target_vertex_index;
+
+            // Create a triangle with (source, next_vertex, prev_vertex)
+            indices.push(source_vertex_index.0 as u32);
+            indices.push(next_vertex_index.0 as u32);
+            indices.push(prev_vertex_index.0 as u32);
+            
+
+            prev_vertex_index = next_vertex_index;
+        }
+
+        indices
+    }
+
+    /// Create a `width` x `height` grid of square quad faces on the XZ plane (y=0), centered
+    /// at the origin, wound like `create_plane`. Interior edges are twinned; boundary edges
+    /// have no twin.
+    pub fn from_quad_grid(width: usize, height: usize, cell_size: f32) -> Self {
+        let columns = width + 1;
+        let x0 = -(width as f32) * cell_size / 2.0;
+        let z0 = -(height as f32) * cell_size / 2.0;
+
+        let mut vertex_coords = Vec::with_capacity(columns * (height + 1) * 3);
+        for row in 0..=height {
+            for column in 0..=width {
+                vertex_coords.extend_from_slice(&[x0 + column as f32 * cell_size, 0.0, z0 + row as f32 * cell_size]);
+            }
+        }
+
+        let mut polygons = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for column in 0..width {
+                let v00 = (row * columns + column) as u32;
+                let columns = columns as u32;
+                // Same winding as create_plane: +X, then +Z, then back
+                polygons.push(vec![v00, v00 + 1, v00 + columns + 1, v00 + columns]);
+            }
+        }
+
+        HalfEdgeMesh::from_polygons(&vertex_coords, &polygons)
+            .expect("grid polygons only reference grid vertices")
+    }
+
+    /// Build a mesh from flat xyz positions and polygon faces given as vertex loops.
+    /// Face half-edges run along each loop; edges shared by two faces are twinned and
+    /// the rest are left as boundaries with no twin.
+    pub fn from_polygons(vertex_coords: &[f32], polygons: &[Vec<u32>]) -> Result<Self, String> {
+        if !vertex_coords.len().is_multiple_of(3) {
+            return Err("vertex coordinates are not a multiple of 3".to_string());
+        }
+        let mut vertices: Vec<Vertex> = vertex_coords.chunks_exact(3)
+            .map(|coord| Vertex {
+                position: Point3::new(coord[0], coord[1], coord[2]),
+                seed_half_edge: None,
+            })
+            .collect();
+
+        let mut half_edges = Vec::with_capacity(polygons.iter().map(Vec::len).sum());
+        let mut faces = Vec::with_capacity(polygons.len());
+        for (face, corners) in polygons.iter().enumerate() {
+            if corners.len() < 3 {
+                return Err(format!("face {face} has fewer than 3 vertices"));
+            }
+            if let Some(&bad) = corners.iter().find(|&&v| v as usize >= vertices.len()) {
+                return Err(format!("face {face} references missing vertex {bad}"));
+            }
+
+            let n = corners.len();
+            let base = half_edges.len();
+            for k in 0..n {
+                // Half-edge k runs corners[k] -> corners[k + 1]
+                half_edges.push(HalfEdge {
+                    target_vertex_index: VertexIndex(corners[(k + 1) % n] as usize),
+                    twin_index: None,
+                    next_edge: HalfEdgeIndex(base + (k + 1) % n),
+                    prev_edge: HalfEdgeIndex(base + (k + n - 1) % n),
+                    face_index: Some(FaceIndex(face)),
+                });
+                let source = &mut vertices[corners[k] as usize];
+                if source.seed_half_edge.is_none() {
+                    source.seed_half_edge = Some(HalfEdgeIndex(base + k));
+                }
+            }
+
+            faces.push(Face { seed_half_edge: HalfEdgeIndex(base) });
         }
+
+        let mut half_edge_mesh = HalfEdgeMesh::from_parts(vertices, half_edges, faces);
+        half_edge_mesh.rebuild_twins();
+        Ok(half_edge_mesh)
     }
 
     // Creating half edge data structure from mesh
@@ -177,7 +359,9 @@ impl HalfEdgeMesh {
         // Half-edge's twin determined at next step
         // Vertex seeds set here
         for (idx, triangle) in mesh.face_indices.chunks_exact(3).enumerate() {
-            let &[vi0, vi1, vi2] = triangle else {unreachable!()};
+            // Face loops run the opposite way to render triangles (see `face_fan_indices`),
+            // so walk the triangle backwards to keep `to_mesh` winding it the same way
+            let &[vi0, vi2, vi1] = triangle else {unreachable!()};
             let v0 = VertexIndex(vi0 as usize);
             let v1 = VertexIndex(vi1 as usize);
             let v2 = VertexIndex(vi2 as usize);
@@ -240,37 +424,10 @@ impl HalfEdgeMesh {
         }
         
 
-        // Quick exploring and connecting half-edges
-
-        let mut edge_map: HashMap<(VertexIndex, VertexIndex), HalfEdgeIndex> = HashMap::new();
-        
-        // Create half edge map
-        for (half_edge_idx, half_edge) in half_edges.iter().enumerate() {
-            let source = half_edges[half_edge.prev_edge.0].target_vertex_index;
-            let target = half_edge.target_vertex_index;
-
-            edge_map.insert((source, target), HalfEdgeIndex(half_edge_idx));
-        }
-
-        // Collect the twins
-        let twins: Vec<Option<HalfEdgeIndex>> = half_edges.iter().map(
-            |half_edge| {
-                let source = half_edges[half_edge.prev_edge.0].target_vertex_index;
-                let target = half_edge.target_vertex_index;
-
-                edge_map.get(&(target, source)).copied()
-            }
-        ).collect();
-
-        for (half_edge, twin) in half_edges.iter_mut().zip(twins.into_iter()) {
-            half_edge.twin_index = twin;
-        }
-
-        HalfEdgeMesh {
-            vertices,
-            half_edges,
-            faces,
-        }
+        // Connect the twins
+        let mut half_edge_mesh = HalfEdgeMesh::from_parts(vertices, half_edges, faces);
+        half_edge_mesh.rebuild_twins();
+        half_edge_mesh
     }
 
     // Helper methods for safe indexing
@@ -298,100 +455,2398 @@ impl HalfEdgeMesh {
         &mut self.faces[idx.0]
     }
 
-    pub fn vertex_outgoing_half_edges(&self, vertex_idx: VertexIndex) -> Vec<HalfEdgeIndex> {
-        let mut outgoing = Vec::new();
-        
-        if let Some(start_he) = self.vertex(vertex_idx).seed_half_edge {
-            let mut current_he = start_he;
-            
-            loop {
-                outgoing.push(current_he);
-                
-                let he = self.half_edge(current_he);
-                if let Some(twin_he) = he.twin_index {
-                    current_he = self.half_edge(twin_he).next_edge;
-                    
-                    if current_he == start_he {
-                        break;
-                    }
-                } else {
-                    break;
+    /// Source vertex of a half-edge (the target of its predecessor)
+    pub fn half_edge_source(&self, idx: HalfEdgeIndex) -> VertexIndex {
+        self.half_edge(self.half_edge(idx).prev_edge).target_vertex_index
+    }
+
+    /// Recompute every `twin_index` from scratch by pairing each (source, target)
+    /// half-edge with its (target, source) counterpart. Half-edges without a
+    /// counterpart (genuine boundaries) get `None`, clearing any stale links.
+    pub fn rebuild_twins(&mut self) {
+        let mut edge_map: HashMap<(VertexIndex, VertexIndex), HalfEdgeIndex> = HashMap::with_capacity(self.half_edges.len());
+
+        // Create half edge map
+        for (half_edge_idx, half_edge) in self.half_edges.iter().enumerate() {
+            if self.is_half_edge_removed(HalfEdgeIndex(half_edge_idx)) {
+                continue;
+            }
+            let source = self.half_edge_source(HalfEdgeIndex(half_edge_idx));
+            let target = half_edge.target_vertex_index;
+
+            edge_map.insert((source, target), HalfEdgeIndex(half_edge_idx));
+        }
+
+        // Collect the twins
+        let twins: Vec<Option<HalfEdgeIndex>> = (0..self.half_edges.len()).map(
+            |half_edge_idx| {
+                if self.is_half_edge_removed(HalfEdgeIndex(half_edge_idx)) {
+                    return None;
                 }
+                let source = self.half_edge_source(HalfEdgeIndex(half_edge_idx));
+                let target = self.half_edges[half_edge_idx].target_vertex_index;
+
+                edge_map.get(&(target, source)).copied()
             }
+        ).collect();
+
+        for (half_edge, twin) in self.half_edges.iter_mut().zip(twins) {
+            half_edge.twin_index = twin;
         }
-        
-        outgoing
     }
 
+    /// Face that triangle `triangle` of `to_mesh` was fanned from
+    pub fn triangle_face(&self, triangle: usize) -> Option<FaceIndex> {
+        let mut first_triangle = 0;
+        for face in (0..self.faces.len()).map(FaceIndex) {
+            if self.is_face_removed(face) {
+                continue;
+            }
+            first_triangle += self.loop_half_edges(self.face(face).seed_half_edge).len().saturating_sub(2);
+            if triangle < first_triangle {
+                return Some(face);
+            }
+        }
+        None
+    }
+
+    /// Number of triangles `to_mesh` fans the live faces into, without building the mesh
+    pub fn triangle_count(&self) -> usize {
+        (0..self.faces.len()).map(FaceIndex)
+            .filter(|&face| !self.is_face_removed(face))
+            .map(|face| self.loop_half_edges(self.face(face).seed_half_edge).len().saturating_sub(2))
+            .sum()
+    }
 
-}
+    /// Half-edges around a face, starting at its seed
+    pub fn face_half_edges(&self, face: FaceIndex) -> Vec<HalfEdgeIndex> {
+        self.loop_half_edges(self.face(face).seed_half_edge)
+    }
 
+    /// Half-edges around the loop (face or explicit boundary) containing `start`, beginning there
+    fn loop_half_edges(&self, start: HalfEdgeIndex) -> Vec<HalfEdgeIndex> {
+        let mut loop_half_edges = vec![start];
+        let mut current = self.half_edge(start).next_edge;
+        while current != start {
+            loop_half_edges.push(current);
+            current = self.half_edge(current).next_edge;
+        }
+        loop_half_edges
+    }
 
+    /// Vertices around a face, in loop order starting at the seed half-edge's source
+    pub fn face_vertices(&self, face: FaceIndex) -> Vec<VertexIndex> {
+        self.face_half_edges(face).into_iter()
+            .map(|idx| self.half_edge_source(idx))
+            .collect()
+    }
 
-impl ToMesh for HalfEdgeMesh {
-    fn to_mesh(&self) -> Mesh {
+    /// Whether a half-edge lies on a boundary: either an explicit boundary half-edge
+    /// (no face) or a face half-edge with no twin (implicit boundary)
+    pub fn is_boundary_half_edge(&self, idx: HalfEdgeIndex) -> bool {
+        let he = self.half_edge(idx);
+        he.face_index.is_none() || he.twin_index.is_none()
+    }
 
-        let vertex_coords = 
-        self.vertices.iter().flat_map(
-            |vertex| [
-                vertex.position.vec3.x,
-                vertex.position.vec3.y,
-                vertex.position.vec3.z
-            ]
-        ).collect();
+    /// For a twinless face half-edge, find the next twinless half-edge along the same
+    /// hole by rotating around its target vertex
+    fn next_implicit_boundary(&self, idx: HalfEdgeIndex) -> HalfEdgeIndex {
+        let mut candidate = self.half_edge(idx).next_edge;
+        // Bounded so a corrupt mesh can't loop forever
+        for _ in 0..self.half_edges.len() {
+            match self.half_edge(candidate).twin_index {
+                Some(twin) => candidate = self.half_edge(twin).next_edge,
+                None => break,
+            }
+        }
+        candidate
+    }
 
-        let face_indices = self.faces.iter().flat_map(
-            |face| {
+    /// Give every twinless face half-edge an explicit twin with no face, linked into
+    /// boundary loops via `next_edge`/`prev_edge`. Meshes built by `from_mesh` or
+    /// `create_plane` leave boundaries implicit; editing operations that open holes
+    /// need them explicit. Does nothing on closed meshes.
+    pub fn add_boundary_half_edges(&mut self) {
+        let twinless: Vec<HalfEdgeIndex> = (0..self.half_edges.len())
+            .map(HalfEdgeIndex)
+            .filter(|&idx| {
+                !self.is_half_edge_removed(idx)
+                    && self.half_edge(idx).face_index.is_some()
+                    && self.half_edge(idx).twin_index.is_none()
+            })
+            .collect();
 
-                // TODO: We know that a face will have at least 3 vertices. But,
-                //       maybe we can imprive efficiency if we know capacity beforehand
-                let mut indices = Vec::with_capacity(3);
+        // Successor along each hole, found before any twins are added
+        let successors: Vec<HalfEdgeIndex> = twinless.iter().map(|&idx| self.next_implicit_boundary(idx)).collect();
 
-                // Have triangular faces made from a single source vertex on the face
-                
-                // The first half-edge simply points to our source vertexs
-                let pointing_half_edge = self.half_edge(face.seed_half_edge);
-                // Source vertex
-                let source_vertex_index = pointing_half_edge.target_vertex_index;
-
-                // The next half edge points to the first half-edge in our sequence of half-edges
-                // which represent the exterior edges of the sequence of triangles that make
-                // up the face. We skip this half-edge.
-                let mut current_half_edge_index = pointing_half_edge.next_edge;
-                let mut prev_vertex_index = self.half_edge(current_half_edge_index).target_vertex_index;
-
-                loop {
-                    // Move to the next half edge
-                    current_half_edge_index = self.half_edge(current_half_edge_index).next_edge;
-                    
-                    // Exit if we've looped back to the beginning
-                    if current_half_edge_index.0 == face.seed_half_edge.0 {
-                        break;
-                    }
+        // Create the boundary twins (links filled in below)
+        for &idx in &twinless {
+            let boundary = HalfEdgeIndex(self.half_edges.len());
+            self.half_edges.push(HalfEdge {
+                target_vertex_index: self.half_edge_source(idx),
+                twin_index: Some(idx),
+                next_edge: boundary,
+                prev_edge: boundary,
+                face_index: None,
+            });
+            self.half_edge_mut(idx).twin_index = Some(boundary);
+        }
 
-                    // Find next vertex (it won't be source!)
-                    let next_vertex_index = self.half_edge(current_half_edge_index).target_vertex_index;
+        // The boundary loop runs against the face winding: for consecutive hole edges
+        // h -> n, twin(n) is followed by twin(h)
+        for (&idx, &successor) in twinless.iter().zip(&successors) {
+            let boundary = self.half_edge(idx).twin_index.unwrap();
+            let successor_boundary = self.half_edge(successor).twin_index.unwrap();
+            self.half_edge_mut(successor_boundary).next_edge = boundary;
+            self.half_edge_mut(boundary).prev_edge = successor_boundary;
+        }
+    }
 
-                    // Create a triangle with (source, next_vertex, prev_vertex)
-                    indices.push(source_vertex_index.0 as u32);
-                    indices.push(next_vertex_index.0 as u32);
-                    indices.push(prev_vertex_index.0 as u32);
-                    
+    /// Every boundary loop as a list of half-edges. Explicit boundary half-edges (no face)
+    /// are listed following `next_edge`; implicit boundaries (twinless face half-edges)
+    /// are listed as those face half-edges, in face-winding order.
+    pub fn boundary_loops(&self) -> Vec<Vec<HalfEdgeIndex>> {
+        let mut visited = HashSet::new();
+        let mut loops = Vec::new();
 
-                    prev_vertex_index = next_vertex_index;
+        for start in (0..self.half_edges.len()).map(HalfEdgeIndex) {
+            if self.is_half_edge_removed(start) || !self.is_boundary_half_edge(start) || visited.contains(&start) {
+                continue;
+            }
+
+            let explicit = self.half_edge(start).face_index.is_none();
+            let mut boundary_loop = Vec::new();
+            let mut current = start;
+            while visited.insert(current) {
+                boundary_loop.push(current);
+                current = if explicit {
+                    self.half_edge(current).next_edge
+                } else {
+                    self.next_implicit_boundary(current)
+                };
+            }
+            loops.push(boundary_loop);
+        }
+
+        loops
+    }
+
+    /// Whether the mesh has no boundary: every live half-edge has a twin and a face
+    pub fn is_closed(&self) -> bool {
+        (0..self.half_edges.len())
+            .map(HalfEdgeIndex)
+            .all(|idx| self.is_half_edge_removed(idx) || !self.is_boundary_half_edge(idx))
+    }
+
+    /// Close the hole bounded by the boundary loop through `loop_start`, which may be a
+    /// boundary half-edge, a twinless face half-edge, or a face half-edge whose twin is on
+    /// the boundary. The hole is triangulated like an imported polygon (fanned when convex,
+    /// ear-clipped on its best-fit plane otherwise) and the new faces take over the loop's
+    /// boundary half-edges, so they are twinned with, and wind like, the faces around the
+    /// hole. Returns the new faces; none if `loop_start` isn't on a boundary or the loop has
+    /// fewer than 3 edges.
+    pub fn fill_hole(&mut self, loop_start: HalfEdgeIndex) -> Vec<FaceIndex> {
+        if loop_start.0 >= self.half_edges.len() || self.is_half_edge_removed(loop_start) {
+            return Vec::new();
+        }
+        let he = self.half_edge(loop_start);
+        let start = match (he.face_index, he.twin_index) {
+            (None, _) => loop_start,
+            (Some(_), Some(twin)) if self.half_edge(twin).face_index.is_none() => twin,
+            (Some(_), None) => {
+                // Implicit boundary: make it explicit so the loop has half-edges to reuse
+                self.add_boundary_half_edges();
+                self.half_edge(loop_start).twin_index.unwrap()
+            }
+            (Some(_), Some(_)) => return Vec::new(),
+        };
+
+        // The boundary loop (bounded so a corrupt mesh can't loop forever)
+        let mut rim = vec![start];
+        let mut current = self.half_edge(start).next_edge;
+        while current != start {
+            if rim.len() > self.half_edges.len() {
+                return Vec::new();
+            }
+            rim.push(current);
+            current = self.half_edge(current).next_edge;
+        }
+        if rim.len() < 3 {
+            return Vec::new();
+        }
+        self.fill_loop(&rim, None)
+    }
+
+    /// Triangulate a single face in place, like `fill_hole` triangulates a hole: the original
+    /// face keeps the first triangle and the others are added as new faces copying its
+    /// attributes. The face's half-edges are reused, so its neighbours and the rest of the
+    /// mesh are untouched. Returns the faces now covering it, the original first; just the
+    /// face itself if it already is a triangle, and none for removed faces or a failed
+    /// triangulation (leaving the face as it was).
+    pub fn triangulate_face(&mut self, face: FaceIndex) -> Vec<FaceIndex> {
+        if face.0 >= self.faces.len() || self.is_face_removed(face) {
+            return Vec::new();
+        }
+        let rim = self.face_half_edges(face);
+        if rim.len() <= 3 {
+            return vec![face];
+        }
+        self.fill_loop(&rim, Some(face))
+    }
+
+    /// Triangulate the corners of a closed loop of half-edges and turn it into triangle faces,
+    /// reusing the loop's half-edges for their sides and adding twinned half-edges for the
+    /// diagonals. With `reuse`, that face is kept for the first triangle and the new faces copy
+    /// its attributes; nothing changes then unless the triangulation covers the whole loop.
+    fn fill_loop(&mut self, rim: &[HalfEdgeIndex], reuse: Option<FaceIndex>) -> Vec<FaceIndex> {
+        let n = rim.len();
+
+        // Triangulate the loop's corners; rim[i] runs from corner i to corner i + 1
+        let corners: Vec<VertexIndex> = rim.iter().map(|&idx| self.half_edge_source(idx)).collect();
+        let coords: Vec<f32> = corners.iter()
+            .flat_map(|&corner| {
+                let p = self.vertex(corner).position.vec3;
+                [p.x, p.y, p.z]
+            })
+            .collect();
+        let polygon: Vec<u32> = (0..n as u32).collect();
+
+        let triangles = triangulate_loop(&coords, &polygon);
+        if reuse.is_some() && triangles.len() != n - 2 {
+            return Vec::new();
+        }
+
+        // Diagonals are shared by two triangles, in opposite directions
+        let mut diagonals: HashMap<(u32, u32), HalfEdgeIndex> = HashMap::new();
+        let mut new_faces = Vec::new();
+        for triangle in triangles {
+            let sides = [0, 1, 2].map(|k| {
+                let (from, to) = (triangle[k], triangle[(k + 1) % 3]);
+                if to == (from + 1) % n as u32 {
+                    return rim[from as usize];
                 }
+                let idx = HalfEdgeIndex(self.half_edges.len());
+                let twin = diagonals.get(&(to, from)).copied();
+                self.half_edges.push(HalfEdge {
+                    target_vertex_index: corners[to as usize],
+                    twin_index: twin,
+                    next_edge: idx,
+                    prev_edge: idx,
+                    face_index: None,
+                });
+                if let Some(twin) = twin {
+                    self.half_edge_mut(twin).twin_index = Some(idx);
+                }
+                diagonals.insert((from, to), idx);
+                idx
+            });
 
-                indices
+            let face = match reuse {
+                Some(original) if new_faces.is_empty() => {
+                    self.face_mut(original).seed_half_edge = sides[0];
+                    original
+                }
+                Some(original) => {
+                    let face = self.add_face(sides[0]);
+                    for values in self.face_attributes.values_mut() {
+                        values[face.0] = values[original.0];
+                    }
+                    face
+                }
+                None => self.add_face(sides[0]),
+            };
+            for k in 0..3 {
+                let side = self.half_edge_mut(sides[k]);
+                side.next_edge = sides[(k + 1) % 3];
+                side.prev_edge = sides[(k + 2) % 3];
+                side.face_index = Some(face);
             }
-        ).collect();
+            new_faces.push(face);
+        }
 
-        // TODO: potentially fill in normals from the half-edge mesh
-        let normals = None;
-        
-        Mesh {
-            vertex_coords: vertex_coords,
-            face_indices: face_indices,
-            normals: normals,
+        new_faces
+    }
+
+    /// Delete a face, opening a hole. The face's half-edges become boundary half-edges
+    /// (no face) linked into boundary loops; edges left with no face on either side are
+    /// removed entirely. Vertices left without any edge keep a `None` seed and stay in
+    /// place (isolated) until `compact`. The face slot is tombstoned.
+    pub fn delete_face(&mut self, face: FaceIndex) {
+        if self.is_face_removed(face) {
+            return;
+        }
+
+        self.add_boundary_half_edges();
+
+        let face_loop = self.face_half_edges(face);
+        for &idx in &face_loop {
+            self.half_edge_mut(idx).face_index = None;
+        }
+        self.mark_face_removed(face);
+
+        // Edges with no face on either side no longer bound anything
+        for &idx in &face_loop {
+            let Some(twin) = self.half_edge(idx).twin_index else { continue };
+            if self.half_edge(twin).face_index.is_none() && !self.is_half_edge_removed(idx) {
+                self.remove_edge_pair(idx, twin);
+            }
+        }
+    }
+
+    /// Unlink and tombstone a pair of faceless twin half-edges, splicing the boundary
+    /// loops on either side together and re-seeding their endpoints
+    fn remove_edge_pair(&mut self, idx: HalfEdgeIndex, twin: HalfEdgeIndex) {
+        let source = self.half_edge_source(idx);
+        let target = self.half_edge(idx).target_vertex_index;
+        let (prev, next) = (self.half_edge(idx).prev_edge, self.half_edge(idx).next_edge);
+        let (twin_prev, twin_next) = (self.half_edge(twin).prev_edge, self.half_edge(twin).next_edge);
+
+        // prev ends where twin_next starts (the source), twin_prev ends where next starts (the target)
+        self.half_edge_mut(prev).next_edge = twin_next;
+        self.half_edge_mut(twin_next).prev_edge = prev;
+        self.half_edge_mut(twin_prev).next_edge = next;
+        self.half_edge_mut(next).prev_edge = twin_prev;
+
+        if self.vertex(source).seed_half_edge == Some(idx) {
+            self.vertex_mut(source).seed_half_edge = (twin_next != idx).then_some(twin_next);
+        }
+        if self.vertex(target).seed_half_edge == Some(twin) {
+            self.vertex_mut(target).seed_half_edge = (next != twin).then_some(next);
+        }
+
+        self.mark_half_edge_removed(idx);
+        self.mark_half_edge_removed(twin);
+    }
+
+    /// Append an unconnected vertex, extending every vertex attribute channel with 0.0
+    pub fn add_vertex(&mut self, position: Point3) -> VertexIndex {
+        self.vertices.push(Vertex { position, seed_half_edge: None });
+        for values in self.vertex_attributes.values_mut() {
+            values.push(0.0);
         }
+        VertexIndex(self.vertices.len() - 1)
+    }
+
+    /// Append a face seeded at the given half-edge, extending every face attribute channel with 0.0
+    pub fn add_face(&mut self, seed_half_edge: HalfEdgeIndex) -> FaceIndex {
+        self.faces.push(Face { seed_half_edge });
+        for values in self.face_attributes.values_mut() {
+            values.push(0.0);
+        }
+        FaceIndex(self.faces.len() - 1)
+    }
+
+    /// Approximate heap size of the connectivity and attribute channels in bytes
+    pub fn memory_estimate(&self) -> usize {
+        let attribute_bytes: usize = self.vertex_attributes.iter()
+            .chain(self.face_attributes.iter())
+            .map(|(name, values)| name.len() + values.len() * std::mem::size_of::<f32>())
+            .sum();
+
+        self.vertices.len() * std::mem::size_of::<Vertex>()
+            + self.half_edges.len() * std::mem::size_of::<HalfEdge>()
+            + self.faces.len() * std::mem::size_of::<Face>()
+            + attribute_bytes
+    }
+
+    // Tombstones
+
+    /// Mark a vertex as removed. Its slot is reclaimed by `compact`.
+    pub fn mark_vertex_removed(&mut self, idx: VertexIndex) {
+        self.removed_vertices.insert(idx);
+    }
+
+    /// Mark a half-edge as removed. Its slot is reclaimed by `compact`.
+    pub fn mark_half_edge_removed(&mut self, idx: HalfEdgeIndex) {
+        self.removed_half_edges.insert(idx);
+    }
+
+    /// Mark a face as removed. Its slot is reclaimed by `compact`.
+    pub fn mark_face_removed(&mut self, idx: FaceIndex) {
+        self.removed_faces.insert(idx);
+    }
+
+    pub fn is_vertex_removed(&self, idx: VertexIndex) -> bool {
+        self.removed_vertices.contains(&idx)
+    }
+
+    pub fn is_half_edge_removed(&self, idx: HalfEdgeIndex) -> bool {
+        self.removed_half_edges.contains(&idx)
+    }
+
+    pub fn is_face_removed(&self, idx: FaceIndex) -> bool {
+        self.removed_faces.contains(&idx)
+    }
+
+    /// Whether any tombstoned elements are waiting to be compacted away
+    pub fn has_removed_elements(&self) -> bool {
+        !(self.removed_vertices.is_empty() && self.removed_half_edges.is_empty() && self.removed_faces.is_empty())
     }
-}
\ No newline at end of file
+
+    /// Check the connectivity invariants: next/prev links are mutual, twins are mutual and
+    /// run the opposite way, loops share one face, seeds point at live half-edges of their own
+    /// vertex or face, faces have at least three sides, and no directed edge appears twice.
+    /// Tombstoned elements are skipped. Returns a description of the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        let live_half_edge = |idx: HalfEdgeIndex| idx.0 < self.half_edges.len() && !self.is_half_edge_removed(idx);
+        let mut directed_edges = HashSet::new();
+
+        for idx in (0..self.half_edges.len()).map(HalfEdgeIndex) {
+            if self.is_half_edge_removed(idx) {
+                continue;
+            }
+            let he = self.half_edge(idx);
+            if !live_half_edge(he.next_edge) || self.half_edge(he.next_edge).prev_edge != idx {
+                return Err(format!("Half-edge {}: next edge {} does not link back", idx.0, he.next_edge.0));
+            }
+            if !live_half_edge(he.prev_edge) || self.half_edge(he.prev_edge).next_edge != idx {
+                return Err(format!("Half-edge {}: prev edge {} does not link back", idx.0, he.prev_edge.0));
+            }
+            if he.target_vertex_index.0 >= self.vertices.len() || self.is_vertex_removed(he.target_vertex_index) {
+                return Err(format!("Half-edge {} targets missing vertex {}", idx.0, he.target_vertex_index.0));
+            }
+            if self.half_edge(he.next_edge).face_index != he.face_index {
+                return Err(format!("Half-edge {} and its next edge belong to different faces", idx.0));
+            }
+            if let Some(face) = he.face_index {
+                if face.0 >= self.faces.len() || self.is_face_removed(face) {
+                    return Err(format!("Half-edge {} belongs to missing face {}", idx.0, face.0));
+                }
+            }
+
+            let source = self.half_edge_source(idx);
+            if source == he.target_vertex_index {
+                return Err(format!("Half-edge {} starts and ends at vertex {}", idx.0, source.0));
+            }
+            match he.twin_index {
+                Some(twin) => {
+                    if !live_half_edge(twin) || self.half_edge(twin).twin_index != Some(idx) {
+                        return Err(format!("Half-edge {}: twin {} does not link back", idx.0, twin.0));
+                    }
+                    if self.half_edge(twin).target_vertex_index != source {
+                        return Err(format!("Half-edge {}: twin {} does not run the opposite way", idx.0, twin.0));
+                    }
+                }
+                None if he.face_index.is_none() => {
+                    return Err(format!("Half-edge {} has neither a face nor a twin", idx.0));
+                }
+                None => {}
+            }
+            if !directed_edges.insert((source, he.target_vertex_index)) {
+                return Err(format!("Edge {} -> {} appears more than once", source.0, he.target_vertex_index.0));
+            }
+        }
+
+        for face in (0..self.faces.len()).map(FaceIndex) {
+            if self.is_face_removed(face) {
+                continue;
+            }
+            let seed = self.face(face).seed_half_edge;
+            if !live_half_edge(seed) || self.half_edge(seed).face_index != Some(face) {
+                return Err(format!("Face {} is seeded at a half-edge outside it", face.0));
+            }
+            if self.face_half_edges(face).len() < 3 {
+                return Err(format!("Face {} has fewer than three sides", face.0));
+            }
+        }
+
+        for vertex in (0..self.vertices.len()).map(VertexIndex) {
+            if self.is_vertex_removed(vertex) {
+                continue;
+            }
+            if let Some(seed) = self.vertex(vertex).seed_half_edge {
+                if !live_half_edge(seed) || self.half_edge_source(seed) != vertex {
+                    return Err(format!("Vertex {} is seeded at a half-edge that does not leave it", vertex.0));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Euler characteristic V - E + F over the live elements. Each undirected edge counts
+    /// once, whether it has a twin, an explicit boundary twin or no twin at all.
+    pub fn euler_characteristic(&self) -> i32 {
+        let vertices = (0..self.vertices.len()).map(VertexIndex)
+            .filter(|&vertex| !self.is_vertex_removed(vertex))
+            .count();
+        let edges = (0..self.half_edges.len()).map(HalfEdgeIndex)
+            .filter(|&idx| !self.is_half_edge_removed(idx))
+            .filter(|&idx| match self.half_edge(idx).twin_index {
+                Some(twin) => idx.0 < twin.0,
+                None => true,
+            })
+            .count();
+        let faces = (0..self.faces.len()).map(FaceIndex)
+            .filter(|&face| !self.is_face_removed(face))
+            .count();
+        vertices as i32 - edges as i32 + faces as i32
+    }
+
+    /// Genus of a closed, connected surface, from χ = 2 - 2g. Half-edge meshes are always
+    /// orientable, so this is `None` only when the mesh has a boundary or χ is odd.
+    pub fn genus(&self) -> Option<i32> {
+        let has_boundary = (0..self.half_edges.len()).map(HalfEdgeIndex)
+            .any(|idx| !self.is_half_edge_removed(idx) && self.is_boundary_half_edge(idx));
+        let chi = self.euler_characteristic();
+        if has_boundary || chi % 2 != 0 {
+            return None;
+        }
+        Some((2 - chi) / 2)
+    }
+
+    /// Drop all tombstoned vertices, half-edges and faces, along with vertices no live
+    /// half-edge reaches (left isolated by deletions), and remap every index (targets, twins,
+    /// next/prev, faces, seeds) into a dense range, keeping the relative order of the surviving
+    /// elements. Attribute channels are compacted too. Returns the old -> new index mapping so
+    /// callers can fix up indices they hold, such as selections.
+    ///
+    /// Live elements must not reference removed `next`/`prev`/target/seed elements;
+    /// references to removed twins and faces become `None`. Vertex seeds that pointed
+    /// at a removed half-edge are re-seeded from a surviving outgoing half-edge.
+    pub fn compact(&mut self) -> CompactMap {
+        let mut reached = vec![false; self.vertices.len()];
+        for (idx, half_edge) in self.half_edges.iter().enumerate() {
+            if !self.is_half_edge_removed(HalfEdgeIndex(idx)) {
+                reached[half_edge.target_vertex_index.0] = true;
+            }
+        }
+
+        // Old index -> new index, None for removed elements
+        fn dense_map(len: usize, is_removed: impl Fn(usize) -> bool) -> Vec<Option<usize>> {
+            let mut next = 0;
+            (0..len).map(|i| {
+                if is_removed(i) {
+                    None
+                } else {
+                    next += 1;
+                    Some(next - 1)
+                }
+            }).collect()
+        }
+
+        let vertex_map = dense_map(self.vertices.len(), |i| !reached[i] || self.is_vertex_removed(VertexIndex(i)));
+        let half_edge_map = dense_map(self.half_edges.len(), |i| self.is_half_edge_removed(HalfEdgeIndex(i)));
+        let face_map = dense_map(self.faces.len(), |i| self.is_face_removed(FaceIndex(i)));
+        let compact_map = CompactMap {
+            vertices: vertex_map.iter().map(|v| v.map(VertexIndex)).collect(),
+            half_edges: half_edge_map.iter().map(|he| he.map(HalfEdgeIndex)).collect(),
+            faces: face_map.iter().map(|f| f.map(FaceIndex)).collect(),
+        };
+        if !self.has_removed_elements() && reached.iter().all(|&r| r) {
+            return compact_map;
+        }
+
+        let remap_vertex = |v: VertexIndex| VertexIndex(vertex_map[v.0].expect("live half-edge targets a removed vertex"));
+        let remap_half_edge = |he: HalfEdgeIndex| half_edge_map[he.0].map(HalfEdgeIndex);
+        let remap_face = |f: FaceIndex| face_map[f.0].map(FaceIndex);
+
+        let half_edges: Vec<HalfEdge> = self.half_edges.iter().enumerate()
+            .filter(|(i, _)| half_edge_map[*i].is_some())
+            .map(|(_, he)| HalfEdge {
+                target_vertex_index: remap_vertex(he.target_vertex_index),
+                twin_index: he.twin_index.and_then(remap_half_edge),
+                next_edge: remap_half_edge(he.next_edge).expect("live half-edge links to a removed next edge"),
+                prev_edge: remap_half_edge(he.prev_edge).expect("live half-edge links to a removed prev edge"),
+                face_index: he.face_index.and_then(remap_face),
+            })
+            .collect();
+
+        let faces: Vec<Face> = self.faces.iter().enumerate()
+            .filter(|(i, _)| face_map[*i].is_some())
+            .map(|(_, face)| Face {
+                seed_half_edge: remap_half_edge(face.seed_half_edge).expect("live face is seeded at a removed half-edge"),
+            })
+            .collect();
+
+        let mut vertices: Vec<Vertex> = self.vertices.iter().enumerate()
+            .filter(|(i, _)| vertex_map[*i].is_some())
+            .map(|(_, vertex)| Vertex {
+                position: vertex.position,
+                seed_half_edge: vertex.seed_half_edge.and_then(remap_half_edge),
+            })
+            .collect();
+
+        // Re-seed vertices whose seed half-edge was removed
+        for (idx, half_edge) in half_edges.iter().enumerate() {
+            let source = half_edges[half_edge.prev_edge.0].target_vertex_index;
+            let vertex = &mut vertices[source.0];
+            if vertex.seed_half_edge.is_none() {
+                vertex.seed_half_edge = Some(HalfEdgeIndex(idx));
+            }
+        }
+
+        for values in self.vertex_attributes.values_mut() {
+            let mut i = 0;
+            values.retain(|_| { i += 1; vertex_map[i - 1].is_some() });
+        }
+        for values in self.face_attributes.values_mut() {
+            let mut i = 0;
+            values.retain(|_| { i += 1; face_map[i - 1].is_some() });
+        }
+
+        self.vertices = vertices;
+        self.half_edges = half_edges;
+        self.faces = faces;
+        self.removed_vertices.clear();
+        self.removed_half_edges.clear();
+        self.removed_faces.clear();
+
+        compact_map
+    }
+
+    // Attribute channels
+
+    fn attribute_channels(&self, domain: AttributeDomain) -> &HashMap<String, Vec<f32>> {
+        match domain {
+            AttributeDomain::Vertex => &self.vertex_attributes,
+            AttributeDomain::Face => &self.face_attributes,
+        }
+    }
+
+    fn attribute_channels_mut(&mut self, domain: AttributeDomain) -> &mut HashMap<String, Vec<f32>> {
+        match domain {
+            AttributeDomain::Vertex => &mut self.vertex_attributes,
+            AttributeDomain::Face => &mut self.face_attributes,
+        }
+    }
+
+    /// Create (or reset) a named channel with one value per element, all set to `default`
+    pub fn add_attribute(&mut self, domain: AttributeDomain, name: &str, default: f32) {
+        let len = match domain {
+            AttributeDomain::Vertex => self.vertices.len(),
+            AttributeDomain::Face => self.faces.len(),
+        };
+        self.attribute_channels_mut(domain).insert(name.to_string(), vec![default; len]);
+    }
+
+    /// Remove a channel, returning its values if it existed
+    pub fn remove_attribute(&mut self, domain: AttributeDomain, name: &str) -> Option<Vec<f32>> {
+        self.attribute_channels_mut(domain).remove(name)
+    }
+
+    /// Values of a channel, indexed by `VertexIndex.0` / `FaceIndex.0`
+    pub fn attribute(&self, domain: AttributeDomain, name: &str) -> Option<&[f32]> {
+        self.attribute_channels(domain).get(name).map(|values| values.as_slice())
+    }
+
+    /// Mutable values of a channel. A slice, so the channel length can't drift from the element count.
+    pub fn attribute_mut(&mut self, domain: AttributeDomain, name: &str) -> Option<&mut [f32]> {
+        self.attribute_channels_mut(domain).get_mut(name).map(|values| values.as_mut_slice())
+    }
+
+    /// Names of all channels in a domain
+    pub fn attribute_names(&self, domain: AttributeDomain) -> Vec<&str> {
+        self.attribute_channels(domain).keys().map(|name| name.as_str()).collect()
+    }
+
+    pub fn vertex_outgoing_half_edges(&self, vertex_idx: VertexIndex) -> Vec<HalfEdgeIndex> {
+        let mut outgoing = Vec::new();
+        
+        if let Some(start_he) = self.vertex(vertex_idx).seed_half_edge {
+            let mut current_he = start_he;
+            
+            loop {
+                outgoing.push(current_he);
+                
+                let he = self.half_edge(current_he);
+                if let Some(twin_he) = he.twin_index {
+                    current_he = self.half_edge(twin_he).next_edge;
+                    
+                    if current_he == start_he {
+                        break;
+                    }
+                } else {
+                    break;
+                }
+            }
+        }
+        
+        outgoing
+    }
+
+    /// Every half-edge leaving a vertex. Unlike `vertex_outgoing_half_edges` this also finds
+    /// the half-edges on the far side of an implicit boundary, by walking both ways round.
+    fn vertex_ring(&self, vertex_idx: VertexIndex) -> Vec<HalfEdgeIndex> {
+        let Some(seed) = self.vertex(vertex_idx).seed_half_edge else { return Vec::new() };
+        let mut ring = vec![seed];
+
+        // Forward (bounded so a corrupt mesh can't loop forever)
+        let mut current = seed;
+        for _ in 0..self.half_edges.len() {
+            match self.half_edge(current).twin_index {
+                Some(twin) => current = self.half_edge(twin).next_edge,
+                None => break,
+            }
+            if current == seed {
+                return ring;
+            }
+            ring.push(current);
+        }
+
+        // Stopped at a boundary: pick up the rest walking backward from the seed
+        current = seed;
+        for _ in 0..self.half_edges.len() {
+            match self.half_edge(self.half_edge(current).prev_edge).twin_index {
+                Some(twin) => current = twin,
+                None => break,
+            }
+            ring.push(current);
+        }
+        ring
+    }
+
+    /// Faces around a vertex, in the order its outgoing half-edges are walked.
+    /// Boundary half-edges have no face and are skipped.
+    pub fn vertex_faces(&self, vertex_idx: VertexIndex) -> Vec<FaceIndex> {
+        self.vertex_ring(vertex_idx).into_iter()
+            .filter_map(|idx| self.half_edge(idx).face_index)
+            .collect()
+    }
+
+    /// Every face connected to `seed` through shared edges, the seed included: the whole
+    /// piece of the mesh the seed belongs to. Pieces touching only at a vertex stay separate.
+    pub fn linked_faces(&self, seed: FaceIndex) -> HashSet<FaceIndex> {
+        let mut linked = HashSet::from([seed]);
+        let mut stack = vec![seed];
+        while let Some(face) = stack.pop() {
+            for idx in self.face_half_edges(face) {
+                let neighbor = self.half_edge(idx).twin_index
+                    .and_then(|twin| self.half_edge(twin).face_index);
+                if let Some(neighbor) = neighbor {
+                    if linked.insert(neighbor) {
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+        linked
+    }
+
+    /// Vertices sharing an edge with the given one
+    pub(crate) fn vertex_neighbors(&self, vertex_idx: VertexIndex) -> HashSet<VertexIndex> {
+        self.vertex_ring(vertex_idx).into_iter()
+            .flat_map(|idx| [self.half_edge(idx).target_vertex_index, self.half_edge_source(self.half_edge(idx).prev_edge)])
+            .collect()
+    }
+
+    /// Whether any edge at the vertex lies on a boundary
+    fn is_boundary_vertex(&self, vertex_idx: VertexIndex) -> bool {
+        self.vertex_ring(vertex_idx).into_iter()
+            .any(|idx| self.is_boundary_half_edge(idx) || self.is_boundary_half_edge(self.half_edge(idx).prev_edge))
+    }
+
+    /// Outward unit normal of a face by Newell's method: the dual of the summed wedge products
+    /// of consecutive corners, which is stable for non-planar faces as well. Faces with fewer
+    /// than three distinct corner positions get a zero normal.
+    pub fn face_normal(&self, face: FaceIndex) -> Vec3 {
+        let same = |a: &Vec3, b: &Vec3| (a.x, a.y, a.z) == (b.x, b.y, b.z);
+        let mut corners: Vec<Vec3> = self.face_vertices(face).into_iter()
+            .map(|v| self.vertex(v).position.vec3)
+            .collect();
+        corners.dedup_by(|a, b| same(a, b));
+        if corners.len() > 1 && same(&corners[0], &corners[corners.len() - 1]) {
+            corners.pop();
+        }
+        if corners.len() < 3 {
+            return Vec3::new(0.0, 0.0, 0.0);
+        }
+
+        // Face loops run clockwise seen from outside, so each corner is wedged with the one
+        // before it to get the outward orientation
+        let area = (0..corners.len())
+            .map(|i| corners[(i + 1) % corners.len()] ^ corners[i])
+            .fold(Bivec3 { xy: 0.0, xz: 0.0, yz: 0.0 }, |sum, plane| sum + plane);
+        area.dual().normalize()
+    }
+
+    /// Smooth per-vertex normals as a flat `[x, y, z, ...]` array aligned with `vertices`, made
+    /// by adding up the area-weighted normals of the faces around each vertex and normalizing.
+    /// Vertices without faces (isolated or removed) get a zero normal.
+    pub fn compute_vertex_normals(&self) -> Vec<f32> {
+        let mut sums = vec![DVec3::ZERO; self.vertices.len()];
+        for face in (0..self.faces.len()).map(FaceIndex).filter(|&face| !self.is_face_removed(face)) {
+            let corners = self.face_vertices(face);
+            let positions: Vec<DVec3> = corners.iter()
+                .map(|&v| {
+                    let p = self.vertex(v).position.vec3;
+                    DVec3::new(p.x as f64, p.y as f64, p.z as f64)
+                })
+                .collect();
+            // Face loops run clockwise seen from outside, so Newell's normal points inward.
+            // Its length is twice the face area.
+            let normal = -polygon_normal(&positions);
+            for v in corners {
+                sums[v.0] += normal;
+            }
+        }
+
+        sums.into_iter()
+            .flat_map(|sum| {
+                let n = sum.normalize_or_zero();
+                [n.x as f32, n.y as f32, n.z as f32]
+            })
+            .collect()
+    }
+
+    /// Length of the edge a half-edge runs along
+    pub fn half_edge_length(&self, idx: HalfEdgeIndex) -> f32 {
+        let source = self.vertex(self.half_edge_source(idx)).position;
+        let target = self.vertex(self.half_edge(idx).target_vertex_index).position;
+        (target - source).vec3.length()
+    }
+
+    /// Check that collapsing the edge of `he` keeps the mesh manifold. The endpoints may only
+    /// share the neighbors opposite the edge in its adjacent triangles (the link condition),
+    /// an interior edge may not join two boundary vertices, and no other face may contain
+    /// both endpoints. Returns a description of the first violation found.
+    pub fn check_link_condition(&self, he: HalfEdgeIndex) -> Result<(), String> {
+        if self.is_half_edge_removed(he) {
+            return Err(format!("Half-edge {} has been removed", he.0));
+        }
+        let source = self.half_edge_source(he);
+        let target = self.half_edge(he).target_vertex_index;
+        let twin = self.half_edge(he).twin_index;
+
+        // Loops (faces or explicit boundaries) that are triangles disappear in the collapse;
+        // their third corner is the only neighbor the endpoints may share
+        let mut opposite = HashSet::new();
+        for side in std::iter::once(he).chain(twin) {
+            let (prev, next) = (self.half_edge(side).prev_edge, self.half_edge(side).next_edge);
+            if self.half_edge(next).next_edge != prev {
+                continue;
+            }
+            opposite.insert(self.half_edge(next).target_vertex_index);
+
+            // The two remaining edges are merged into one, which needs a face on some side
+            let has_face = |idx: HalfEdgeIndex| {
+                self.half_edge(idx).twin_index.is_some_and(|twin| self.half_edge(twin).face_index.is_some())
+            };
+            if !has_face(prev) && !has_face(next) {
+                return Err(format!("Collapsing edge {} would leave a dangling edge", he.0));
+            }
+        }
+
+        if self.vertex_neighbors(source).intersection(&self.vertex_neighbors(target)).any(|v| !opposite.contains(v)) {
+            return Err(format!("Collapsing edge {} violates the link condition: its endpoints share a neighbor off the edge", he.0));
+        }
+
+        // Both opposite corners joined to both endpoints by triangles: the two sides of the
+        // collapse would fold onto each other (a tetrahedron flattening to a double triangle)
+        if let [a, b] = opposite.iter().copied().collect::<Vec<_>>()[..] {
+            let has_triangle = |vertex: VertexIndex| self.vertex_ring(vertex).into_iter().any(|idx| {
+                self.half_edge(idx).face_index.is_some_and(|face| {
+                    let corners = self.face_vertices(face);
+                    corners.len() == 3 && corners.contains(&a) && corners.contains(&b)
+                })
+            });
+            if has_triangle(source) && has_triangle(target) {
+                return Err(format!("Collapsing edge {} would fold its neighborhood flat", he.0));
+            }
+        }
+
+        let boundary_edge = self.is_boundary_half_edge(he) || twin.is_some_and(|twin| self.is_boundary_half_edge(twin));
+        if !boundary_edge && self.is_boundary_vertex(source) && self.is_boundary_vertex(target) {
+            return Err(format!("Collapsing interior edge {} would pinch two boundaries together", he.0));
+        }
+
+        let edge_faces = [self.half_edge(he).face_index, twin.and_then(|twin| self.half_edge(twin).face_index)];
+        for idx in self.vertex_ring(source) {
+            let Some(face) = self.half_edge(idx).face_index else { continue };
+            if !edge_faces.contains(&Some(face)) && self.face_vertices(face).contains(&target) {
+                return Err(format!("Collapsing edge {} would fold face {} onto itself", he.0, face.0));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Merge the endpoints of the edge of `he` into its source vertex, moved to the edge midpoint.
+    /// Adjacent triangles degenerate and are removed, their two remaining edges becoming twins;
+    /// larger adjacent faces just lose a side. The target vertex, the edge's half-edges and the
+    /// removed faces are tombstoned. Fails, without changing anything, when `check_link_condition` does.
+    pub fn collapse_edge(&mut self, he: HalfEdgeIndex) -> Result<VertexIndex, String> {
+        self.check_link_condition(he)?;
+
+        let keep = self.half_edge_source(he);
+        let remove = self.half_edge(he).target_vertex_index;
+        let twin = self.half_edge(he).twin_index;
+        let sides: Vec<HalfEdgeIndex> = std::iter::once(he).chain(twin).collect();
+
+        // Outgoing half-edges of every vertex that may lose its seed, gathered while the rings are intact
+        let mut reseed: Vec<(VertexIndex, Vec<HalfEdgeIndex>)> = sides.iter()
+            .map(|&side| self.half_edge(self.half_edge(side).next_edge).target_vertex_index)
+            .map(|vertex| (vertex, self.vertex_ring(vertex)))
+            .collect();
+        let mut merged_ring = self.vertex_ring(keep);
+        merged_ring.extend(self.vertex_ring(remove));
+        reseed.push((keep, merged_ring));
+
+        // Everything arriving at the removed vertex now arrives at the kept one
+        for idx in self.vertex_ring(remove) {
+            let prev = self.half_edge(idx).prev_edge;
+            self.half_edge_mut(prev).target_vertex_index = keep;
+        }
+
+        for &side in &sides {
+            let (prev, next) = (self.half_edge(side).prev_edge, self.half_edge(side).next_edge);
+            self.half_edge_mut(prev).next_edge = next;
+            self.half_edge_mut(next).prev_edge = prev;
+            let face = self.half_edge(side).face_index;
+            if let Some(face) = face {
+                if self.face(face).seed_half_edge == side {
+                    self.face_mut(face).seed_half_edge = next;
+                }
+            }
+            self.mark_half_edge_removed(side);
+
+            // A triangle is now a two-sided loop: drop it and twin its neighbors across it
+            if self.half_edge(next).next_edge == prev {
+                let (prev_twin, next_twin) = (self.half_edge(prev).twin_index, self.half_edge(next).twin_index);
+                if let Some(prev_twin) = prev_twin {
+                    self.half_edge_mut(prev_twin).twin_index = next_twin;
+                }
+                if let Some(next_twin) = next_twin {
+                    self.half_edge_mut(next_twin).twin_index = prev_twin;
+                }
+                self.mark_half_edge_removed(prev);
+                self.mark_half_edge_removed(next);
+                if let Some(face) = face {
+                    self.mark_face_removed(face);
+                }
+            }
+        }
+
+        for (vertex, ring) in reseed {
+            let seed = self.vertex(vertex).seed_half_edge;
+            if vertex == keep || seed.is_some_and(|seed| self.is_half_edge_removed(seed)) {
+                self.vertex_mut(vertex).seed_half_edge = ring.into_iter().find(|&idx| !self.is_half_edge_removed(idx));
+            }
+        }
+
+        let (a, b) = (self.vertex(keep).position.vec3, self.vertex(remove).position.vec3);
+        self.vertex_mut(keep).position = Point3 { vec3: (a + b) * 0.5 };
+        self.vertex_mut(remove).seed_half_edge = None;
+        self.mark_vertex_removed(remove);
+
+        Ok(keep)
+    }
+
+    /// Collapse every edge shorter than `min_length`, repeating until none is left that can be
+    /// collapsed without breaking manifoldness. Returns the number of edges collapsed.
+    pub fn collapse_short_edges(&mut self, min_length: f32) -> usize {
+        let mut collapsed = 0;
+        loop {
+            let collapsed_before = collapsed;
+            for idx in (0..self.half_edges.len()).map(HalfEdgeIndex) {
+                // Visit each edge once, through the lower-indexed of its half-edges
+                if self.is_half_edge_removed(idx) || self.half_edge(idx).twin_index.is_some_and(|twin| twin.0 < idx.0) {
+                    continue;
+                }
+                if self.half_edge_length(idx) < min_length && self.collapse_edge(idx).is_ok() {
+                    collapsed += 1;
+                }
+            }
+            if collapsed == collapsed_before {
+                return collapsed;
+            }
+        }
+    }
+
+    /// Weld `remove` into `keep` topologically: every half-edge arriving at `remove` arrives at
+    /// `keep` instead, and half-edges left without a twin that now run between the same two
+    /// vertices in opposite directions are paired up. Positions are not touched. Fails, without
+    /// changing anything, if the result would not be manifold: the vertices share a face, an
+    /// edge would be used more than twice, or the faces around `keep` would not form a single
+    /// fan (two pieces touching only at the vertex). A cut seam is therefore zipped up starting
+    /// next to where it ends, not in the middle. Vertices on explicit boundary loops are refused.
+    pub fn merge_vertices(&mut self, keep: VertexIndex, remove: VertexIndex) -> Result<(), String> {
+        if keep == remove {
+            return Err(format!("Cannot merge vertex {} with itself", keep.0));
+        }
+        for vertex in [keep, remove] {
+            if vertex.0 >= self.vertices.len() || self.is_vertex_removed(vertex) {
+                return Err(format!("Vertex {} does not exist", vertex.0));
+            }
+        }
+
+        // Half-edges leaving either vertex; those arriving are their prev edges
+        let outgoing: Vec<HalfEdgeIndex> = (0..self.half_edges.len()).map(HalfEdgeIndex)
+            .filter(|&idx| !self.is_half_edge_removed(idx))
+            .filter(|&idx| [keep, remove].contains(&self.half_edge_source(idx)))
+            .collect();
+        if outgoing.iter().any(|&idx| self.half_edge(idx).face_index.is_none()) {
+            return Err("Cannot merge vertices on an explicit boundary loop".to_string());
+        }
+
+        let mut keep_faces = HashSet::new();
+        let mut remove_faces = HashSet::new();
+        for &idx in &outgoing {
+            let faces = if self.half_edge_source(idx) == keep { &mut keep_faces } else { &mut remove_faces };
+            faces.extend(self.half_edge(idx).face_index);
+        }
+        if let Some(face) = keep_faces.intersection(&remove_faces).next() {
+            return Err(format!("Vertices {} and {} share face {}", keep.0, remove.0, face.0));
+        }
+
+        // After the merge each edge at the vertex may be used once in each direction
+        let mut leaving_to: HashMap<VertexIndex, HalfEdgeIndex> = HashMap::new();
+        let mut arriving_from: HashMap<VertexIndex, HalfEdgeIndex> = HashMap::new();
+        for &idx in &outgoing {
+            let incoming = self.half_edge(idx).prev_edge;
+            let to = self.half_edge(idx).target_vertex_index;
+            let from = self.half_edge_source(incoming);
+            if leaving_to.insert(to, idx).is_some() || arriving_from.insert(from, incoming).is_some() {
+                return Err(format!("Merging vertices {} and {} would make a non-manifold edge", keep.0, remove.0));
+            }
+        }
+
+        // Twin of each arriving half-edge once merged: its current twin, or else the twinless
+        // half-edge leaving for the vertex it comes from
+        let new_twin = |incoming: HalfEdgeIndex| -> Option<HalfEdgeIndex> {
+            self.half_edge(incoming).twin_index.or_else(|| {
+                leaving_to.get(&self.half_edge_source(incoming)).copied()
+                    .filter(|&out| self.half_edge(out).twin_index.is_none())
+            })
+        };
+
+        // Rotating around the merged vertex goes from an outgoing half-edge to the twin of the
+        // half-edge before it. The outgoing half-edges must form a single chain or cycle.
+        let successor: HashMap<HalfEdgeIndex, HalfEdgeIndex> = outgoing.iter()
+            .filter_map(|&idx| new_twin(self.half_edge(idx).prev_edge).map(|next| (idx, next)))
+            .collect();
+        let has_predecessor: HashSet<HalfEdgeIndex> = successor.values().copied().collect();
+        let chain_starts: Vec<HalfEdgeIndex> = outgoing.iter().copied().filter(|idx| !has_predecessor.contains(idx)).collect();
+        let mut visited = HashSet::new();
+        let mut fans = 0;
+        for &start in chain_starts.iter().chain(&outgoing) {
+            if visited.contains(&start) {
+                continue;
+            }
+            fans += 1;
+            let mut current = Some(start);
+            while let Some(idx) = current.filter(|&idx| visited.insert(idx)) {
+                current = successor.get(&idx).copied();
+            }
+        }
+        if fans > 1 {
+            return Err(format!("Merging vertices {} and {} would join faces only at the vertex", keep.0, remove.0));
+        }
+
+        let pairs: Vec<(HalfEdgeIndex, HalfEdgeIndex)> = outgoing.iter()
+            .map(|&idx| self.half_edge(idx).prev_edge)
+            .filter(|&incoming| self.half_edge(incoming).twin_index.is_none())
+            .filter_map(|incoming| new_twin(incoming).map(|out| (incoming, out)))
+            .collect();
+        let seed = chain_starts.first().or(outgoing.first()).copied();
+
+        for &idx in &outgoing {
+            let incoming = self.half_edge(idx).prev_edge;
+            self.half_edge_mut(incoming).target_vertex_index = keep;
+        }
+        for (incoming, out) in pairs {
+            self.half_edge_mut(incoming).twin_index = Some(out);
+            self.half_edge_mut(out).twin_index = Some(incoming);
+        }
+        self.vertex_mut(keep).seed_half_edge = seed;
+        self.vertex_mut(remove).seed_half_edge = None;
+        self.mark_vertex_removed(remove);
+
+        Ok(())
+    }
+
+    /// Simplified copy of the mesh with about `target_faces` faces, made by repeatedly collapsing
+    /// the edge whose collapse moves the surface least (quadric error metrics, Garland-Heckbert).
+    /// Stops early once no edge can be collapsed without breaking manifoldness or flipping faces.
+    pub fn simplify(&self, target_faces: usize) -> HalfEdgeMesh {
+        self.simplify_qem(target_faces, f64::INFINITY)
+    }
+
+    /// Simplified copy keeping about `ratio` (0 to 1) of the faces; see `simplify`
+    pub fn simplify_to_ratio(&self, ratio: f32) -> HalfEdgeMesh {
+        let target_faces = (self.live_face_count() as f32 * ratio.clamp(0.0, 1.0)).round() as usize;
+        self.simplify_qem(target_faces, f64::INFINITY)
+    }
+
+    /// Simplified copy with every collapse that keeps the error within `max_error` applied.
+    /// The error of a vertex is its summed squared distance to the planes of the original
+    /// faces it stands for, so `max_error` is roughly how far the surface may move.
+    pub fn simplify_to_error(&self, max_error: f32) -> HalfEdgeMesh {
+        let max_error = max_error.max(0.0) as f64;
+        self.simplify_qem(0, max_error * max_error)
+    }
+
+    /// Number of faces that haven't been removed
+    fn live_face_count(&self) -> usize {
+        self.faces.len() - self.removed_faces.len()
+    }
+
+    /// Core QEM loop: collapse the cheapest edge until `target_faces` is reached or the
+    /// cheapest collapse would cost more than `max_cost`, then compact the result
+    fn simplify_qem(&self, target_faces: usize, max_cost: f64) -> HalfEdgeMesh {
+        let mut mesh = self.clone();
+        let position = |mesh: &HalfEdgeMesh, v: VertexIndex| {
+            let p = mesh.vertex(v).position.vec3;
+            DVec3::new(p.x as f64, p.y as f64, p.z as f64)
+        };
+
+        // Each vertex starts with the planes of the faces around it. Boundary edges also get
+        // a plane through the edge, perpendicular to its face, so open borders hold their shape.
+        let mut quadrics = vec![Quadric::ZERO; mesh.vertices.len()];
+        for face in (0..mesh.faces.len()).map(FaceIndex).filter(|&face| !mesh.is_face_removed(face)) {
+            let corners: Vec<DVec3> = mesh.face_vertices(face).into_iter().map(|v| position(&mesh, v)).collect();
+            let normal = polygon_normal(&corners).normalize_or_zero();
+            if normal == DVec3::ZERO {
+                continue;
+            }
+            let plane = Quadric::from_plane(normal, corners[0]);
+            for idx in mesh.face_half_edges(face) {
+                let v = mesh.half_edge(idx).target_vertex_index;
+                quadrics[v.0] += plane;
+                if mesh.half_edge(idx).twin_index.is_none_or(|twin| mesh.half_edge(twin).face_index.is_none()) {
+                    let source = mesh.half_edge_source(idx);
+                    let (a, b) = (position(&mesh, source), position(&mesh, v));
+                    let border_normal = (b - a).cross(normal).normalize_or_zero();
+                    let border = Quadric::from_plane(border_normal, a) * QEM_BOUNDARY_WEIGHT;
+                    quadrics[source.0] += border;
+                    quadrics[v.0] += border;
+                }
+            }
+        }
+
+        // Collapse candidates, cheapest first. A vertex's stamp changes whenever its quadric
+        // does, which invalidates the queued candidates of every edge around it.
+        let mut stamps = vec![0u32; mesh.vertices.len()];
+        let mut queue = BinaryHeap::new();
+        let candidate = |mesh: &HalfEdgeMesh, quadrics: &[Quadric], stamps: &[u32], he: HalfEdgeIndex| {
+            let (a, b) = (mesh.half_edge_source(he), mesh.half_edge(he).target_vertex_index);
+            let quadric = quadrics[a.0] + quadrics[b.0];
+            let (target, cost) = quadric.best_position(position(mesh, a), position(mesh, b));
+            CollapseCandidate { cost, he, target, stamps: (stamps[a.0], stamps[b.0]) }
+        };
+        for he in (0..mesh.half_edges.len()).map(HalfEdgeIndex) {
+            // Each edge once, through the lower-indexed of its half-edges
+            if !mesh.is_half_edge_removed(he) && mesh.half_edge(he).twin_index.is_none_or(|twin| he.0 < twin.0) {
+                queue.push(candidate(&mesh, &quadrics, &stamps, he));
+            }
+        }
+
+        let mut face_count = mesh.live_face_count();
+        while face_count > target_faces {
+            let Some(next) = queue.pop() else { break };
+            if next.cost > max_cost {
+                break;
+            }
+            let he = next.he;
+            if mesh.is_half_edge_removed(he) {
+                continue;
+            }
+            let (keep, remove) = (mesh.half_edge_source(he), mesh.half_edge(he).target_vertex_index);
+            if next.stamps != (stamps[keep.0], stamps[remove.0]) || mesh.collapse_flips_faces(he, next.target) {
+                continue;
+            }
+            if mesh.collapse_edge(he).is_err() {
+                continue;
+            }
+
+            mesh.vertex_mut(keep).position = Point3::new(next.target.x as f32, next.target.y as f32, next.target.z as f32);
+            let removed_quadric = quadrics[remove.0];
+            quadrics[keep.0] += removed_quadric;
+            stamps[keep.0] += 1;
+            face_count = mesh.live_face_count();
+
+            let mut edges: Vec<HalfEdgeIndex> = mesh.vertex_ring(keep).into_iter()
+                .flat_map(|idx| [idx, mesh.half_edge(idx).prev_edge])
+                .map(|idx| mesh.half_edge(idx).twin_index.map_or(idx, |twin| HalfEdgeIndex(idx.0.min(twin.0))))
+                .collect();
+            edges.sort_by_key(|idx| idx.0);
+            edges.dedup();
+            for edge in edges {
+                queue.push(candidate(&mesh, &quadrics, &stamps, edge));
+            }
+        }
+
+        mesh.compact();
+        mesh
+    }
+
+    /// Whether moving both endpoints of the edge of `he` to `target` would turn any of the
+    /// surrounding faces (other than those collapsing with the edge) upside down
+    fn collapse_flips_faces(&self, he: HalfEdgeIndex, target: DVec3) -> bool {
+        let endpoints = [self.half_edge_source(he), self.half_edge(he).target_vertex_index];
+        let position = |v: VertexIndex| {
+            let p = self.vertex(v).position.vec3;
+            DVec3::new(p.x as f64, p.y as f64, p.z as f64)
+        };
+        endpoints.iter()
+            .flat_map(|&v| self.vertex_faces(v))
+            .any(|face| {
+                let corners = self.face_vertices(face);
+                if corners.len() == 3 && endpoints.iter().all(|v| corners.contains(v)) {
+                    return false; // Collapses away with the edge
+                }
+                let before: Vec<DVec3> = corners.iter().map(|&v| position(v)).collect();
+                let after: Vec<DVec3> = corners.iter()
+                    .map(|&v| if endpoints.contains(&v) { target } else { position(v) })
+                    .collect();
+                polygon_normal(&before).dot(polygon_normal(&after)) <= 0.0
+            })
+    }
+
+    /// Insert a vertex at the midpoint of the edge of `he`, splitting it (and its twin, if it
+    /// has one) in two. Each adjacent face gets a new triangle cut off it, from the new vertex
+    /// round the corner at the end of the split edge, so a triangle becomes two triangles and
+    /// a quad a quad and a triangle; explicit boundary loops just gain a corner. Vertex
+    /// attributes are interpolated, new faces copy their original's attributes. Returns the new vertex.
+    pub fn split_edge(&mut self, he: HalfEdgeIndex) -> VertexIndex {
+        let source = self.half_edge_source(he);
+        let target = self.half_edge(he).target_vertex_index;
+        let (a, b) = (self.vertex(source).position.vec3, self.vertex(target).position.vec3);
+        let midpoint = self.add_vertex(Point3 { vec3: (a + b) * 0.5 });
+        for values in self.vertex_attributes.values_mut() {
+            values[midpoint.0] = 0.5 * (values[source.0] + values[target.0]);
+        }
+
+        let twin = self.half_edge(he).twin_index;
+        let second_half = self.split_half_edge(he, midpoint);
+        if let Some(twin) = twin {
+            let twin_second_half = self.split_half_edge(twin, midpoint);
+            self.half_edge_mut(he).twin_index = Some(twin_second_half);
+            self.half_edge_mut(twin_second_half).twin_index = Some(he);
+            self.half_edge_mut(twin).twin_index = Some(second_half);
+            self.half_edge_mut(second_half).twin_index = Some(twin);
+        }
+        self.vertex_mut(midpoint).seed_half_edge = Some(second_half);
+
+        for side in std::iter::once(he).chain(twin) {
+            if self.half_edge(side).face_index.is_some() {
+                self.split_off_corner_triangle(side);
+            }
+        }
+
+        midpoint
+    }
+
+    /// Cut `he` short at `midpoint` and append a half-edge for the rest of the way, in the same
+    /// loop and with no twin. Returns the new half-edge.
+    fn split_half_edge(&mut self, he: HalfEdgeIndex, midpoint: VertexIndex) -> HalfEdgeIndex {
+        let second_half = HalfEdgeIndex(self.half_edges.len());
+        let next = self.half_edge(he).next_edge;
+        self.half_edges.push(HalfEdge {
+            target_vertex_index: self.half_edge(he).target_vertex_index,
+            twin_index: None,
+            next_edge: next,
+            prev_edge: he,
+            face_index: self.half_edge(he).face_index,
+        });
+        self.half_edge_mut(next).prev_edge = second_half;
+        let he = self.half_edge_mut(he);
+        he.next_edge = second_half;
+        he.target_vertex_index = midpoint;
+        second_half
+    }
+
+    /// Cut a triangle off a face that just had its edge `first` split (so its second corner
+    /// is the new vertex), joined to the rest of the face by a diagonal from that vertex to
+    /// the corner after next. Faces left with fewer than four corners are not split.
+    fn split_off_corner_triangle(&mut self, first: HalfEdgeIndex) {
+        let [e0, e1, e2, e3, ..] = self.loop_half_edges(first)[..] else { return };
+        let face = self.half_edge(e0).face_index.expect("only face loops are split");
+        let midpoint = self.half_edge(e0).target_vertex_index;
+        let opposite = self.half_edge(e2).target_vertex_index;
+
+        let new_face = self.add_face(e1);
+        for values in self.face_attributes.values_mut() {
+            values[new_face.0] = values[face.0];
+        }
+
+        // e0 -> diagonal -> e3 -> ... keeps the original face, e1 -> e2 -> back-diagonal is the new one
+        let diagonal = HalfEdgeIndex(self.half_edges.len());
+        let back_diagonal = HalfEdgeIndex(self.half_edges.len() + 1);
+        self.half_edges.push(HalfEdge {
+            target_vertex_index: opposite,
+            twin_index: Some(back_diagonal),
+            next_edge: e3,
+            prev_edge: e0,
+            face_index: Some(face),
+        });
+        self.half_edges.push(HalfEdge {
+            target_vertex_index: midpoint,
+            twin_index: Some(diagonal),
+            next_edge: e1,
+            prev_edge: e2,
+            face_index: Some(new_face),
+        });
+        self.half_edge_mut(e0).next_edge = diagonal;
+        self.half_edge_mut(e3).prev_edge = diagonal;
+        self.half_edge_mut(e2).next_edge = back_diagonal;
+        self.half_edge_mut(e1).prev_edge = back_diagonal;
+        self.half_edge_mut(e1).face_index = Some(new_face);
+        self.half_edge_mut(e2).face_index = Some(new_face);
+        self.face_mut(face).seed_half_edge = e0;
+    }
+
+    /// Split every edge longer than `max_length` at its midpoint, longest first. Edges created
+    /// by a split are checked in turn, so no edge is longer than the limit afterwards. Going
+    /// longest first keeps the diagonals each split cuts into the faces beside it from piling
+    /// up into slivers, which would need ever more splits. Returns the number of splits.
+    pub fn split_long_edges(&mut self, max_length: f32) -> usize {
+        self.split_long_edges_with_progress(max_length, &mut |_| true).unwrap_or_default()
+    }
+
+    /// `split_long_edges` for large meshes: `progress` is called every `PROGRESS_INTERVAL`
+    /// edges with the fraction done (0..1) and returns false to cancel. A cancelled run
+    /// returns an error and leaves the mesh valid, with the longest edges split and the rest
+    /// untouched, so running it again finishes the job.
+    pub fn split_long_edges_with_progress(&mut self, max_length: f32, progress: &mut dyn FnMut(f32) -> bool) -> Result<usize, String> {
+        if max_length <= 0.0 || max_length.is_nan() {
+            return Ok(0);
+        }
+
+        // Each edge is queued through the lower-indexed of its half-edges
+        let mut queue = BinaryHeap::new();
+        let enqueue = |mesh: &HalfEdgeMesh, queue: &mut BinaryHeap<SplitCandidate>, he: HalfEdgeIndex| {
+            let he = mesh.half_edge(he).twin_index.map_or(he, |twin| HalfEdgeIndex(he.0.min(twin.0)));
+            let length = mesh.half_edge_length(he);
+            if length > max_length {
+                queue.push(SplitCandidate { length, he });
+            }
+        };
+        for he in (0..self.half_edges.len()).map(HalfEdgeIndex) {
+            if !self.is_half_edge_removed(he) {
+                enqueue(self, &mut queue, he);
+            }
+        }
+
+        let mut split = 0;
+        let mut popped = 0;
+        let mut reported = 0.0f32;
+        while let Some(next) = queue.pop() {
+            if popped % PROGRESS_INTERVAL == 0 {
+                // The queue grows as we go, so keep the reported fraction from going backwards
+                reported = reported.max(popped as f32 / (popped + queue.len() + 1) as f32);
+                if !progress(reported) {
+                    return Err(format!("Edge splitting cancelled after {split} splits"));
+                }
+            }
+            popped += 1;
+            // An edge already split (or queued twice) has a different length now
+            if self.half_edge_length(next.he) != next.length {
+                continue;
+            }
+
+            let first_new = self.half_edges.len();
+            self.split_edge(next.he);
+            split += 1;
+            for he in std::iter::once(next.he).chain((first_new..self.half_edges.len()).map(HalfEdgeIndex)) {
+                enqueue(self, &mut queue, he);
+            }
+        }
+        progress(1.0);
+        Ok(split)
+    }
+
+    /// Add a vertex at the centroid of `face` and fan it to the corners, turning an n-gon into
+    /// n triangles. The original face keeps the triangle on its seed half-edge; the others are
+    /// new faces copying its attributes. The new vertex's attributes average the corners'.
+    /// Returns the new vertex.
+    pub fn poke_face(&mut self, face: FaceIndex) -> VertexIndex {
+        let rim = self.face_half_edges(face);
+        let corners: Vec<VertexIndex> = rim.iter().map(|&idx| self.half_edge_source(idx)).collect();
+        let n = rim.len();
+
+        let centroid = corners.iter()
+            .fold(Vec3::new(0.0, 0.0, 0.0), |sum, &corner| sum + self.vertex(corner).position.vec3)
+            * (1.0 / n as f32);
+        let center = self.add_vertex(Point3 { vec3: centroid });
+        for values in self.vertex_attributes.values_mut() {
+            values[center.0] = corners.iter().map(|corner| values[corner.0]).sum::<f32>() / n as f32;
+        }
+
+        // Triangle i is rim[i], then spoke_in(i) to the center, then spoke_out(i) back to the rim
+        let base = self.half_edges.len();
+        let spoke_in = |i: usize| HalfEdgeIndex(base + 2 * (i % n));
+        let spoke_out = |i: usize| HalfEdgeIndex(base + 2 * (i % n) + 1);
+        for (i, &rim_edge) in rim.iter().enumerate() {
+            let triangle = if i == 0 {
+                face
+            } else {
+                let new_face = self.add_face(rim_edge);
+                for values in self.face_attributes.values_mut() {
+                    values[new_face.0] = values[face.0];
+                }
+                new_face
+            };
+            self.half_edges.push(HalfEdge {
+                target_vertex_index: center,
+                twin_index: Some(spoke_out(i + 1)),
+                next_edge: spoke_out(i),
+                prev_edge: rim_edge,
+                face_index: Some(triangle),
+            });
+            self.half_edges.push(HalfEdge {
+                target_vertex_index: corners[i],
+                twin_index: Some(spoke_in(i + n - 1)),
+                next_edge: rim_edge,
+                prev_edge: spoke_in(i),
+                face_index: Some(triangle),
+            });
+            let rim_half_edge = self.half_edge_mut(rim_edge);
+            rim_half_edge.next_edge = spoke_in(i);
+            rim_half_edge.prev_edge = spoke_out(i);
+            rim_half_edge.face_index = Some(triangle);
+        }
+        self.face_mut(face).seed_half_edge = rim[0];
+        self.vertex_mut(center).seed_half_edge = Some(spoke_out(0));
+
+        center
+    }
+
+    /// Extrude a region of faces: the faces are lifted by `distance` along their normals
+    /// (summed at shared corners) and joined back to the rest of the mesh by a wall of quads
+    /// along the region's border. Faces sharing an edge move together. The region's faces keep
+    /// their indices, each wall quad copies the attributes of the face it borders, and lifted
+    /// corners copy the attributes of the corner they came from. Corners inside the region
+    /// are left without faces and removed. Returns the wall faces.
+    pub fn extrude_faces(&mut self, faces: &HashSet<FaceIndex>, distance: f32) -> Vec<FaceIndex> {
+        let mut region: Vec<FaceIndex> = faces.iter().copied()
+            .filter(|&face| face.0 < self.faces.len() && !self.is_face_removed(face))
+            .collect();
+        region.sort_by_key(|face| face.0);
+        let in_region = |mesh: &Self, idx: HalfEdgeIndex| mesh.half_edge(idx).face_index.is_some_and(|face| faces.contains(&face));
+
+        let mut directions: HashMap<VertexIndex, Vec3> = HashMap::new();
+        for &face in &region {
+            let normal = self.face_normal(face);
+            for corner in self.face_vertices(face) {
+                let direction = directions.entry(corner).or_insert(Vec3::new(0.0, 0.0, 0.0));
+                *direction = *direction + normal;
+            }
+        }
+        let mut corners: Vec<VertexIndex> = directions.keys().copied().collect();
+        corners.sort_by_key(|corner| corner.0);
+        let mut lifted = HashMap::new();
+        for &corner in &corners {
+            let position = self.vertex(corner).position.vec3 + directions[&corner].normalize() * distance;
+            let copy = self.add_vertex(Point3 { vec3: position });
+            for values in self.vertex_attributes.values_mut() {
+                values[copy.0] = values[corner.0];
+            }
+            lifted.insert(corner, copy);
+        }
+
+        // Half-edges leaving each corner outside the region, which stay put
+        let outside_seeds: HashMap<VertexIndex, HalfEdgeIndex> = corners.iter()
+            .filter_map(|&corner| {
+                self.vertex_ring(corner).into_iter()
+                    .find(|&idx| !in_region(self, idx))
+                    .map(|idx| (corner, idx))
+            })
+            .collect();
+
+        // Border half-edges (across from a face outside the region, or from nothing) with
+        // their original endpoints, then move the whole region onto the lifted corners
+        let region_half_edges: Vec<HalfEdgeIndex> = region.iter().flat_map(|&face| self.face_half_edges(face)).collect();
+        let border: Vec<(HalfEdgeIndex, VertexIndex, VertexIndex)> = region_half_edges.iter()
+            .filter(|&&idx| !self.half_edge(idx).twin_index.is_some_and(|twin| in_region(self, twin)))
+            .map(|&idx| (idx, self.half_edge_source(idx), self.half_edge(idx).target_vertex_index))
+            .collect();
+        for &idx in &region_half_edges {
+            let copy = lifted[&self.half_edge(idx).target_vertex_index];
+            self.half_edge_mut(idx).target_vertex_index = copy;
+            self.vertex_mut(copy).seed_half_edge = Some(self.half_edge(idx).next_edge);
+        }
+
+        // Each wall runs bottom (a -> b, facing the outside) -> rise (b -> b') -> top (b' -> a',
+        // facing the border half-edge) -> drop (a' -> a)
+        let mut rises = HashMap::new();
+        let mut drops = HashMap::new();
+        let mut walls = Vec::with_capacity(border.len());
+        for (edge, a, b) in border {
+            let base = self.half_edges.len();
+            let [bottom, rise, top, drop] = [0, 1, 2, 3].map(|k| HalfEdgeIndex(base + k));
+            let wall = self.add_face(bottom);
+            if let Some(face) = self.half_edge(edge).face_index {
+                for values in self.face_attributes.values_mut() {
+                    values[wall.0] = values[face.0];
+                }
+            }
+            let outside = self.half_edge(edge).twin_index;
+            for (target, twin, next, prev) in [(b, outside, rise, drop), (lifted[&b], None, top, bottom), (lifted[&a], Some(edge), drop, rise), (a, None, bottom, top)] {
+                self.half_edges.push(HalfEdge { target_vertex_index: target, twin_index: twin, next_edge: next, prev_edge: prev, face_index: Some(wall) });
+            }
+            if let Some(outside) = outside {
+                self.half_edge_mut(outside).twin_index = Some(bottom);
+            }
+            self.half_edge_mut(edge).twin_index = Some(top);
+            rises.insert(b, rise);
+            drops.insert(a, drop);
+            walls.push(wall);
+        }
+
+        // Neighbouring walls share the vertical edge at the corner between them
+        for (corner, &rise) in &rises {
+            if let Some(&drop) = drops.get(corner) {
+                self.half_edge_mut(rise).twin_index = Some(drop);
+                self.half_edge_mut(drop).twin_index = Some(rise);
+            }
+        }
+
+        // Corners whose seed left with the region reseed on their wall or the faces around them
+        // that stayed, and go if they have neither
+        for corner in corners {
+            let seed = self.vertex(corner).seed_half_edge;
+            if seed.is_some_and(|seed| self.half_edge_source(seed) == corner) {
+                continue;
+            }
+            let new_seed = drops.get(&corner)
+                .map(|&drop| self.half_edge(drop).next_edge)
+                .or_else(|| outside_seeds.get(&corner).copied());
+            self.vertex_mut(corner).seed_half_edge = new_seed;
+            if new_seed.is_none() {
+                self.mark_vertex_removed(corner);
+            }
+        }
+
+        walls
+    }
+
+    /// Reverse every loop (faces and explicit boundaries), turning the mesh inside out.
+    /// Each half-edge keeps its twin and face but now runs the other way: it targets its old
+    /// source, and its next/prev links swap. Vertex seeds move to the half-edge that leaves
+    /// them after the flip; face seeds are unaffected.
+    pub fn flip_orientation(&mut self) {
+        let live: Vec<HalfEdgeIndex> = (0..self.half_edges.len()).map(HalfEdgeIndex)
+            .filter(|&idx| !self.is_half_edge_removed(idx))
+            .collect();
+        let new_targets: Vec<VertexIndex> = live.iter().map(|&idx| self.half_edge_source(idx)).collect();
+
+        for (&idx, target) in live.iter().zip(new_targets) {
+            let he = self.half_edge_mut(idx);
+            he.target_vertex_index = target;
+            std::mem::swap(&mut he.next_edge, &mut he.prev_edge);
+        }
+
+        // The old seed now arrives at its vertex; the half-edge before it (now after it) leaves
+        for vertex in (0..self.vertices.len()).map(VertexIndex) {
+            if self.is_vertex_removed(vertex) {
+                continue;
+            }
+            if let Some(seed) = self.vertex(vertex).seed_half_edge {
+                self.vertex_mut(vertex).seed_half_edge = Some(self.half_edge(seed).next_edge);
+            }
+        }
+    }
+
+    /// Like `to_mesh`, but quad faces stay quads instead of being split in two.
+    /// Triangles and larger polygons are triangulated exactly as `to_mesh` does.
+    pub fn to_quad_mesh(&self) -> QuadMesh {
+        let mut quad_mesh = QuadMesh::new();
+        quad_mesh.vertex_coords = self.vertices.iter()
+            .flat_map(|vertex| [vertex.position.vec3.x, vertex.position.vec3.y, vertex.position.vec3.z])
+            .collect();
+
+        for face in (0..self.faces.len()).map(FaceIndex) {
+            if self.is_face_removed(face) {
+                continue;
+            }
+            let corners = self.face_vertices(face);
+            if corners.len() == 4 {
+                // Reverse the loop, matching the winding `to_mesh` gives its triangles
+                quad_mesh.quad_indices.extend(corners.iter().rev().map(|v| v.0 as u32));
+            } else {
+                quad_mesh.triangle_indices.extend(self.face_fan_indices(face));
+            }
+        }
+
+        quad_mesh
+    }
+
+    /// Dual mesh: one vertex at the centroid of every face, and one face per vertex joining the
+    /// centroids of the faces around it. Faces keep the orientation of the original.
+    /// Meant for closed manifolds; vertices on a boundary produce no dual face.
+    pub fn dual(&self) -> HalfEdgeMesh {
+        let mut dual_vertex = vec![None; self.faces.len()];
+        let mut vertex_coords = Vec::new();
+        for face in (0..self.faces.len()).map(FaceIndex) {
+            if self.is_face_removed(face) {
+                continue;
+            }
+            let corners = self.face_vertices(face);
+            let mut centroid = [0.0f32; 3];
+            for vertex in &corners {
+                let position = self.vertex(*vertex).position.vec3;
+                centroid[0] += position.x;
+                centroid[1] += position.y;
+                centroid[2] += position.z;
+            }
+            dual_vertex[face.0] = Some((vertex_coords.len() / 3) as u32);
+            vertex_coords.extend(centroid.map(|c| c / corners.len() as f32));
+        }
+
+        let mut polygons = Vec::new();
+        'vertices: for vertex in (0..self.vertices.len()).map(VertexIndex) {
+            let Some(seed) = self.vertex(vertex).seed_half_edge else { continue };
+            if self.is_vertex_removed(vertex) {
+                continue;
+            }
+
+            // Step to the next face sharing this vertex via the twin of the incoming half-edge,
+            // which walks the faces in the same rotational order as the face loops
+            let mut polygon = Vec::new();
+            let mut current = seed;
+            loop {
+                let Some(face) = self.half_edge(current).face_index else { continue 'vertices };
+                polygon.push(dual_vertex[face.0].expect("live half-edges belong to live faces"));
+
+                let Some(twin) = self.half_edge(self.half_edge(current).prev_edge).twin_index else { continue 'vertices };
+                current = twin;
+                if current == seed {
+                    break;
+                }
+            }
+            polygons.push(polygon);
+        }
+
+        HalfEdgeMesh::from_polygons(&vertex_coords, &polygons)
+            .expect("dual faces only reference face centroids")
+    }
+}
+
+
+
+impl ToMesh for HalfEdgeMesh {
+    fn to_mesh(&self) -> Mesh {
+
+        let vertex_coords = 
+        self.vertices.iter().flat_map(
+            |vertex| [
+                vertex.position.vec3.x,
+                vertex.position.vec3.y,
+                vertex.position.vec3.z
+            ]
+        ).collect();
+
+        let face_indices = (0..self.faces.len())
+            .flat_map(|idx| self.face_fan_indices(FaceIndex(idx)))
+            .collect();
+
+        let normals = Some(self.compute_vertex_normals());
+
+        Mesh {
+            vertex_coords: vertex_coords,
+            face_indices: face_indices,
+            normals: normals,
+            uvs: None,
+            tangents: None,
+            smoothing_groups: None,
+            lines: None,
+            points: None,
+        }
+    }
+
+    fn face_triangles(&self, face: FaceIndex) -> Option<Vec<u32>> {
+        (face.0 < self.faces.len()).then(|| self.face_fan_indices(face))
+    }
+
+    fn vertex_position(&self, vertex: u32) -> Option<[f32; 3]> {
+        let position = self.vertices.get(vertex as usize)?.position.vec3;
+        Some([position.x, position.y, position.z])
+    }
+}
+
+/// Sum of squared distances to a set of planes, as a quadratic form:
+/// `error(p) = p·Ap + 2b·p + c`
+#[derive(Debug, Clone, Copy)]
+struct Quadric {
+    a: DMat3,
+    b: DVec3,
+    c: f64,
+}
+
+impl Quadric {
+    // Not derived: glam's default matrix is the identity
+    const ZERO: Quadric = Quadric { a: DMat3::ZERO, b: DVec3::ZERO, c: 0.0 };
+
+    /// Squared distance to the plane through `point` with unit `normal`
+    fn from_plane(normal: DVec3, point: DVec3) -> Self {
+        let d = -normal.dot(point);
+        Quadric {
+            a: DMat3::from_cols(normal * normal.x, normal * normal.y, normal * normal.z),
+            b: normal * d,
+            c: d * d,
+        }
+    }
+
+    fn error(&self, p: DVec3) -> f64 {
+        (p.dot(self.a * p) + 2.0 * self.b.dot(p) + self.c).max(0.0)
+    }
+
+    /// Position with the least error for the collapse of edge a-b, with that error. Falls
+    /// back to the cheapest of the endpoints and midpoint when the minimum isn't unique
+    /// (e.g. all planes parallel) or lies implausibly far from the edge.
+    fn best_position(&self, a: DVec3, b: DVec3) -> (DVec3, f64) {
+        if self.a.determinant().abs() > 1e-10 {
+            let optimum = -(self.a.inverse() * self.b);
+            if optimum.is_finite() && optimum.distance(0.5 * (a + b)) <= a.distance(b) {
+                return (optimum, self.error(optimum));
+            }
+        }
+        [a, b, (a + b) * 0.5].into_iter()
+            .map(|p| (p, self.error(p)))
+            .min_by(|x, y| x.1.total_cmp(&y.1))
+            .unwrap()
+    }
+}
+
+impl Add for Quadric {
+    type Output = Quadric;
+    fn add(self, rhs: Quadric) -> Quadric {
+        Quadric { a: self.a + rhs.a, b: self.b + rhs.b, c: self.c + rhs.c }
+    }
+}
+
+impl AddAssign for Quadric {
+    fn add_assign(&mut self, rhs: Quadric) {
+        *self = *self + rhs;
+    }
+}
+
+impl Mul<f64> for Quadric {
+    type Output = Quadric;
+    fn mul(self, weight: f64) -> Quadric {
+        Quadric { a: self.a * weight, b: self.b * weight, c: self.c * weight }
+    }
+}
+
+/// Queued edge collapse, ordered cheapest first in a `BinaryHeap`
+struct CollapseCandidate {
+    cost: f64,
+    he: HalfEdgeIndex,
+    target: DVec3,
+    stamps: (u32, u32),  // Endpoint quadric stamps when the cost was computed
+}
+
+impl PartialEq for CollapseCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CollapseCandidate {}
+
+impl PartialOrd for CollapseCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CollapseCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost).then_with(|| other.he.0.cmp(&self.he.0))
+    }
+}
+
+/// Queued edge split, ordered longest first in a `BinaryHeap`
+struct SplitCandidate {
+    length: f32,
+    he: HalfEdgeIndex,
+}
+
+impl PartialEq for SplitCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SplitCandidate {}
+
+impl PartialOrd for SplitCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SplitCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.length.total_cmp(&other.length).then_with(|| other.he.0.cmp(&self.he.0))
+    }
+}
+
+/// Area-weighted normal of a polygon (Newell's method); zero for degenerate polygons
+fn polygon_normal(corners: &[DVec3]) -> DVec3 {
+    (0..corners.len())
+        .map(|i| corners[i].cross(corners[(i + 1) % corners.len()]))
+        .sum::<DVec3>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat `size` x `size` grid of unit squares in the XZ plane, each cut into two triangles
+    fn triangle_grid(size: usize) -> HalfEdgeMesh {
+        let row = size as u32 + 1;
+        let vertex_coords: Vec<f32> = (0..row)
+            .flat_map(|z| (0..row).flat_map(move |x| [x as f32, 0.0, z as f32]))
+            .collect();
+        let polygons: Vec<Vec<u32>> = (0..size as u32)
+            .flat_map(|z| (0..size as u32).map(move |x| z * row + x))
+            .flat_map(|corner| [vec![corner, corner + row, corner + row + 1], vec![corner, corner + row + 1, corner + 1]])
+            .collect();
+        HalfEdgeMesh::from_polygons(&vertex_coords, &polygons).unwrap()
+    }
+
+    #[test]
+    fn split_cube_edge_cuts_a_triangle_off_each_side() {
+        let mut cube = HalfEdgeMesh::create_cube(1.0);
+        let (faces, triangles) = (cube.faces.len(), cube.to_mesh().face_count());
+        let he = HalfEdgeIndex(0);
+        let (source, target) = (cube.half_edge_source(he), cube.half_edge(he).target_vertex_index);
+
+        let midpoint = cube.split_edge(he);
+
+        assert!(cube.validate().is_ok());
+        assert_eq!(cube.faces.len(), faces + 2);
+        assert_eq!(cube.to_mesh().face_count(), triangles + 2);
+        // Both quads beside the edge keep four corners, each next to a new triangle
+        let corner_counts: Vec<usize> = cube.vertex_faces(midpoint).into_iter().map(|face| cube.face_vertices(face).len()).collect();
+        assert_eq!(corner_counts.iter().filter(|&&corners| corners == 3).count(), 2);
+        assert_eq!(corner_counts.iter().filter(|&&corners| corners == 4).count(), 2);
+        let (a, b) = (cube.vertex(source).position.vec3, cube.vertex(target).position.vec3);
+        assert!(((a + b) * 0.5 - cube.vertex(midpoint).position.vec3).length() < 1e-6);
+        assert!(cube.is_closed());
+
+        // On the border of a single quad there is only one face to split
+        let mut plane = HalfEdgeMesh::create_plane(1.0);
+        let border = (0..plane.half_edges.len()).map(HalfEdgeIndex)
+            .find(|&he| plane.half_edge(he).face_index.is_some())
+            .unwrap();
+        plane.split_edge(border);
+        assert!(plane.validate().is_ok());
+        assert_eq!(plane.faces.len(), 2);
+        assert_eq!(plane.to_mesh().face_count(), 3);
+    }
+
+    #[test]
+    fn collapse_on_subdivided_plane_drops_counts() {
+        let live = |mesh: &HalfEdgeMesh| (
+            (0..mesh.vertices.len()).filter(|&v| !mesh.is_vertex_removed(VertexIndex(v))).count(),
+            mesh.live_face_count(),
+        );
+        let collapsible = |mesh: &HalfEdgeMesh, interior: bool| (0..mesh.half_edges.len()).map(HalfEdgeIndex)
+            .find(|&he| !mesh.is_half_edge_removed(he)
+                && mesh.half_edge(he).face_index.is_some()
+                && mesh.half_edge(he).twin_index.is_some() == interior
+                && mesh.check_link_condition(he).is_ok())
+            .unwrap();
+
+        // Inside the grid both triangles on the edge go; on its border only the one
+        let mut mesh = triangle_grid(4);
+        let (vertices, faces) = live(&mesh);
+        mesh.collapse_edge(collapsible(&mesh, true)).unwrap();
+        assert!(mesh.validate().is_ok());
+        assert_eq!(live(&mesh), (vertices - 1, faces - 2));
+        mesh.collapse_edge(collapsible(&mesh, false)).unwrap();
+        assert!(mesh.validate().is_ok());
+        assert_eq!(live(&mesh), (vertices - 2, faces - 3));
+
+        // Quads beside the edge lose a side but stay
+        let mut quads = HalfEdgeMesh::from_quad_grid(3, 3, 1.0);
+        let (vertices, faces) = live(&quads);
+        quads.collapse_edge(collapsible(&quads, true)).unwrap();
+        assert!(quads.validate().is_ok());
+        assert_eq!(live(&quads), (vertices - 1, faces));
+    }
+
+    #[test]
+    fn split_edge_extends_attribute_channels() {
+        let mut mesh = HalfEdgeMesh::create_cube(2.0);
+        let source = mesh.half_edge_source(HalfEdgeIndex(0));
+        mesh.add_attribute(AttributeDomain::Vertex, "mask", 0.0);
+        mesh.attribute_mut(AttributeDomain::Vertex, "mask").unwrap()[source.0] = 1.0;
+        mesh.add_attribute(AttributeDomain::Face, "material", 2.0);
+
+        let vertex = mesh.split_edge(HalfEdgeIndex(0));
+
+        let mask = mesh.attribute(AttributeDomain::Vertex, "mask").unwrap();
+        assert_eq!(mask.len(), mesh.vertices.len());
+        assert_eq!(mask[vertex.0], 0.5);
+        let material = mesh.attribute(AttributeDomain::Face, "material").unwrap();
+        assert_eq!(material.len(), mesh.faces.len());
+        assert!(material.iter().all(|&value| value == 2.0));
+    }
+
+    #[test]
+    fn compact_after_collapses_leaves_valid_indices() {
+        let mut mesh = triangle_grid(4);
+        let (vertices, faces) = (mesh.vertices.len(), mesh.faces.len());
+
+        for _ in 0..3 {
+            let he = (0..mesh.half_edges.len()).map(HalfEdgeIndex)
+                .find(|&idx| !mesh.is_half_edge_removed(idx) && mesh.check_link_condition(idx).is_ok())
+                .unwrap();
+            mesh.collapse_edge(he).unwrap();
+        }
+        assert!(mesh.has_removed_elements());
+
+        mesh.compact();
+
+        assert!(!mesh.has_removed_elements());
+        mesh.validate().unwrap();
+        assert_eq!(mesh.vertices.len(), vertices - 3);
+        assert!(mesh.faces.len() < faces);
+        let rendered = mesh.to_mesh();
+        assert!(rendered.face_indices.iter().all(|&index| (index as usize) < rendered.vertex_count()));
+    }
+
+    #[test]
+    fn extrude_adjacent_cube_faces() {
+        let mut mesh = HalfEdgeMesh::create_cube(2.0);
+        let first = FaceIndex(0);
+        let seed = mesh.face(first).seed_half_edge;
+        let second = mesh.half_edge(mesh.half_edge(seed).twin_index.unwrap()).face_index.unwrap();
+        let volume = mesh.to_mesh().signed_volume();
+
+        let walls = mesh.extrude_faces(&HashSet::from([first, second]), 1.0);
+
+        // The two faces share one edge, leaving a border of six
+        assert_eq!(walls.len(), 6);
+        mesh.validate().unwrap();
+        assert!(mesh.is_closed());
+        assert_eq!(mesh.euler_characteristic(), 2);
+        assert!(walls.iter().all(|&wall| mesh.face_vertices(wall).len() == 4));
+        // Every lifted corner is outside the original cube
+        for corner in [first, second].into_iter().flat_map(|face| mesh.face_vertices(face)) {
+            let p = mesh.vertex(corner).position.vec3;
+            assert!(p.x.abs().max(p.y.abs()).max(p.z.abs()) > 1.5);
+        }
+        assert!(mesh.to_mesh().signed_volume() > volume + 4.0);
+
+        // A whole closed surface just moves out, leaving its old corners behind
+        let mut cube = HalfEdgeMesh::create_cube(2.0);
+        let all: HashSet<FaceIndex> = (0..6).map(FaceIndex).collect();
+        assert!(cube.extrude_faces(&all, 1.0).is_empty());
+        cube.validate().unwrap();
+        cube.compact();
+        assert_eq!(cube.vertices.len(), 8);
+    }
+
+    #[test]
+    fn delete_cube_face_leaves_one_boundary_loop() {
+        let mut mesh = HalfEdgeMesh::create_cube(2.0);
+        assert!(mesh.boundary_loops().is_empty());
+
+        mesh.delete_face(FaceIndex(0));
+
+        assert!(!mesh.is_closed());
+        let loops = mesh.boundary_loops();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 4);
+        mesh.validate().unwrap();
+        assert!((0..mesh.vertices.len()).all(|v| mesh.vertex(VertexIndex(v)).seed_half_edge.is_some()));
+    }
+
+    #[test]
+    fn quad_grid_counts() {
+        let grid = HalfEdgeMesh::from_quad_grid(2, 2, 1.0);
+
+        grid.validate().unwrap();
+        assert_eq!(grid.faces.len(), 4);
+        assert!((0..4).all(|face| grid.face_vertices(FaceIndex(face)).len() == 4));
+        assert_eq!(grid.vertices.len(), 9);
+        let loops = grid.boundary_loops();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 8);
+    }
+
+    #[test]
+    fn cube_dual_is_octahedron() {
+        let cube = HalfEdgeMesh::create_cube(2.0);
+        let dual = cube.dual();
+        assert_eq!(dual.vertices.len(), 6);
+        assert_eq!(dual.faces.len(), 8);
+        dual.validate().unwrap();
+        assert!((0..8).all(|face| dual.face_vertices(FaceIndex(face)).len() == 3));
+        // Same winding as the input, so it still faces outward
+        assert!(dual.to_mesh().signed_volume() > 0.0);
+
+        let dual_of_dual = dual.dual();
+        assert_eq!((dual_of_dual.vertices.len(), dual_of_dual.faces.len()), (8, 6));
+    }
+
+    #[test]
+    fn cube_keeps_quads() {
+        let cube = HalfEdgeMesh::create_cube(1.0);
+        let quads = cube.to_quad_mesh();
+        assert_eq!(quads.quad_count(), 6);
+        assert_eq!(quads.quad_indices.len(), 24);
+        assert_eq!(quads.triangle_count(), 0);
+
+        // Split back into triangles, the quads wind the same way as `to_mesh`
+        let mut mesh = Mesh::new();
+        mesh.vertex_coords = quads.vertex_coords.clone();
+        for quad in quads.quad_indices.chunks(4) {
+            mesh.add_triangle(quad[0], quad[1], quad[2]);
+            mesh.add_triangle(quad[0], quad[2], quad[3]);
+        }
+        assert!((mesh.signed_volume() - cube.to_mesh().signed_volume()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn collapse_short_edges_removes_tiny_edges() {
+        // Three vertices of a 4x4 grid pulled almost onto a neighbour: two inside, one on the border
+        let mut mesh = triangle_grid(4);
+        mesh.vertices[7].position.vec3.x -= 0.95;
+        mesh.vertices[18].position.vec3.z -= 0.97;
+        mesh.vertices[4].position.vec3.x -= 0.98;
+        let triangles = mesh.to_mesh().face_count();
+
+        assert_eq!(mesh.collapse_short_edges(0.1), 3);
+
+        mesh.validate().unwrap();
+        assert!((0..mesh.half_edges.len())
+            .map(HalfEdgeIndex)
+            .filter(|&half_edge| !mesh.is_half_edge_removed(half_edge))
+            .all(|half_edge| mesh.half_edge_length(half_edge) >= 0.1));
+        // Two triangles go with each interior edge, one with the border edge
+        assert_eq!(mesh.to_mesh().face_count(), triangles - 5);
+        mesh.compact();
+        mesh.validate().unwrap();
+        assert_eq!(mesh.vertices.len(), 25 - 3);
+    }
+
+    #[test]
+    fn split_long_edges_subdivides_below_threshold() {
+        let mut plane = HalfEdgeMesh::create_plane(10.0);
+        assert!(plane.split_long_edges(1.0) > 0);
+        plane.validate().unwrap();
+        assert!((0..plane.half_edges.len()).all(|half_edge| plane.half_edge_length(HalfEdgeIndex(half_edge)) <= 1.0));
+
+        // Triangle faces stay triangles
+        let mut grid = triangle_grid(2);
+        let triangles = grid.to_mesh().face_count();
+        assert!(grid.split_long_edges(0.3) > 0);
+        grid.validate().unwrap();
+        assert!((0..grid.half_edges.len()).all(|half_edge| grid.half_edge_length(HalfEdgeIndex(half_edge)) <= 0.3));
+        assert!((0..grid.faces.len()).all(|face| grid.face_vertices(FaceIndex(face)).len() == 3));
+        assert!(grid.to_mesh().face_count() > triangles);
+    }
+
+    #[test]
+    fn compact_after_delete_face_shrinks_arrays() {
+        let mut cube = HalfEdgeMesh::create_cube(1.0);
+        let vertices = cube.vertices.len();
+        cube.delete_face(FaceIndex(2));
+
+        let map = cube.compact();
+
+        cube.validate().unwrap();
+        assert_eq!(cube.faces.len(), 5);
+        // Every corner still belongs to another face
+        assert_eq!(cube.vertices.len(), vertices);
+        assert_eq!(map.face(FaceIndex(2)), None);
+        assert_eq!(map.face(FaceIndex(3)), Some(FaceIndex(2)));
+
+        // Deleting a triangle leaves its now unused corner behind, which compaction drops
+        let mut grid = triangle_grid(1);
+        grid.delete_face(FaceIndex(0));
+        let map = grid.compact();
+        grid.validate().unwrap();
+        assert_eq!(grid.vertices.len(), 3);
+        assert_eq!(map.vertex(VertexIndex(2)), None);
+        assert_eq!(map.vertex(VertexIndex(3)), Some(VertexIndex(2)));
+    }
+
+    #[test]
+    fn euler_characteristic_and_genus() {
+        let cube = HalfEdgeMesh::create_cube(1.0);
+        assert_eq!(cube.euler_characteristic(), 2);
+        assert_eq!(cube.genus(), Some(0));
+
+        // 8 x 6 quad torus around the Y axis
+        let (rings, sides) = (8, 6);
+        let vertex_coords: Vec<f32> = (0..rings)
+            .flat_map(|ring| (0..sides).map(move |side| (ring, side)))
+            .flat_map(|(ring, side)| {
+                let u = ring as f32 / rings as f32 * std::f32::consts::TAU;
+                let v = side as f32 / sides as f32 * std::f32::consts::TAU;
+                let radius = 2.0 + 0.5 * v.cos();
+                [radius * u.cos(), 0.5 * v.sin(), radius * u.sin()]
+            })
+            .collect();
+        let corner = |ring: usize, side: usize| ((ring % rings) * sides + side % sides) as u32;
+        let polygons: Vec<Vec<u32>> = (0..rings)
+            .flat_map(|i| (0..sides).map(move |j| vec![corner(i, j), corner(i, j + 1), corner(i + 1, j + 1), corner(i + 1, j)]))
+            .collect();
+        let torus = HalfEdgeMesh::from_polygons(&vertex_coords, &polygons).unwrap();
+        torus.validate().unwrap();
+        assert_eq!(torus.euler_characteristic(), 0);
+        assert_eq!(torus.genus(), Some(1));
+
+        // Open meshes have no genus
+        let plane = HalfEdgeMesh::create_plane(1.0);
+        assert_eq!(plane.euler_characteristic(), 1);
+        assert_eq!(plane.genus(), None);
+    }
+
+    #[test]
+    fn flip_orientation_inverts_cube() {
+        let mut cube = HalfEdgeMesh::create_cube(2.0);
+        assert!((cube.to_mesh().signed_volume() - 8.0).abs() < 1e-4);
+
+        cube.flip_orientation();
+
+        cube.validate().unwrap();
+        assert!((cube.to_mesh().signed_volume() + 8.0).abs() < 1e-4);
+
+        // Boundary half-edges are flipped along with the faces
+        let mut plane = HalfEdgeMesh::create_plane(1.0);
+        plane.add_boundary_half_edges();
+        plane.flip_orientation();
+        plane.validate().unwrap();
+    }
+
+    #[test]
+    fn poke_cube_face_into_four_triangles() {
+        let mut cube = HalfEdgeMesh::create_cube(2.0);
+        let faces = cube.faces.len();
+
+        let center = cube.poke_face(FaceIndex(0));
+
+        cube.validate().unwrap();
+        assert_eq!(cube.faces.len(), faces + 3);
+        for face in [0, faces, faces + 1, faces + 2] {
+            let corners = cube.face_vertices(FaceIndex(face));
+            assert_eq!(corners.len(), 3);
+            assert!(corners.contains(&center));
+        }
+        assert_eq!(cube.euler_characteristic(), 2);
+        assert!((cube.to_mesh().signed_volume() - 8.0).abs() < 1e-4);
+
+        // Faces on a boundary too
+        let mut plane = HalfEdgeMesh::create_plane(1.0);
+        plane.poke_face(FaceIndex(0));
+        plane.validate().unwrap();
+    }
+
+    #[test]
+    fn cube_corner_has_three_faces() {
+        let cube = HalfEdgeMesh::create_cube(1.0);
+        for vertex in (0..cube.vertices.len()).map(VertexIndex) {
+            let faces = cube.vertex_faces(vertex);
+            assert_eq!(faces.len(), 3);
+            assert!(faces.iter().all(|&face| cube.face_vertices(face).contains(&vertex)));
+        }
+
+        // Boundary corners skip the missing faces
+        let grid = triangle_grid(2);
+        assert_eq!(grid.vertex_faces(VertexIndex(0)).len(), 2);
+        assert_eq!(grid.vertex_faces(VertexIndex(4)).len(), 6);
+    }
+
+    #[test]
+    fn cancelled_split_leaves_valid_partial_mesh() {
+        let full = triangle_grid(8).split_long_edges(0.2);
+
+        // Cancelling on the third report stops with some, but not all, of the splits done
+        let mut mesh = triangle_grid(8);
+        let mut reports = Vec::new();
+        let result = mesh.split_long_edges_with_progress(0.2, &mut |fraction| {
+            reports.push(fraction);
+            reports.len() < 3
+        });
+        assert!(result.is_err());
+        assert!(mesh.validate().is_ok());
+        let rest = mesh.split_long_edges(0.2);
+        assert!(rest > 0 && rest < full, "{rest} of {full}");
+
+        // An uncancelled run reports rising fractions up to 1 and does every split
+        let mut reports = Vec::new();
+        let mut mesh = triangle_grid(8);
+        assert_eq!(mesh.split_long_edges_with_progress(0.2, &mut |fraction| { reports.push(fraction); true }), Ok(full));
+        assert!(reports.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(reports.last(), Some(&1.0));
+    }
+
+    #[test]
+    fn fill_hole_closes_deleted_cube_face() {
+        let mut mesh = HalfEdgeMesh::create_cube(1.0);
+        let volume = mesh.to_mesh().signed_volume();
+        mesh.delete_face(FaceIndex(2));
+        assert!(!mesh.is_closed());
+
+        let loops = mesh.boundary_loops();
+        assert_eq!(loops.len(), 1);
+        assert_eq!(mesh.fill_hole(loops[0][0]).len(), 2);
+        assert!(mesh.is_closed());
+        assert!(mesh.validate().is_ok());
+        assert!((mesh.to_mesh().signed_volume() - volume).abs() < 1e-5);
+    }
+
+    #[test]
+    fn fill_hole_triangulates_concave_loop() {
+        // Deleting an L of triangles from the middle of the grid leaves a concave hole,
+        // which a fan from the wrong corner would overfill
+        let mut mesh = triangle_grid(4);
+        for face in [10, 11, 12, 13, 18, 19] {
+            mesh.delete_face(FaceIndex(face));
+        }
+        let hole = mesh.boundary_loops().into_iter().min_by_key(|boundary| boundary.len()).unwrap();
+        mesh.fill_hole(hole[0]);
+        assert!(mesh.validate().is_ok());
+        assert_eq!(mesh.boundary_loops().len(), 1);
+        let filled = mesh.to_mesh();
+        let corner = |i: u32| glam::Vec3::from_slice(&filled.vertex_coords[3 * i as usize..3 * i as usize + 3]);
+        let area: f32 = filled.face_indices.chunks(3)
+            .map(|tri| (corner(tri[1]) - corner(tri[0])).cross(corner(tri[2]) - corner(tri[0])).length() / 2.0)
+            .sum();
+        assert!((area - 16.0).abs() < 1e-4, "{area}");
+    }
+
+    #[test]
+    fn linked_faces_select_one_of_two_cubes() {
+        let mut merged = Mesh::create_cube(1.0);
+        let mut other = Mesh::create_cube(1.0);
+        for position in other.vertex_coords.chunks_exact_mut(3) {
+            position[0] += 5.0;
+        }
+        merged.append(&other);
+        let mesh = HalfEdgeMesh::from_mesh(&merged);
+
+        let first = mesh.linked_faces(FaceIndex(0));
+        let second = mesh.linked_faces(FaceIndex(mesh.faces.len() - 1));
+        assert_eq!(first.len(), 12);
+        assert_eq!(second.len(), 12);
+        assert!(first.is_disjoint(&second));
+        for face in &first {
+            assert!(mesh.face_vertices(*face).iter().all(|vertex| mesh.vertex(*vertex).position.vec3.x < 2.0));
+        }
+    }
+
+    #[test]
+    fn simplify_to_ratio_halves_sphere() {
+        let sphere = HalfEdgeMesh::from_mesh(&Mesh::create_sphere(1.0, 32, 24));
+        let faces = sphere.faces.len() as f32;
+        let volume = sphere.to_mesh().signed_volume();
+
+        let half = sphere.simplify_to_ratio(0.5);
+        assert!(half.validate().is_ok());
+        // Each collapse removes two triangles, so it can land a face either side of the target
+        assert!((half.faces.len() as f32 - faces * 0.5).abs() <= 2.0, "{} of {faces}", half.faces.len());
+        assert!((half.to_mesh().signed_volume() - volume).abs() / volume < 0.03);
+
+        // An error budget of zero removes nothing that would change the shape of a sphere
+        assert_eq!(sphere.simplify_to_error(0.0).faces.len(), sphere.faces.len());
+    }
+
+    #[test]
+    fn merge_split_cube_corner_restores_twins() {
+        // A cube with corner 6 split in two: one of its three faces uses a coincident copy
+        let cube = HalfEdgeMesh::create_cube(2.0);
+        let corner = VertexIndex(6);
+        let mut vertex_coords: Vec<f32> = cube.vertices.iter().flat_map(|vertex| <[f32; 3]>::from(vertex.position)).collect();
+        vertex_coords.extend(<[f32; 3]>::from(cube.vertex(corner).position));
+        let copy = VertexIndex(cube.vertices.len());
+        let mut polygons: Vec<Vec<u32>> = (0..cube.faces.len())
+            .map(|face| cube.face_vertices(FaceIndex(face)).iter().map(|vertex| vertex.0 as u32).collect())
+            .collect();
+        let split_face = polygons.iter().position(|polygon| polygon.contains(&(corner.0 as u32))).unwrap();
+        for index in polygons[split_face].iter_mut().filter(|index| **index == corner.0 as u32) {
+            *index = copy.0 as u32;
+        }
+        let mut mesh = HalfEdgeMesh::from_polygons(&vertex_coords, &polygons).unwrap();
+        // The two edges either side of the split corner lost their twins
+        assert_eq!(mesh.half_edges.iter().filter(|he| he.twin_index.is_none()).count(), 4);
+
+        // Merging a vertex with itself or with a neighbour on the same face is refused
+        assert!(mesh.merge_vertices(corner, corner).is_err());
+        assert!(mesh.merge_vertices(VertexIndex(0), VertexIndex(1)).is_err());
+
+        mesh.merge_vertices(corner, copy).unwrap();
+        assert!(mesh.validate().is_ok());
+        assert!((0..mesh.faces.len())
+            .flat_map(|face| mesh.face_half_edges(FaceIndex(face)))
+            .all(|he| mesh.half_edge(he).twin_index.is_some()));
+        assert_eq!(mesh.vertex_faces(corner).len(), 3);
+        assert!(mesh.is_closed());
+    }
+
+    #[test]
+    fn cube_face_normals_are_outward_axes() {
+        let cube = HalfEdgeMesh::create_cube(2.0);
+        let mut axes = Vec::new();
+        for face in (0..cube.faces.len()).map(FaceIndex) {
+            let normal = cube.face_normal(face);
+            assert!((normal.length() - 1.0).abs() < 1e-6);
+            let components = [normal.x, normal.y, normal.z];
+            assert_eq!(components.iter().filter(|component| component.abs() > 0.999).count(), 1, "{components:?}");
+            // Pointing away from the cube's center, towards the face
+            let corner = cube.vertex(cube.face_vertices(face)[0]).position.vec3;
+            assert!(normal.dot(&corner) > 0.0);
+            axes.push(components.map(|component| component.round() as i32));
+        }
+        axes.sort();
+        axes.dedup();
+        assert_eq!(axes.len(), 6);
+
+        // A face squashed to a point has no normal
+        let mut squashed = HalfEdgeMesh::create_cube(2.0);
+        for vertex in squashed.face_vertices(FaceIndex(0)) {
+            squashed.vertex_mut(vertex).position = Point3::new(1.0, 1.0, 1.0);
+        }
+        let normal = squashed.face_normal(FaceIndex(0));
+        assert_eq!([normal.x, normal.y, normal.z], [0.0; 3]);
+    }
+
+    #[test]
+    fn triangulate_one_cube_quad() {
+        let mut cube = HalfEdgeMesh::create_cube(2.0);
+        cube.add_attribute(AttributeDomain::Face, "material", 0.0);
+        cube.attribute_mut(AttributeDomain::Face, "material").unwrap()[2] = 7.0;
+        let before = cube.to_mesh();
+
+        let faces = cube.triangulate_face(FaceIndex(2));
+
+        assert_eq!(faces.len(), 2);
+        assert_eq!(faces[0], FaceIndex(2));
+        assert!(cube.validate().is_ok());
+        assert!(cube.is_closed());
+        let corner_counts: Vec<usize> = (0..cube.faces.len()).map(|face| cube.face_vertices(FaceIndex(face)).len()).collect();
+        assert_eq!(corner_counts.iter().filter(|&&corners| corners == 4).count(), 5);
+        assert_eq!(corner_counts.iter().filter(|&&corners| corners == 3).count(), 2);
+        assert_eq!(cube.attribute(AttributeDomain::Face, "material").unwrap()[faces[1].0], 7.0);
+        // Rendered, it's the same cube
+        let after = cube.to_mesh();
+        assert_eq!(after.face_count(), before.face_count());
+        assert!((after.signed_volume() - before.signed_volume()).abs() < 1e-4);
+
+        // A triangle is left alone
+        assert_eq!(cube.triangulate_face(faces[1]), vec![faces[1]]);
+    }
+
+    #[test]
+    fn from_mesh_keeps_triangle_winding() {
+        // A tetrahedron wound counter-clockwise seen from outside, as an OBJ file would give it
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nf 1 3 2\nf 1 2 4\nf 1 4 3\nf 2 3 4\n";
+        let imported = crate::obj_import::parse_obj_to_mesh(obj).unwrap();
+        assert!(imported.signed_volume() > 0.0);
+
+        for mesh in [Mesh::create_cube(2.0), Mesh::create_sphere(1.0, 16, 8), imported] {
+            let round_trip = HalfEdgeMesh::from_mesh(&mesh).to_mesh();
+            assert!((round_trip.signed_volume() - mesh.signed_volume()).abs() < 1e-4);
+            // The same triangles, each with its corners in the same cyclic order
+            let canonical = |mesh: &Mesh| {
+                let mut triangles: Vec<[u32; 3]> = mesh.face_indices.chunks_exact(3)
+                    .map(|tri| {
+                        let first = (0..3).min_by_key(|&k| tri[k]).unwrap();
+                        [tri[first], tri[(first + 1) % 3], tri[(first + 2) % 3]]
+                    })
+                    .collect();
+                triangles.sort();
+                triangles
+            };
+            assert_eq!(canonical(&round_trip), canonical(&mesh));
+        }
+    }
+}
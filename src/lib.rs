@@ -1,7 +1,10 @@
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
 use wasm_bindgen::prelude::*;
 
 mod algebra;
 mod mesh;
+mod quad_mesh;
 mod half_edge_mesh;
 mod transform;
 mod transformable;
@@ -15,26 +18,47 @@ mod model;
 mod visitor;
 mod render_instance;
 mod obj_import;
+mod obj_export;
+mod mesh_selection;
+mod mesh_adjacency;
+mod scene_format;
+mod scene_event;
+mod aabb_tree;
+mod curve;
 
 pub use algebra::Vec3;
-pub use mesh::Mesh;
-pub use half_edge_mesh::{HalfEdgeMesh, Vertex, HalfEdge, Face, VertexIndex, HalfEdgeIndex, FaceIndex};
+pub use mesh::{Mesh, RepairReport};
+pub use quad_mesh::QuadMesh;
+pub use half_edge_mesh::{HalfEdgeMesh, Vertex, HalfEdge, Face, VertexIndex, HalfEdgeIndex, FaceIndex, AttributeDomain, CompactMap, PROGRESS_INTERVAL};
 pub use scene::SceneAPI;
 pub use scene_graph::{SceneGraphNode, SceneGraphChild};
 pub use render_instance::{RenderInstance, MeshId};
 pub use transform::Transform;
 pub use transformable::Transformable;
 pub use material::Material;
-pub use geometry::Point3;
-pub use model_wrapper::ModelWrapper;
+pub use geometry::{Point3, Frustum, Intersection};
+pub use model_wrapper::{ModelWrapper, NormalMode};
 pub use model::{ToMesh, ModelEntry};
+pub use mesh_selection::MeshSelection;
+pub use mesh_adjacency::MeshAdjacency;
+pub use curve::CatmullRomSpline;
+pub use scene_event::SceneEvent;
+pub use visitor::{SceneVisitor, AsyncVisitor, Adjacency, Traversal, half_edge_mesh_traverse, half_edge_mesh_bfs};
+pub use algorithms::triangulate_polygon;
 
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = console)]
     pub fn log(s: &str);
 }
 
+// Native builds (tools, tests) have no JS console to log to
+#[cfg(not(target_arch = "wasm32"))]
+pub fn log(s: &str) {
+    eprintln!("{s}");
+}
+
 #[macro_export]
 macro_rules! console_log {
     ($($t:tt)*) => ($crate::log(&format_args!($($t)*).to_string()))
@@ -5,4 +5,73 @@ pub struct Material {
     pub color: [f32; 3],
     pub metalness: f32,
     pub roughness: f32,
+    // Texture ids/names, only stored and saved; the renderer resolves them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub albedo_texture: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normal_texture: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roughness_texture: Option<String>,
+}
+
+impl Material {
+    /// Componentwise blend towards `other`; `t` is clamped to [0, 1].
+    /// Textures can't be blended, so they switch over to `other`'s halfway.
+    pub fn lerp(&self, other: &Material, t: f32) -> Material {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        let textures = if t < 0.5 { self } else { other };
+        Material {
+            color: std::array::from_fn(|i| mix(self.color[i], other.color[i])),
+            metalness: mix(self.metalness, other.metalness),
+            roughness: mix(self.roughness, other.roughness),
+            albedo_texture: textures.albedo_texture.clone(),
+            normal_texture: textures.normal_texture.clone(),
+            roughness_texture: textures.roughness_texture.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn material(value: f32) -> Material {
+        Material {
+            color: [value; 3],
+            metalness: value,
+            roughness: 1.0 - value,
+            albedo_texture: None,
+            normal_texture: None,
+            roughness_texture: None,
+        }
+    }
+
+    #[test]
+    fn lerp_halfway_is_mid_gray() {
+        let (black, white) = (material(0.0), material(1.0));
+
+        let mid = black.lerp(&white, 0.5);
+        assert_eq!(mid.color, [0.5; 3]);
+        assert_eq!(mid.metalness, 0.5);
+        assert_eq!(mid.roughness, 0.5);
+
+        // Clamped to the ends
+        assert_eq!(black.lerp(&white, 3.0).color, [1.0; 3]);
+        assert_eq!(black.lerp(&white, -1.0).color, [0.0; 3]);
+    }
+
+    #[test]
+    fn texture_ids_round_trip_through_json() {
+        // Materials saved before textures existed still load, with no textures
+        let old: Material = serde_json::from_str(r#"{"color":[1,0,0],"metalness":0.5,"roughness":0.2}"#).unwrap();
+        assert!(old.albedo_texture.is_none());
+        assert!(!serde_json::to_string(&old).unwrap().contains("texture"));
+
+        let mut textured = old.clone();
+        textured.albedo_texture = Some("bricks_albedo".to_string());
+        let back: Material = serde_json::from_str(&serde_json::to_string(&textured).unwrap()).unwrap();
+        assert_eq!(back.albedo_texture.as_deref(), Some("bricks_albedo"));
+        assert!(back.normal_texture.is_none());
+    }
 }
@@ -1,4 +1,44 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use crate::{Point3, Transform, Vec3};
+use crate::algorithms::SplitMix64;
+
+/// Vertices closer than this are merged by `Mesh::sanitize`
+const SANITIZE_WELD_EPSILON: f32 = 1e-5;
+
+/// Vertices closer than this to the cutting plane of `Mesh::slice_by_plane` count as on it
+const SLICE_PLANE_EPSILON: f32 = 1e-6;
+
+/// A vertex of a sliced mesh: an original vertex, or where the plane cuts the edge between
+/// two (lower index first)
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum SliceVertex {
+    Original(u32),
+    Cut(u32, u32),
+}
+
+/// Most subdivision levels `Mesh::create_icosphere` applies (20 * 4^8, about 1.3M triangles)
+const ICOSPHERE_MAX_SUBDIVISIONS: u32 = 8;
+
+/// Fewest triangles a worker takes on in the parallel mesh operations
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_TRIANGLES: usize = 1 << 16;
+
+/// What `Mesh::sanitize` changed
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    pub invalid_triangles_removed: usize,   // Triangles with out-of-range indices
+    pub degenerate_triangles_removed: usize,
+    pub vertices_welded: usize,
+    pub faces_flipped: usize,
+}
+
+impl RepairReport {
+    /// True if the mesh needed no repairs
+    pub fn is_empty(&self) -> bool {
+        *self == RepairReport::default()
+    }
+}
 
 /// Flat, render/serialize-friendly mesh representation used throughout runtime.
 #[derive(Serialize, Deserialize, Clone)]
@@ -6,6 +46,16 @@ pub struct Mesh {
     pub vertex_coords: Vec<f32>,
     pub face_indices: Vec<u32>,
     pub normals: Option<Vec<f32>>, // optional, computed or supplied by caller
+    #[serde(default)]
+    pub uvs: Option<Vec<f32>>, // optional, 2 floats per vertex
+    #[serde(default)]
+    pub tangents: Option<Vec<f32>>, // optional, xyzw per vertex with handedness in w
+    #[serde(default)]
+    pub smoothing_groups: Option<Vec<u32>>, // optional, one per triangle; 0 is unsmoothed (OBJ `s off`)
+    #[serde(default)]
+    pub lines: Option<Vec<u32>>, // optional, vertex index pairs drawn as line segments (display only)
+    #[serde(default)]
+    pub points: Option<Vec<u32>>, // optional, vertex indices drawn as points (display only)
 }
 
 impl Mesh {
@@ -14,6 +64,11 @@ impl Mesh {
             vertex_coords: Vec::new(),
             face_indices: Vec::new(),
             normals: None,
+            uvs: None,
+            tangents: None,
+            smoothing_groups: None,
+            lines: None,
+            points: None,
         }
     }
 
@@ -27,6 +82,18 @@ impl Mesh {
         self.face_indices.extend_from_slice(&[i0, i1, i2]);
     }
 
+    /// Add a line segment between two vertices. Lines and points are drawn but never hit by
+    /// raycasts and don't count as faces.
+    #[inline]
+    pub fn add_line(&mut self, i0: u32, i1: u32) {
+        self.lines.get_or_insert_with(Vec::new).extend_from_slice(&[i0, i1]);
+    }
+
+    #[inline]
+    pub fn add_point(&mut self, i0: u32) {
+        self.points.get_or_insert_with(Vec::new).push(i0);
+    }
+
     #[inline]
     pub fn set_vertex(&mut self, i: usize, x: f32, y: f32, z: f32) {
         let base = i * 3;
@@ -45,6 +112,983 @@ impl Mesh {
         self.face_indices.len() / 3
     }
 
+    /// Approximate heap size of the mesh buffers in bytes
+    pub fn memory_estimate(&self) -> usize {
+        let channel_len = |channel: &Option<Vec<f32>>| channel.as_ref().map_or(0, |values| values.len());
+        let float_count = self.vertex_coords.len()
+            + channel_len(&self.normals)
+            + channel_len(&self.uvs)
+            + channel_len(&self.tangents);
+        let index_len = |channel: &Option<Vec<u32>>| channel.as_ref().map_or(0, |values| values.len());
+        let index_count = self.face_indices.len()
+            + index_len(&self.smoothing_groups)
+            + index_len(&self.lines)
+            + index_len(&self.points);
+        float_count * std::mem::size_of::<f32>() + index_count * std::mem::size_of::<u32>()
+    }
+
+    /// Axis-aligned bounding box of the vertices as (min, max), or None if the mesh is empty
+    pub fn bounding_box(&self) -> Option<(Point3, Point3)> {
+        let mut coords = self.vertex_coords.chunks_exact(3);
+        let first = coords.next()?;
+        let mut min = [first[0], first[1], first[2]];
+        let mut max = min;
+
+        for coord in coords {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(coord[axis]);
+                max[axis] = max[axis].max(coord[axis]);
+            }
+        }
+
+        Some((Point3::new(min[0], min[1], min[2]), Point3::new(max[0], max[1], max[2])))
+    }
+
+    /// Oriented bounding box as (center, axes, half-extents). The axes are the principal
+    /// directions of the vertex positions (eigenvectors of their covariance), longest spread
+    /// first, forming a right-handed frame. An empty mesh gives a zero-size box at the origin.
+    pub fn oriented_bounding_box(&self) -> (Point3, [Vec3; 3], [f32; 3]) {
+        let world_axes = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)];
+        let vertex_count = self.vertex_count();
+        if vertex_count == 0 {
+            return (Point3::new(0.0, 0.0, 0.0), world_axes, [0.0; 3]);
+        }
+
+        let mean = (0..vertex_count).fold(Vec3::new(0.0, 0.0, 0.0), |sum, i| sum + self.position(i))
+            * (1.0 / vertex_count as f32);
+        let mut covariance = [[0.0f32; 3]; 3];
+        for i in 0..vertex_count {
+            let d = self.position(i) - mean;
+            let d = [d.x, d.y, d.z];
+            for row in 0..3 {
+                for col in 0..3 {
+                    covariance[row][col] += d[row] * d[col] / vertex_count as f32;
+                }
+            }
+        }
+
+        let (_, [first, second, _]) = crate::algorithms::symmetric_eigen3(covariance);
+        let axes = [first, second, first.cross(&second).normalize()];
+
+        // Extent of the vertices along each axis, measured from the mean
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for i in 0..vertex_count {
+            let d = self.position(i) - mean;
+            for (axis, direction) in axes.iter().enumerate() {
+                let along = d.dot(direction);
+                min[axis] = min[axis].min(along);
+                max[axis] = max[axis].max(along);
+            }
+        }
+
+        let center = (0..3).fold(mean, |center, axis| center + axes[axis] * (0.5 * (min[axis] + max[axis])));
+        let half_extents = [0, 1, 2].map(|axis| 0.5 * (max[axis] - min[axis]));
+        (Point3 { vec3: center }, axes, half_extents)
+    }
+
+    /// Volume enclosed by the triangles, positive when they wind counter-clockwise seen from
+    /// outside. Only meaningful for closed meshes; a negative value means the mesh is inside out.
+    pub fn signed_volume(&self) -> f32 {
+        let p = |i: u32| {
+            let i = i as usize * 3;
+            glam::Vec3::new(self.vertex_coords[i], self.vertex_coords[i + 1], self.vertex_coords[i + 2])
+        };
+        self.face_indices.chunks_exact(3)
+            .map(|tri| p(tri[0]).dot(p(tri[1]).cross(p(tri[2]))))
+            .sum::<f32>() / 6.0
+    }
+
+    /// `n` points spread uniformly over the surface: triangles are picked with probability
+    /// proportional to their area, then a uniformly random point is taken inside each.
+    /// The same seed always gives the same points. Meshes without area give no points.
+    pub fn sample_surface(&self, n: usize, seed: u64) -> Vec<Point3> {
+        let corners = |tri: &[u32]| [0, 1, 2].map(|k| self.position(tri[k] as usize));
+        let mut total_area = 0.0;
+        let cumulative_areas: Vec<f32> = self.face_indices.chunks_exact(3)
+            .map(|tri| {
+                let [a, b, c] = corners(tri);
+                total_area += 0.5 * (b - a).cross(&(c - a)).length();
+                total_area
+            })
+            .collect();
+        if total_area <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut rng = SplitMix64::new(seed);
+        (0..n)
+            .map(|_| {
+                let target = rng.next_f32() * total_area;
+                let face = cumulative_areas.partition_point(|&area| area <= target).min(cumulative_areas.len() - 1);
+                let [a, b, c] = corners(&self.face_indices[3 * face..3 * face + 3]);
+
+                // Fold points from the far half of the parallelogram back into the triangle
+                let (mut u, mut v) = (rng.next_f32(), rng.next_f32());
+                if u + v > 1.0 {
+                    (u, v) = (1.0 - u, 1.0 - v);
+                }
+                Point3 { vec3: a + (b - a) * u + (c - a) * v }
+            })
+            .collect()
+    }
+
+    /// Bake a transform into the vertex positions (and normals, via the inverse transpose).
+    /// Mirroring transforms also flip the triangle winding so faces stay outward-facing.
+    pub fn apply_transform(&mut self, transform: &Transform) {
+        for coord in self.vertex_coords.chunks_exact_mut(3) {
+            let p = transform.transform_point(glam::Vec3::new(coord[0], coord[1], coord[2]));
+            coord.copy_from_slice(&p.to_array());
+        }
+
+        if let Some(normals) = &mut self.normals {
+            let normal_matrix = transform.matrix().inverse().transpose();
+            for normal in normals.chunks_exact_mut(3) {
+                let n = normal_matrix
+                    .transform_vector3(glam::Vec3::new(normal[0], normal[1], normal[2]))
+                    .normalize_or_zero();
+                normal.copy_from_slice(&n.to_array());
+            }
+        }
+
+        if let Some(tangents) = &mut self.tangents {
+            // Tangents follow the surface, so they take the plain linear part; a mirror flips handedness
+            let handedness = transform.matrix().determinant().signum();
+            for tangent in tangents.chunks_exact_mut(4) {
+                let t = transform
+                    .matrix()
+                    .transform_vector3(glam::Vec3::new(tangent[0], tangent[1], tangent[2]))
+                    .normalize_or_zero();
+                tangent.copy_from_slice(&[t.x, t.y, t.z, tangent[3] * handedness]);
+            }
+        }
+
+        if transform.matrix().determinant() < 0.0 {
+            for tri in self.face_indices.chunks_exact_mut(3) {
+                tri.swap(1, 2);
+            }
+        }
+    }
+
+    /// Snap every vertex to a grid of `2^bits` steps per axis spanning the bounding box, so
+    /// positions can be stored as `bits`-bit integers. Returns the grid `(origin, scale)`: a
+    /// vertex stored as integer `q` (per axis, `0 <= q < 2^bits`) is reconstructed as
+    /// `origin + q * scale`, and the quantized positions written here are exactly that. The
+    /// same scale is used on every axis, set by the longest side of the box, so each position
+    /// moves by at most `scale / 2` per axis. `bits` is clamped to 1..=24, the precision an f32
+    /// holds exactly. An empty mesh gives a zero origin and scale.
+    pub fn quantize_positions(&mut self, bits: u8) -> (Point3, f32) {
+        let Some((min, max)) = self.bounding_box() else {
+            return (Point3::new(0.0, 0.0, 0.0), 0.0);
+        };
+        let extent = (max - min).vec3;
+        let steps = ((1u32 << bits.clamp(1, 24)) - 1) as f32;
+        let scale = extent.x.max(extent.y).max(extent.z) / steps;
+        if scale <= 0.0 {
+            return (min, 0.0);
+        }
+
+        let origin = [min.vec3.x, min.vec3.y, min.vec3.z];
+        for coord in self.vertex_coords.chunks_exact_mut(3) {
+            for axis in 0..3 {
+                let q = ((coord[axis] - origin[axis]) / scale).round().clamp(0.0, steps);
+                coord[axis] = origin[axis] + q * scale;
+            }
+        }
+        (min, scale)
+    }
+
+    /// Append another mesh's geometry, offsetting its indices.
+    /// Normals, UVs and tangents are each kept only if both meshes have them; lines and points
+    /// are kept from either mesh.
+    pub fn append(&mut self, other: &Mesh) {
+        let had_vertices = !self.vertex_coords.is_empty();
+        let base_vertex = self.vertex_count() as u32;
+
+        fn merge<T: Clone>(channel: Option<Vec<T>>, other_channel: &Option<Vec<T>>, had_vertices: bool) -> Option<Vec<T>> {
+            match (channel, other_channel) {
+                (Some(mut values), Some(other_values)) => {
+                    values.extend_from_slice(other_values);
+                    Some(values)
+                }
+                // An empty mesh adopts the other mesh's channel
+                (None, Some(other_values)) if !had_vertices => Some(other_values.clone()),
+                _ => None,
+            }
+        }
+        self.normals = merge(self.normals.take(), &other.normals, had_vertices);
+        self.uvs = merge(self.uvs.take(), &other.uvs, had_vertices);
+        self.tangents = merge(self.tangents.take(), &other.tangents, had_vertices);
+        self.smoothing_groups = merge(self.smoothing_groups.take(), &other.smoothing_groups, had_vertices);
+
+        for (indices, other_indices) in [(&mut self.lines, &other.lines), (&mut self.points, &other.points)] {
+            if let Some(other_indices) = other_indices {
+                indices.get_or_insert_with(Vec::new).extend(other_indices.iter().map(|i| i + base_vertex));
+            }
+        }
+
+        self.vertex_coords.extend_from_slice(&other.vertex_coords);
+        self.face_indices.extend(other.face_indices.iter().map(|i| i + base_vertex));
+    }
+
+    /// Compute smooth per-vertex normals by accumulating area-weighted face normals
+    /// Vertices not referenced by any triangle get a zero normal
+    pub fn compute_smooth_normals(&mut self) {
+        let mut normals = vec![0.0f32; self.vertex_coords.len()];
+        for tri in self.face_indices.chunks_exact(3) {
+            Self::accumulate_face_normal(&self.vertex_coords, tri, &mut normals);
+        }
+        Self::normalize_normals(&mut normals);
+        self.normals = Some(normals);
+    }
+
+    /// `compute_smooth_normals` spread over threads with rayon, for meshes of millions of
+    /// triangles. Each worker accumulates face normals into its own buffer and the buffers
+    /// are summed, so the result matches the serial version up to floating-point rounding.
+    #[cfg(feature = "parallel")]
+    pub fn compute_smooth_normals_parallel(&mut self) {
+        use rayon::prelude::*;
+
+        let len = self.vertex_coords.len();
+        let mut normals = self.face_indices
+            .par_chunks_exact(3)
+            // Large batches, so there are only a few buffers to allocate and sum
+            .with_min_len(PARALLEL_MIN_TRIANGLES)
+            .fold(|| vec![0.0f32; len], |mut normals, tri| {
+                Self::accumulate_face_normal(&self.vertex_coords, tri, &mut normals);
+                normals
+            })
+            .reduce(|| vec![0.0f32; len], |mut sum, normals| {
+                for (total, value) in sum.iter_mut().zip(&normals) {
+                    *total += value;
+                }
+                sum
+            });
+        Self::normalize_normals(&mut normals);
+        self.normals = Some(normals);
+    }
+
+    /// Add a triangle's area-weighted normal to each of its corners
+    fn accumulate_face_normal(vertex_coords: &[f32], tri: &[u32], normals: &mut [f32]) {
+        let p = |i: usize| Vec3::new(vertex_coords[3 * i], vertex_coords[3 * i + 1], vertex_coords[3 * i + 2]);
+        let (i0, i1, i2) = (tri[0] as usize, tri[1] as usize, tri[2] as usize);
+        // Unnormalized cross product: its length is twice the triangle area
+        let face_normal = (p(i1) - p(i0)).cross(&(p(i2) - p(i0)));
+
+        for i in [i0, i1, i2] {
+            normals[3 * i] += face_normal.x;
+            normals[3 * i + 1] += face_normal.y;
+            normals[3 * i + 2] += face_normal.z;
+        }
+    }
+
+    fn normalize_normals(normals: &mut [f32]) {
+        for normal in normals.chunks_exact_mut(3) {
+            let n = Vec3::new(normal[0], normal[1], normal[2]).normalize();
+            normal.copy_from_slice(&[n.x, n.y, n.z]);
+        }
+    }
+
+    /// Compute per-vertex tangents (xyzw, handedness in w) from the UVs and normals by accumulating
+    /// per-triangle tangents and bitangents, then orthogonalizing against the normal.
+    /// Leaves the mesh unchanged (with a warning) if UVs or normals are missing.
+    pub fn compute_tangents(&mut self) {
+        let vertex_count = self.vertex_count();
+        let (Some(uvs), Some(normals)) = (&self.uvs, &self.normals) else {
+            crate::console_log!("compute_tangents: mesh needs both UVs and normals, skipping");
+            return;
+        };
+        if uvs.len() != vertex_count * 2 || normals.len() != vertex_count * 3 {
+            crate::console_log!("compute_tangents: UV or normal count does not match the vertex count, skipping");
+            return;
+        }
+
+        let p = |i: usize| Vec3::new(self.vertex_coords[3 * i], self.vertex_coords[3 * i + 1], self.vertex_coords[3 * i + 2]);
+        let uv = |i: usize| (uvs[2 * i], uvs[2 * i + 1]);
+        let mut tangents = vec![Vec3::new(0.0, 0.0, 0.0); vertex_count];
+        let mut bitangents = vec![Vec3::new(0.0, 0.0, 0.0); vertex_count];
+
+        for tri in self.face_indices.chunks_exact(3) {
+            let (i0, i1, i2) = (tri[0] as usize, tri[1] as usize, tri[2] as usize);
+            let (e1, e2) = (p(i1) - p(i0), p(i2) - p(i0));
+            let (du1, dv1) = (uv(i1).0 - uv(i0).0, uv(i1).1 - uv(i0).1);
+            let (du2, dv2) = (uv(i2).0 - uv(i0).0, uv(i2).1 - uv(i0).1);
+
+            let det = du1 * dv2 - du2 * dv1;
+            if det.abs() <= f32::EPSILON {
+                continue; // Degenerate UV mapping, no usable tangent frame
+            }
+            let r = 1.0 / det;
+            let tangent = (e1 * dv2 - e2 * dv1) * r;
+            let bitangent = (e2 * du1 - e1 * du2) * r;
+
+            for i in [i0, i1, i2] {
+                tangents[i] = tangents[i] + tangent;
+                bitangents[i] = bitangents[i] + bitangent;
+            }
+        }
+
+        let mut out = Vec::with_capacity(vertex_count * 4);
+        for i in 0..vertex_count {
+            let n = Vec3::new(normals[3 * i], normals[3 * i + 1], normals[3 * i + 2]);
+            // Gram-Schmidt: keep only the part of the tangent perpendicular to the normal
+            let t = (tangents[i] - n * n.dot(&tangents[i])).normalize();
+            let w = if n.cross(&t).dot(&bitangents[i]) < 0.0 { -1.0 } else { 1.0 };
+            out.extend_from_slice(&[t.x, t.y, t.z, w]);
+        }
+
+        self.tangents = Some(out);
+    }
+
+    /// Build a copy of the mesh with split normals: vertices are duplicated along edges whose
+    /// dihedral angle exceeds `angle_threshold` (radians), so creases export crisp while smooth
+    /// regions keep sharing a vertex and an averaged normal. With smoothing groups, edges
+    /// between different groups (or touching group 0) are split too, and the groups carry over.
+    pub fn compute_split_normals(&self, angle_threshold: f32) -> Mesh {
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        let p = |i: u32| {
+            let i = i as usize;
+            Vec3::new(self.vertex_coords[3 * i], self.vertex_coords[3 * i + 1], self.vertex_coords[3 * i + 2])
+        };
+        let triangles: Vec<&[u32]> = self.face_indices.chunks_exact(3).collect();
+
+        // Area-weighted (unnormalized) face normals
+        let face_normals: Vec<Vec3> = triangles.iter()
+            .map(|tri| (p(tri[1]) - p(tri[0])).cross(&(p(tri[2]) - p(tri[0]))))
+            .collect();
+
+        // Slivers have no meaningful normal
+        let degenerate: Vec<bool> = triangles.iter()
+            .map(|tri| Self::is_sliver(p(tri[0]), p(tri[1]), p(tri[2])))
+            .collect();
+
+        // Triangles around each undirected edge
+        let mut edge_faces: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+        for (face, tri) in triangles.iter().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                edge_faces.entry((a.min(b), a.max(b))).or_default().push(face);
+            }
+        }
+
+        // Corners (face * 3 + k) are merged across every smooth edge they touch
+        let mut parent: Vec<usize> = (0..triangles.len() * 3).collect();
+        let corner = |face: usize, vertex: u32| face * 3 + triangles[face].iter().position(|&v| v == vertex).unwrap();
+        let cos_threshold = angle_threshold.cos();
+        let groups = self.smoothing_groups.as_ref().filter(|groups| groups.len() == triangles.len());
+
+        for (&(a, b), faces) in &edge_faces {
+            for (i, &f0) in faces.iter().enumerate() {
+                for &f1 in &faces[i + 1..] {
+                    if groups.is_some_and(|groups| groups[f0] == 0 || groups[f0] != groups[f1]) {
+                        continue; // Different smoothing groups never share normals
+                    }
+                    // Degenerate triangles never make an edge sharp
+                    let n0 = face_normals[f0].normalize();
+                    let n1 = face_normals[f1].normalize();
+                    if !degenerate[f0] && !degenerate[f1] && n0.dot(&n1) < cos_threshold {
+                        continue; // Sharp edge, keep the corners apart
+                    }
+                    for vertex in [a, b] {
+                        let r0 = find(&mut parent, corner(f0, vertex));
+                        let r1 = find(&mut parent, corner(f1, vertex));
+                        parent[r0] = r1;
+                    }
+                }
+            }
+        }
+
+        // One output vertex per corner group
+        let mut out = Mesh::new();
+        let mut normals: Vec<Vec3> = Vec::new();
+        let mut group_vertex: HashMap<usize, u32> = HashMap::new();
+
+        for (face, tri) in triangles.iter().enumerate() {
+            let mut new_tri = [0u32; 3];
+            for (k, &vertex) in tri.iter().enumerate() {
+                let root = find(&mut parent, face * 3 + k);
+                let new_index = *group_vertex.entry(root).or_insert_with(|| {
+                    let position = p(vertex);
+                    out.add_vertex(position.x, position.y, position.z);
+                    normals.push(Vec3::new(0.0, 0.0, 0.0));
+                    (out.vertex_count() - 1) as u32
+                });
+                normals[new_index as usize] = normals[new_index as usize] + face_normals[face];
+                new_tri[k] = new_index;
+            }
+            out.add_triangle(new_tri[0], new_tri[1], new_tri[2]);
+        }
+
+        // Lines and points attach to the first copy of their vertices; vertices no triangle
+        // uses are carried over with a zero normal
+        let mut first_copy: HashMap<u32, u32> = HashMap::new();
+        for (face, tri) in triangles.iter().enumerate() {
+            for (k, &vertex) in tri.iter().enumerate() {
+                let root = find(&mut parent, face * 3 + k);
+                first_copy.entry(vertex).or_insert(group_vertex[&root]);
+            }
+        }
+        let mut carry = |indices: &Option<Vec<u32>>| indices.as_ref().map(|indices| {
+            indices.iter().map(|&vertex| *first_copy.entry(vertex).or_insert_with(|| {
+                let position = p(vertex);
+                out.add_vertex(position.x, position.y, position.z);
+                normals.push(Vec3::new(0.0, 0.0, 0.0));
+                (out.vertex_count() - 1) as u32
+            })).collect()
+        });
+        let lines = carry(&self.lines);
+        let points = carry(&self.points);
+        out.lines = lines;
+        out.points = points;
+
+        out.normals = Some(normals.iter()
+            .flat_map(|n| {
+                let n = n.normalize();
+                [n.x, n.y, n.z]
+            })
+            .collect());
+        out.smoothing_groups = groups.cloned();
+        out
+    }
+
+    /// Move each vertex along its smooth normal by `amount * per_vertex[i]`, then recompute normals.
+    /// `per_vertex` must hold exactly one weight per vertex.
+    pub fn displace(&mut self, amount: f32, per_vertex: &[f32]) -> Result<(), String> {
+        if per_vertex.len() != self.vertex_count() {
+            return Err(format!(
+                "expected {} displacement weights (one per vertex), got {}",
+                self.vertex_count(),
+                per_vertex.len()
+            ));
+        }
+
+        // Supplied normals are only usable if they match the vertex buffer
+        if !matches!(&self.normals, Some(n) if n.len() == self.vertex_coords.len()) {
+            self.compute_smooth_normals();
+        }
+
+        if let Some(normals) = &self.normals {
+            for (i, weight) in per_vertex.iter().enumerate() {
+                let offset = amount * weight;
+                for axis in 0..3 {
+                    self.vertex_coords[3 * i + axis] += normals[3 * i + axis] * offset;
+                }
+            }
+        }
+
+        self.compute_smooth_normals();
+        Ok(())
+    }
+
+    /// The two axes perpendicular to `axis`, in right-handed cyclic order (X -> (Y, Z), Y -> (Z, X), Z -> (X, Y))
+    /// Returns None for anything other than 0, 1 or 2
+    fn perpendicular_axes(axis: u8) -> Option<(usize, usize)> {
+        match axis {
+            0 => Some((1, 2)),
+            1 => Some((2, 0)),
+            2 => Some((0, 1)),
+            _ => None,
+        }
+    }
+
+    /// Min and max vertex coordinate along an axis
+    fn axis_extent(&self, axis: usize) -> Option<(f32, f32)> {
+        let (min, max) = self.bounding_box()?;
+        let min = [min.vec3.x, min.vec3.y, min.vec3.z];
+        let max = [max.vec3.x, max.vec3.y, max.vec3.z];
+        Some((min[axis], max[axis]))
+    }
+
+    /// Deformers recompute normals only if the mesh already has them
+    fn refresh_normals(&mut self) {
+        if self.normals.is_some() {
+            self.compute_smooth_normals();
+        }
+    }
+
+    /// Rotate vertices about `axis` (0 = X, 1 = Y, 2 = Z) by `angle_per_unit` radians per unit of
+    /// their coordinate along that axis. Other axis values leave the mesh unchanged.
+    pub fn twist(&mut self, axis: u8, angle_per_unit: f32) {
+        let Some((u, v)) = Self::perpendicular_axes(axis) else { return };
+        if angle_per_unit == 0.0 {
+            return;
+        }
+
+        let axis = axis as usize;
+        for coord in self.vertex_coords.chunks_exact_mut(3) {
+            let (sin, cos) = (angle_per_unit * coord[axis]).sin_cos();
+            let (cu, cv) = (coord[u], coord[v]);
+            coord[u] = cu * cos - cv * sin;
+            coord[v] = cu * sin + cv * cos;
+        }
+
+        self.refresh_normals();
+    }
+
+    /// Scale vertices perpendicular to `axis` (0 = X, 1 = Y, 2 = Z), linearly from 1 at the
+    /// minimum extent along the axis to `factor` at the maximum. A factor of 1 is a no-op.
+    pub fn taper(&mut self, axis: u8, factor: f32) {
+        let Some((u, v)) = Self::perpendicular_axes(axis) else { return };
+        let axis = axis as usize;
+        let Some((min, max)) = self.axis_extent(axis) else { return };
+        let length = max - min;
+        if factor == 1.0 || length <= 0.0 {
+            return;
+        }
+
+        for coord in self.vertex_coords.chunks_exact_mut(3) {
+            let t = (coord[axis] - min) / length;
+            let scale = 1.0 + (factor - 1.0) * t;
+            coord[u] *= scale;
+            coord[v] *= scale;
+        }
+
+        self.refresh_normals();
+    }
+
+    /// Bend the mesh along `axis` (0 = X, 1 = Y, 2 = Z) through a total of `angle` radians over its
+    /// extent along that axis. The bend curls towards the first perpendicular axis (see `twist`),
+    /// around a circle of radius extent / angle. An angle of 0 is a no-op.
+    pub fn bend(&mut self, axis: u8, angle: f32) {
+        let Some((u, _)) = Self::perpendicular_axes(axis) else { return };
+        let axis = axis as usize;
+        let Some((min, max)) = self.axis_extent(axis) else { return };
+        let length = max - min;
+        if angle == 0.0 || length <= 0.0 {
+            return;
+        }
+
+        let radius = length / angle;
+        for coord in self.vertex_coords.chunks_exact_mut(3) {
+            let theta = angle * (coord[axis] - min) / length;
+            let (sin, cos) = theta.sin_cos();
+            let distance_to_center = radius - coord[u];
+            coord[axis] = min + distance_to_center * sin;
+            coord[u] = radius - distance_to_center * cos;
+        }
+
+        self.refresh_normals();
+    }
+
+    /// Whether a triangle is a sliver: its area is negligible next to its edge lengths
+    /// (this includes triangles with repeated corners)
+    fn is_sliver(a: Vec3, b: Vec3, c: Vec3) -> bool {
+        let longest_edge = [b - a, c - b, a - c].iter()
+            .map(Vec3::length)
+            .fold(0.0f32, f32::max);
+        (b - a).cross(&(c - a)).length() <= 1e-6 * longest_edge * longest_edge
+    }
+
+    #[inline]
+    fn position(&self, i: usize) -> Vec3 {
+        Vec3::new(self.vertex_coords[3 * i], self.vertex_coords[3 * i + 1], self.vertex_coords[3 * i + 2])
+    }
+
+    /// Drop triangles referencing vertices that don't exist, along with any trailing
+    /// indices that don't form a whole triangle. Returns the number of triangles removed.
+    pub fn remove_invalid_triangles(&mut self) -> usize {
+        let vertex_count = self.vertex_count() as u32;
+        let trailing = usize::from(!self.face_indices.len().is_multiple_of(3));
+        self.face_indices.truncate(self.face_count() * 3);
+        trailing + self.retain_triangles(|tri| tri.iter().all(|&i| i < vertex_count))
+    }
+
+    /// Keep only the triangles `keep` accepts, along with their smoothing groups.
+    /// Returns the number of triangles removed.
+    fn retain_triangles(&mut self, keep: impl Fn(&[u32]) -> bool) -> usize {
+        let before = self.face_count();
+        let kept: Vec<bool> = self.face_indices.chunks_exact(3).map(&keep).collect();
+        self.face_indices = self.face_indices.chunks_exact(3)
+            .zip(&kept)
+            .filter(|(_, &kept)| kept)
+            .flat_map(|(tri, _)| tri.iter().copied())
+            .collect();
+        if let Some(groups) = &mut self.smoothing_groups {
+            if groups.len() == kept.len() {
+                let mut i = 0;
+                groups.retain(|_| { i += 1; kept[i - 1] });
+            } else {
+                // Out of step with the triangles, so no longer meaningful
+                self.smoothing_groups = None;
+            }
+        }
+        before - self.face_count()
+    }
+
+    /// Drop zero-area triangles (repeated corners or slivers). Returns the number removed.
+    pub fn remove_degenerate_triangles(&mut self) -> usize {
+        // Moved out for the duration so the filter can read positions while triangles are removed
+        let positions = std::mem::take(&mut self.vertex_coords);
+        let position = |i: u32| {
+            let i = i as usize;
+            Vec3::new(positions[3 * i], positions[3 * i + 1], positions[3 * i + 2])
+        };
+        let removed = self.retain_triangles(|tri| {
+            let (a, b, c) = (tri[0], tri[1], tri[2]);
+            a != b && b != c && c != a && !Self::is_sliver(position(a), position(b), position(c))
+        });
+        self.vertex_coords = positions;
+        removed
+    }
+
+    /// Merge vertices closer than `epsilon` into one, keeping the first vertex's normal, UV
+    /// and tangent, and drop the merged duplicates. Returns the number of vertices removed.
+    pub fn weld_vertices(&mut self, epsilon: f32) -> usize {
+        self.weld_vertices_where(epsilon, |_, _| true)
+    }
+
+    /// Like `weld_vertices`, but coincident vertices are only merged when their normals are
+    /// within `normal_angle` radians of each other, so hard edges keep their split vertices.
+    /// Without (matching) normals this is a plain weld.
+    pub fn weld_vertices_with_normal_threshold(&mut self, position_eps: f32, normal_angle: f32) -> usize {
+        let Some(normals) = self.normals.clone().filter(|n| n.len() == self.vertex_coords.len()) else {
+            return self.weld_vertices(position_eps);
+        };
+        let normal = |i: usize| glam::Vec3::from_slice(&normals[3 * i..3 * i + 3]).normalize_or_zero();
+        let min_cos = normal_angle.cos();
+        self.weld_vertices_where(position_eps, |kept, i| normal(kept).dot(normal(i)) >= min_cos)
+    }
+
+    /// Shared weld: vertex `i` merges into the first earlier kept vertex `k` within `epsilon`
+    /// for which `accept(k, i)` holds
+    fn weld_vertices_where(&mut self, epsilon: f32, accept: impl Fn(usize, usize) -> bool) -> usize {
+        let vertex_count = self.vertex_count();
+        let cell_size = epsilon.max(f32::MIN_POSITIVE);
+        let cell_of = |p: Vec3| {
+            ((p.x / cell_size).floor() as i64, (p.y / cell_size).floor() as i64, (p.z / cell_size).floor() as i64)
+        };
+
+        // Kept vertices bucketed by grid cell; a match can only be in a neighbouring cell
+        let mut cells: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+        let mut remap = vec![0u32; vertex_count];
+        let mut kept = Vec::new();
+
+        for (i, remapped) in remap.iter_mut().enumerate() {
+            let p = self.position(i);
+            let (cx, cy, cz) = cell_of(p);
+            let mut existing = None;
+            'search: for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        let Some(candidates) = cells.get(&(cx + dx, cy + dy, cz + dz)) else { continue };
+                        if let Some(&k) = candidates.iter().find(|&&k| (self.position(kept[k]) - p).length() <= epsilon && accept(kept[k], i)) {
+                            existing = Some(k);
+                            break 'search;
+                        }
+                    }
+                }
+            }
+
+            *remapped = match existing {
+                Some(k) => k as u32,
+                None => {
+                    cells.entry((cx, cy, cz)).or_default().push(kept.len());
+                    kept.push(i);
+                    (kept.len() - 1) as u32
+                }
+            };
+        }
+
+        let welded = vertex_count - kept.len();
+        if welded == 0 {
+            return 0;
+        }
+
+        let gather = |values: &[f32], width: usize| -> Vec<f32> {
+            kept.iter().flat_map(|&i| values[width * i..width * (i + 1)].iter().copied()).collect()
+        };
+        self.vertex_coords = gather(&self.vertex_coords, 3);
+        // Channels that don't match the vertex count can't be remapped, so they are dropped
+        self.normals = self.normals.take().filter(|n| n.len() == vertex_count * 3).map(|n| gather(&n, 3));
+        self.uvs = self.uvs.take().filter(|uv| uv.len() == vertex_count * 2).map(|uv| gather(&uv, 2));
+        self.tangents = self.tangents.take().filter(|t| t.len() == vertex_count * 4).map(|t| gather(&t, 4));
+        let lines = self.lines.iter_mut().flatten();
+        let points = self.points.iter_mut().flatten();
+        for index in self.face_indices.iter_mut().chain(lines).chain(points) {
+            *index = remap[*index as usize];
+        }
+
+        welded
+    }
+
+    /// Reverse the winding of every triangle, turning the mesh inside out.
+    /// Normals are negated to match.
+    pub fn flip_winding(&mut self) {
+        for tri in self.face_indices.chunks_exact_mut(3) {
+            tri.swap(1, 2);
+        }
+        if let Some(normals) = &mut self.normals {
+            for n in normals.iter_mut() {
+                *n = -*n;
+            }
+        }
+    }
+
+    /// Flip triangles so that every triangle agrees in winding with its edge neighbours.
+    /// Within each connected patch the majority winding wins, keeping the flips to a minimum.
+    /// Edges shared by more than two triangles don't propagate winding.
+    /// Returns the number of triangles flipped.
+    pub fn make_winding_consistent(&mut self) -> usize {
+        let triangle_count = self.face_count();
+        let mut edge_faces: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+        for (face, tri) in self.face_indices.chunks_exact(3).enumerate() {
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                edge_faces.entry((a.min(b), a.max(b))).or_default().push(face);
+            }
+        }
+        // Whether a triangle walks the edge a -> b (as opposed to b -> a)
+        let walks = |tri: &[u32], a: u32, b: u32| (0..3).any(|k| tri[k] == a && tri[(k + 1) % 3] == b);
+
+        // flip[face]: whether the face must be reversed to match its patch's seed
+        let mut flip: Vec<Option<bool>> = vec![None; triangle_count];
+        let mut flipped = 0;
+        for seed in 0..triangle_count {
+            if flip[seed].is_some() {
+                continue;
+            }
+            flip[seed] = Some(false);
+            let mut patch = vec![seed];
+            let mut queue = vec![seed];
+
+            while let Some(face) = queue.pop() {
+                let tri = &self.face_indices[3 * face..3 * face + 3];
+                let face_flipped = flip[face] == Some(true);
+                for k in 0..3 {
+                    // Edge as the (possibly flipped) face actually walks it
+                    let (a, b) = if face_flipped { (tri[(k + 1) % 3], tri[k]) } else { (tri[k], tri[(k + 1) % 3]) };
+                    let neighbours = &edge_faces[&(a.min(b), a.max(b))];
+                    if neighbours.len() != 2 {
+                        continue;
+                    }
+                    for &other in neighbours.iter().filter(|&&other| other != face) {
+                        if flip[other].is_some() {
+                            continue;
+                        }
+                        // Consistent neighbours walk a shared edge in opposite directions
+                        let other_tri = &self.face_indices[3 * other..3 * other + 3];
+                        flip[other] = Some(walks(other_tri, a, b));
+                        patch.push(other);
+                        queue.push(other);
+                    }
+                }
+            }
+
+            // Flip whichever side of the patch is smaller
+            let to_flip = patch.iter().filter(|&&face| flip[face] == Some(true)).count();
+            let flip_marked = to_flip * 2 <= patch.len();
+            for &face in &patch {
+                if (flip[face] == Some(true)) == flip_marked {
+                    self.face_indices.swap(3 * face + 1, 3 * face + 2);
+                    flipped += 1;
+                }
+            }
+        }
+
+        flipped
+    }
+
+    /// Regroup the triangles into triangle strips, in the usual alternating-winding convention
+    /// (strip triangle `i` is `s[i], s[i+1], s[i+2]`, with the first two swapped for odd `i`),
+    /// so every triangle keeps its winding. Strips grow greedily across shared edges from each
+    /// unused triangle, taking whichever starting corner reaches furthest. This is a quick
+    /// heuristic, not an optimal (minimum strip count) stripification.
+    pub fn to_triangle_strips(&self) -> Vec<Vec<u32>> {
+        let triangle_count = self.face_count();
+        // Directed edge a -> b -> the triangles walking it, with their third corner
+        let mut edge_triangles: HashMap<(u32, u32), Vec<(usize, u32)>> = HashMap::new();
+        for (face, tri) in self.face_indices.chunks_exact(3).enumerate() {
+            for k in 0..3 {
+                edge_triangles.entry((tri[k], tri[(k + 1) % 3])).or_default().push((face, tri[(k + 2) % 3]));
+            }
+        }
+
+        let mut used = vec![false; triangle_count];
+        // Walk a strip starting a -> b -> c without committing to it; returns the strip and its triangles
+        let grow = |used: &[bool], [a, b, c]: [u32; 3], seed: usize| {
+            let mut strip = vec![a, b, c];
+            let mut faces = HashSet::from([seed]);
+            loop {
+                let (p, q) = (strip[strip.len() - 2], strip[strip.len() - 1]);
+                // The next triangle is the last two strip vertices plus one, wound by its parity
+                let edge = if strip.len() % 2 == 0 { (p, q) } else { (q, p) };
+                let next = edge_triangles.get(&edge).and_then(|candidates| {
+                    candidates.iter().find(|&&(face, _)| !used[face] && !faces.contains(&face))
+                });
+                match next {
+                    Some(&(face, corner)) => {
+                        strip.push(corner);
+                        faces.insert(face);
+                    }
+                    None => return (strip, faces),
+                }
+            }
+        };
+
+        let mut strips = Vec::new();
+        for seed in 0..triangle_count {
+            if used[seed] {
+                continue;
+            }
+            let tri = &self.face_indices[3 * seed..3 * seed + 3];
+            let (strip, faces) = (0..3)
+                .map(|k| grow(&used, [tri[k], tri[(k + 1) % 3], tri[(k + 2) % 3]], seed))
+                .max_by_key(|(strip, _)| strip.len())
+                .unwrap();
+            for face in faces {
+                used[face] = true;
+            }
+            strips.push(strip);
+        }
+        strips
+    }
+
+    /// Cut the mesh with a plane. Returns the part on the side `plane_normal` points to, the
+    /// part behind it, and the cut outline. Triangles crossing the plane are split, with new
+    /// vertices shared along each cut edge; normals and UVs are interpolated, smoothing groups
+    /// carry over and tangents are dropped. Triangles lying in the plane go to both parts.
+    /// The outline lists the points where the plane crosses the surface in order around each
+    /// cut loop, loops one after another; it follows the mesh's connectivity, so weld first.
+    /// A zero normal returns the whole mesh in front.
+    pub fn slice_by_plane(&self, plane_point: Point3, plane_normal: Vec3) -> (Mesh, Mesh, Vec<Point3>) {
+        let normal = glam::Vec3::from(plane_normal).normalize_or_zero();
+        if normal == glam::Vec3::ZERO {
+            return (self.clone(), Mesh::new(), Vec::new());
+        }
+        let origin = glam::Vec3::from(plane_point);
+        let position = |i: u32| glam::Vec3::from(self.position(i as usize));
+        let distances: Vec<f32> = (0..self.vertex_count() as u32)
+            .map(|i| {
+                let d = (position(i) - origin).dot(normal);
+                if d.abs() <= SLICE_PLANE_EPSILON { 0.0 } else { d }
+            })
+            .collect();
+        // A sliced vertex as a blend of two original ones: (a, b, fraction of the way to b)
+        let blend = |vertex: SliceVertex| match vertex {
+            SliceVertex::Original(i) => (i, i, 0.0),
+            SliceVertex::Cut(a, b) => (a, b, distances[a as usize] / (distances[a as usize] - distances[b as usize])),
+        };
+
+        let valid_normals = self.normals.as_ref().filter(|n| n.len() == self.vertex_coords.len());
+        let valid_uvs = self.uvs.as_ref().filter(|uv| 3 * uv.len() == 2 * self.vertex_coords.len());
+        let mut parts = [Mesh::new(), Mesh::new()];
+        let mut part_vertices: [HashMap<SliceVertex, u32>; 2] = [HashMap::new(), HashMap::new()];
+        for part in &mut parts {
+            part.normals = valid_normals.map(|_| Vec::new());
+            part.uvs = valid_uvs.map(|_| Vec::new());
+            part.smoothing_groups = self.smoothing_groups.as_ref().map(|_| Vec::new());
+        }
+        let emit = |part: &mut Mesh, vertex: SliceVertex| {
+            let (a, b, t) = blend(vertex);
+            let lerp = |values: &[f32], width: usize, k: usize| {
+                let (a, b) = (width * a as usize + k, width * b as usize + k);
+                values[a] + (values[b] - values[a]) * t
+            };
+            let p = position(a).lerp(position(b), t);
+            part.add_vertex(p.x, p.y, p.z);
+            if let (Some(out), Some(normals)) = (&mut part.normals, valid_normals) {
+                let n = glam::Vec3::from_array([0, 1, 2].map(|k| lerp(normals, 3, k))).normalize_or_zero();
+                out.extend_from_slice(&n.to_array());
+            }
+            if let (Some(out), Some(uvs)) = (&mut part.uvs, valid_uvs) {
+                out.extend([0, 1].map(|k| lerp(uvs, 2, k)));
+            }
+            (part.vertex_count() - 1) as u32
+        };
+
+        let mut segments: Vec<[SliceVertex; 2]> = Vec::new();
+        for (triangle, tri) in self.face_indices.chunks_exact(3).enumerate() {
+            let cut = |k: usize| {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                let (da, db) = (distances[a as usize], distances[b as usize]);
+                (da * db < 0.0).then_some(SliceVertex::Cut(a.min(b), a.max(b)))
+            };
+
+            // Where the plane meets the triangle: vertices on it, and crossed edges
+            let on_plane: Vec<SliceVertex> = (0..3)
+                .flat_map(|k| [(distances[tri[k] as usize] == 0.0).then_some(SliceVertex::Original(tri[k])), cut(k)])
+                .flatten()
+                .collect();
+            if let [a, b] = on_plane[..] {
+                segments.push([a, b]);
+            }
+
+            // Clip the triangle to each side's half-space, then fan the (convex) remainder
+            for (side, sign) in [1.0f32, -1.0].into_iter().enumerate() {
+                let polygon: Vec<SliceVertex> = (0..3)
+                    .flat_map(|k| [(sign * distances[tri[k] as usize] >= 0.0).then_some(SliceVertex::Original(tri[k])), cut(k)])
+                    .flatten()
+                    .collect();
+                if polygon.len() < 3 {
+                    continue;
+                }
+                let indices: Vec<u32> = polygon.iter()
+                    .map(|&vertex| match part_vertices[side].get(&vertex) {
+                        Some(&index) => index,
+                        None => {
+                            let index = emit(&mut parts[side], vertex);
+                            part_vertices[side].insert(vertex, index);
+                            index
+                        }
+                    })
+                    .collect();
+                for k in 1..indices.len() - 1 {
+                    parts[side].add_triangle(indices[0], indices[k], indices[k + 1]);
+                    if let (Some(out), Some(groups)) = (&mut parts[side].smoothing_groups, &self.smoothing_groups) {
+                        out.push(groups.get(triangle).copied().unwrap_or(0));
+                    }
+                }
+            }
+        }
+
+        // Chain the segments into loops (or open runs, which are started from an end)
+        let mut neighbours: HashMap<SliceVertex, Vec<SliceVertex>> = HashMap::new();
+        for &[a, b] in &segments {
+            neighbours.entry(a).or_default().push(b);
+            neighbours.entry(b).or_default().push(a);
+        }
+        let starts = segments.iter().flatten()
+            .filter(|vertex| neighbours[vertex].len() == 1)
+            .chain(segments.iter().flatten());
+        let mut visited = HashSet::new();
+        let mut contour = Vec::new();
+        for &start in starts {
+            let mut current = Some(start);
+            while let Some(vertex) = current.filter(|&vertex| visited.insert(vertex)) {
+                let (a, b, t) = blend(vertex);
+                contour.push(Point3 { vec3: position(a).lerp(position(b), t).into() });
+                current = neighbours[&vertex].iter().copied().find(|next| !visited.contains(next));
+            }
+        }
+
+        let [front, back] = parts;
+        (front, back, contour)
+    }
+
+    /// One-shot cleanup for imported meshes: drops triangles with bad indices, welds
+    /// coincident vertices, removes the degenerate triangles that leaves behind, and makes
+    /// the winding consistent. Normals are recomputed if the mesh had them and anything changed.
+    pub fn sanitize(&mut self) -> RepairReport {
+        let report = RepairReport {
+            invalid_triangles_removed: self.remove_invalid_triangles(),
+            vertices_welded: self.weld_vertices(SANITIZE_WELD_EPSILON),
+            degenerate_triangles_removed: self.remove_degenerate_triangles(),
+            faces_flipped: self.make_winding_consistent(),
+        };
+
+        if !report.is_empty() {
+            self.refresh_normals();
+        }
+        report
+    }
+
     /// Create a cube mesh
     pub fn create_cube(size: f32) -> Mesh {
         let mut mesh = Mesh::new();
@@ -83,43 +1127,834 @@ impl Mesh {
         mesh
     }
 
-    /// Create a sphere mesh using UV sphere generation
+    /// Create a closed UV sphere: `rings` bands from pole to pole, each split into `segments`.
+    /// The poles are single vertices fanned to the first and last ring, so there are no
+    /// zero-area triangles. Triangles wind counter-clockwise seen from outside.
+    /// At least 3 segments and 2 rings are used.
     pub fn create_sphere(radius: f32, segments: u32, rings: u32) -> Mesh {
         let mut mesh = Mesh::new();
-        
-        // Generate vertices
-        for ring in 0..=rings {
+        let segments = segments.max(3);
+        let rings = rings.max(2);
+
+        // Top pole, the rings between the poles, then the bottom pole
+        mesh.add_vertex(0.0, radius, 0.0);
+        for ring in 1..rings {
             let phi = std::f32::consts::PI * ring as f32 / rings as f32;
             let sin_phi = phi.sin();
             let cos_phi = phi.cos();
-            
-            for segment in 0..=segments {
+
+            for segment in 0..segments {
                 let theta = 2.0 * std::f32::consts::PI * segment as f32 / segments as f32;
                 let sin_theta = theta.sin();
                 let cos_theta = theta.cos();
-                
+
                 let x = radius * sin_phi * cos_theta;
                 let y = radius * cos_phi;
                 let z = radius * sin_phi * sin_theta;
-                
+
                 mesh.add_vertex(x, y, z);
             }
         }
-        
-        // Generate faces
-        for ring in 0..rings {
+        mesh.add_vertex(0.0, -radius, 0.0);
+
+        let top = 0;
+        let bottom = 1 + (rings - 1) * segments;
+        // Vertex `segment` of ring `ring` (1..rings), wrapping around the seam
+        let ring_vertex = |ring: u32, segment: u32| 1 + (ring - 1) * segments + segment % segments;
+
+        for segment in 0..segments {
+            mesh.add_triangle(top, ring_vertex(1, segment + 1), ring_vertex(1, segment));
+        }
+        for ring in 1..rings - 1 {
+            for segment in 0..segments {
+                let upper = ring_vertex(ring, segment);
+                let upper_next = ring_vertex(ring, segment + 1);
+                let lower = ring_vertex(ring + 1, segment);
+                let lower_next = ring_vertex(ring + 1, segment + 1);
+
+                mesh.add_triangle(upper, upper_next, lower);
+                mesh.add_triangle(upper_next, lower_next, lower);
+            }
+        }
+        for segment in 0..segments {
+            mesh.add_triangle(ring_vertex(rings - 1, segment), ring_vertex(rings - 1, segment + 1), bottom);
+        }
+
+        mesh
+    }
+
+    /// Create a closed cylinder around the Y axis, centered at the origin like `create_sphere`.
+    /// Each cap is a fan around a single center vertex; the wall is a band of `segments` quads,
+    /// each split into two triangles. Caps and wall share their rim vertices, so the mesh is
+    /// watertight. Triangles wind counter-clockwise seen from outside. At least 3 segments are used.
+    pub fn create_cylinder(radius: f32, height: f32, segments: u32) -> Mesh {
+        let mut mesh = Mesh::new();
+        let segments = segments.max(3);
+        let half = height / 2.0;
+
+        // Cap centers, then the top rim, then the bottom rim
+        mesh.add_vertex(0.0, half, 0.0);
+        mesh.add_vertex(0.0, -half, 0.0);
+        for y in [half, -half] {
             for segment in 0..segments {
-                let current = ring * (segments + 1) + segment;
-                let next = current + segments + 1;
-                
-                // First triangle
-                mesh.add_triangle(current, next, current + 1);
-                // Second triangle  
-                mesh.add_triangle(current + 1, next, next + 1);
+                let theta = 2.0 * std::f32::consts::PI * segment as f32 / segments as f32;
+                mesh.add_vertex(radius * theta.cos(), y, radius * theta.sin());
             }
         }
-        
+
+        let (top, bottom) = (0, 1);
+        // Rim vertices wrap around the seam rather than repeating its first column
+        let top_rim = |segment: u32| 2 + segment % segments;
+        let bottom_rim = |segment: u32| 2 + segments + segment % segments;
+
+        for segment in 0..segments {
+            mesh.add_triangle(top, top_rim(segment + 1), top_rim(segment));
+            mesh.add_triangle(top_rim(segment), top_rim(segment + 1), bottom_rim(segment));
+            mesh.add_triangle(top_rim(segment + 1), bottom_rim(segment + 1), bottom_rim(segment));
+            mesh.add_triangle(bottom_rim(segment), bottom_rim(segment + 1), bottom);
+        }
+
         mesh
     }
 
+    /// Create a closed cone around the Y axis, centered at the origin: the apex at +height/2
+    /// and a capped circular base at -height/2. The sides fan from the single apex vertex and
+    /// the base from a single center vertex. Triangles wind counter-clockwise seen from outside.
+    /// At least 3 segments are used.
+    pub fn create_cone(radius: f32, height: f32, segments: u32) -> Mesh {
+        let mut mesh = Mesh::new();
+        let segments = segments.max(3);
+        let half = height / 2.0;
+
+        // Apex, base center, then the base rim
+        mesh.add_vertex(0.0, half, 0.0);
+        mesh.add_vertex(0.0, -half, 0.0);
+        for segment in 0..segments {
+            let theta = 2.0 * std::f32::consts::PI * segment as f32 / segments as f32;
+            mesh.add_vertex(radius * theta.cos(), -half, radius * theta.sin());
+        }
+
+        let (apex, base) = (0, 1);
+        let rim = |segment: u32| 2 + segment % segments;
+
+        for segment in 0..segments {
+            mesh.add_triangle(apex, rim(segment + 1), rim(segment));
+            mesh.add_triangle(rim(segment), rim(segment + 1), base);
+        }
+
+        mesh
+    }
+
+    /// Create a closed torus centered at the origin: a circle of `minor_radius` swept around a
+    /// ring of `major_radius` in the XZ plane. The grid wraps around both seams without repeating
+    /// vertices. Normals are filled in from the analytic surface normal. Triangles wind
+    /// counter-clockwise seen from outside. At least 3 segments are used in each direction.
+    pub fn create_torus(major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32) -> Mesh {
+        let mut mesh = Mesh::new();
+        let major_segments = major_segments.max(3);
+        let minor_segments = minor_segments.max(3);
+
+        let mut normals = Vec::with_capacity((major_segments * minor_segments * 3) as usize);
+        for major in 0..major_segments {
+            let theta = 2.0 * std::f32::consts::PI * major as f32 / major_segments as f32;
+            let (sin_theta, cos_theta) = theta.sin_cos();
+
+            for minor in 0..minor_segments {
+                let phi = 2.0 * std::f32::consts::PI * minor as f32 / minor_segments as f32;
+                let (sin_phi, cos_phi) = phi.sin_cos();
+
+                // Outward direction from the ring to the surface point
+                let normal = [cos_phi * cos_theta, sin_phi, cos_phi * sin_theta];
+                mesh.add_vertex(
+                    major_radius * cos_theta + minor_radius * normal[0],
+                    minor_radius * normal[1],
+                    major_radius * sin_theta + minor_radius * normal[2],
+                );
+                normals.extend(normal);
+            }
+        }
+
+        // Vertex `minor` of the circle at `major`, wrapping around both seams
+        let grid_vertex = |major: u32, minor: u32| (major % major_segments) * minor_segments + minor % minor_segments;
+
+        for major in 0..major_segments {
+            for minor in 0..minor_segments {
+                let corner = grid_vertex(major, minor);
+                let corner_minor = grid_vertex(major, minor + 1);
+                let corner_major = grid_vertex(major + 1, minor);
+                let corner_both = grid_vertex(major + 1, minor + 1);
+
+                mesh.add_triangle(corner, corner_minor, corner_major);
+                mesh.add_triangle(corner_minor, corner_both, corner_major);
+            }
+        }
+
+        mesh.normals = Some(normals);
+        mesh
+    }
+
+    /// Create a sphere centered at the origin from an icosahedron whose triangles are split
+    /// into four `subdivisions` times, with new vertices pushed out onto the sphere. Unlike
+    /// `create_sphere` the triangles are all close to the same size. Edge midpoints are shared
+    /// by the triangles on both sides. Triangles wind counter-clockwise seen from outside.
+    /// At most `ICOSPHERE_MAX_SUBDIVISIONS` levels are applied.
+    pub fn create_icosphere(radius: f32, subdivisions: u32) -> Mesh {
+        let t = (1.0 + 5.0f32.sqrt()) / 2.0;
+        let mut directions: Vec<Vec3> = [
+            [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+            [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+            [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+        ].iter().map(|&p| Vec3::new_from_array(p).normalize()).collect();
+        let mut triangles: Vec<[u32; 3]> = vec![
+            [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+            [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+            [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+            [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+        ];
+
+        for _ in 0..subdivisions.min(ICOSPHERE_MAX_SUBDIVISIONS) {
+            // Midpoint vertex of each edge, keyed by its endpoints (lower index first)
+            let mut midpoints: HashMap<(u32, u32), u32> = HashMap::with_capacity(triangles.len() * 3 / 2);
+            let mut midpoint = |a: u32, b: u32| *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let (pa, pb) = (directions[a as usize], directions[b as usize]);
+                directions.push(((pa + pb) * 0.5).normalize());
+                directions.len() as u32 - 1
+            });
+
+            triangles = triangles.iter()
+                .flat_map(|&[a, b, c]| {
+                    let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                    [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        let mut mesh = Mesh::new();
+        for direction in directions {
+            mesh.add_vertex(radius * direction.x, radius * direction.y, radius * direction.z);
+        }
+        for [a, b, c] in triangles {
+            mesh.add_triangle(a, b, c);
+        }
+        mesh
+    }
+
+    /// Closed triangle mesh of the convex hull of `points`, built incrementally. Only hull
+    /// corners are kept as vertices; points inside or on the hull are dropped. Fails if the
+    /// points don't span a volume (fewer than four, or all coplanar).
+    pub fn convex_hull(points: &[Point3]) -> Result<Mesh, String> {
+        if points.len() < 4 {
+            return Err(format!("Convex hull needs at least 4 points, got {}", points.len()));
+        }
+        let p: Vec<Vec3> = points.iter().map(|point| point.vec3).collect();
+
+        // Tolerance relative to the extent of the input
+        let (min, max) = p.iter().fold(
+            (Vec3::new(f32::MAX, f32::MAX, f32::MAX), Vec3::new(f32::MIN, f32::MIN, f32::MIN)),
+            |(min, max), v| (
+                Vec3::new(min.x.min(v.x), min.y.min(v.y), min.z.min(v.z)),
+                Vec3::new(max.x.max(v.x), max.y.max(v.y), max.z.max(v.z)),
+            ),
+        );
+        let eps = 1e-5 * (max - min).length();
+        let farthest = |distance: &dyn Fn(Vec3) -> f32| {
+            (0..p.len()).max_by(|&a, &b| distance(p[a]).total_cmp(&distance(p[b]))).unwrap()
+        };
+
+        // Initial tetrahedron: the point farthest from the first, then the farthest from their
+        // line, then the farthest from the plane through all three
+        let i0 = 0;
+        let i1 = farthest(&|v| (v - p[i0]).length());
+        let axis = p[i1] - p[i0];
+        let i2 = farthest(&|v| axis.cross(&(v - p[i0])).length());
+        let base_normal = axis.cross(&(p[i2] - p[i0]));
+        let i3 = farthest(&|v| base_normal.dot(&(v - p[i0])).abs());
+        if axis.length() <= eps
+            || base_normal.length() <= eps * axis.length()
+            || base_normal.dot(&(p[i3] - p[i0])).abs() <= eps * base_normal.length()
+        {
+            return Err("Convex hull input is degenerate: the points are coincident, collinear or coplanar".to_string());
+        }
+
+        // Faces wind counter-clockwise seen from outside; `None` marks a face that was replaced.
+        // Signed distance of a point above a face's plane:
+        let outward = |[a, b, c]: [usize; 3], v: Vec3| {
+            let normal = (p[b] - p[a]).cross(&(p[c] - p[a]));
+            normal.dot(&(v - p[a])) / normal.length()
+        };
+        let mut faces: Vec<Option<[usize; 3]>> = Vec::new();
+        let centroid = (p[i0] + p[i1] + p[i2] + p[i3]) * 0.25;
+        for [a, b, c] in [[i0, i1, i2], [i0, i3, i1], [i1, i3, i2], [i2, i3, i0]] {
+            let face = if outward([a, b, c], centroid) > 0.0 { [a, c, b] } else { [a, b, c] };
+            faces.push(Some(face));
+        }
+
+        // Points outside each face (its conflict list); every point is listed at most once
+        let mut outside: Vec<Vec<usize>> = vec![Vec::new(); faces.len()];
+        let assign = |faces: &[Option<[usize; 3]>], outside: &mut Vec<Vec<usize>>, candidates: &[usize], point: usize| {
+            if let Some(&f) = candidates.iter().find(|&&f| faces[f].is_some_and(|face| outward(face, p[point]) > eps)) {
+                outside[f].push(point);
+            }
+        };
+        let initial_faces = [0, 1, 2, 3];
+        for i in (0..p.len()).filter(|i| ![i0, i1, i2, i3].contains(i)) {
+            assign(&faces, &mut outside, &initial_faces, i);
+        }
+
+        while let Some(f) = (0..faces.len()).find(|&f| !outside[f].is_empty()) {
+            // Grow towards the farthest outside point. Among points equally far from the plane,
+            // take the one farthest from the face itself: a hull corner rather than a point
+            // partway along an edge that is parallel to the face.
+            let face = faces[f].unwrap();
+            let face_center = (p[face[0]] + p[face[1]] + p[face[2]]) * (1.0 / 3.0);
+            let apex = outside[f].iter().copied()
+                .reduce(|best, i| {
+                    let (d_best, d_i) = (outward(face, p[best]), outward(face, p[i]));
+                    let farther = d_i > d_best + eps
+                        || (d_i > d_best - eps && (p[i] - face_center).length() > (p[best] - face_center).length());
+                    if farther { i } else { best }
+                })
+                .unwrap();
+
+            let visible: Vec<usize> = (0..faces.len())
+                .filter(|&f| faces[f].is_some_and(|face| outward(face, p[apex]) > eps))
+                .collect();
+
+            // The horizon: edges of the visible region not shared by two visible faces
+            let visible_edges: std::collections::HashSet<(usize, usize)> = visible.iter()
+                .flat_map(|&f| {
+                    let [a, b, c] = faces[f].unwrap();
+                    [(a, b), (b, c), (c, a)]
+                })
+                .collect();
+            let mut orphans = Vec::new();
+            for &f in &visible {
+                faces[f] = None;
+                orphans.append(&mut outside[f]);
+            }
+            let first_new = faces.len();
+            for &(a, b) in &visible_edges {
+                if !visible_edges.contains(&(b, a)) {
+                    faces.push(Some([a, b, apex]));
+                    outside.push(Vec::new());
+                }
+            }
+
+            // Points left inside the grown hull are dropped for good
+            let new_faces: Vec<usize> = (first_new..faces.len()).collect();
+            for point in orphans.into_iter().filter(|&point| point != apex) {
+                assign(&faces, &mut outside, &new_faces, point);
+            }
+        }
+
+        // Keep only the points the hull uses, in input order
+        let faces: Vec<[usize; 3]> = faces.into_iter().flatten().collect();
+        let mut used = vec![false; p.len()];
+        for &corner in faces.iter().flatten() {
+            used[corner] = true;
+        }
+        let mut remap = vec![0u32; p.len()];
+        let mut mesh = Mesh::new();
+        for i in (0..p.len()).filter(|&i| used[i]) {
+            remap[i] = mesh.vertex_count() as u32;
+            mesh.add_vertex(p[i].x, p[i].y, p[i].z);
+        }
+        for [a, b, c] in faces {
+            mesh.add_triangle(remap[a], remap[b], remap[c]);
+        }
+        Ok(mesh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_normals_facet_cube_but_not_sphere() {
+        let threshold = 30f32.to_radians();
+
+        let cube = Mesh::create_cube(2.0).compute_split_normals(threshold);
+        // Each of the 8 corners is split between its 3 faces
+        assert_eq!(cube.vertex_count(), 24);
+        assert_eq!(cube.face_count(), 12);
+
+        let sphere = Mesh::create_sphere(1.0, 24, 16);
+        let smooth = sphere.compute_split_normals(threshold);
+        assert_eq!(smooth.vertex_count(), sphere.vertex_count());
+        assert_eq!(smooth.normals.as_ref().unwrap().len(), smooth.vertex_coords.len());
+    }
+
+    #[test]
+    fn tangents_follow_u_direction() {
+        let mut plane = Mesh::new();
+        for (x, z) in [(0.0, 0.0), (1.0, 0.0), (1.0, -1.0), (0.0, -1.0)] {
+            plane.add_vertex(x, 0.0, z);
+        }
+        plane.add_triangle(0, 1, 2);
+        plane.add_triangle(0, 2, 3);
+        plane.uvs = Some(vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0]);
+        plane.compute_smooth_normals();
+
+        plane.compute_tangents();
+
+        let tangents = plane.tangents.clone().unwrap();
+        assert_eq!(tangents.len(), 4 * plane.vertex_count());
+        for tangent in tangents.chunks_exact(4) {
+            assert!((tangent[0] - 1.0).abs() < 1e-5 && tangent[1].abs() < 1e-5 && tangent[2].abs() < 1e-5);
+            assert_eq!(tangent[3], 1.0);
+        }
+        let json = serde_json::to_string(&plane).unwrap();
+        assert_eq!(serde_json::from_str::<Mesh>(&json).unwrap().tangents, plane.tangents);
+    }
+
+    #[test]
+    fn sanitize_repairs_messy_cube() {
+        let cube = Mesh::create_cube(1.0);
+        // Every triangle on its own vertices
+        let mut mesh = Mesh::new();
+        for triangle in cube.face_indices.chunks(3) {
+            let base = mesh.vertex_count() as u32;
+            for &index in triangle {
+                let index = index as usize * 3;
+                mesh.add_vertex(cube.vertex_coords[index], cube.vertex_coords[index + 1], cube.vertex_coords[index + 2]);
+            }
+            mesh.add_triangle(base, base + 1, base + 2);
+        }
+        // One flipped, one degenerate, one out of range and one trailing partial triangle
+        mesh.face_indices.swap(4, 5);
+        mesh.add_triangle(0, 0, 1);
+        mesh.compute_smooth_normals();
+        mesh.add_triangle(0, 1, 999);
+        mesh.face_indices.push(3);
+
+        let report = mesh.sanitize();
+
+        assert!(!report.is_empty());
+        assert_eq!(report.invalid_triangles_removed, 2);
+        assert_eq!(report.degenerate_triangles_removed, 1);
+        assert_eq!(report.vertices_welded, 36 - 8);
+        assert_eq!(report.faces_flipped, 1);
+        assert_eq!((mesh.vertex_count(), mesh.face_count()), (8, 12));
+        assert!((mesh.signed_volume() - cube.signed_volume()).abs() < 1e-5);
+        assert_eq!(mesh.normals.as_ref().unwrap().len(), 24);
+        assert!(mesh.sanitize().is_empty());
+    }
+
+    #[test]
+    fn convex_hull_of_cube_with_interior_points() {
+        let mut points: Vec<Point3> = Vec::new();
+        for x in [-1.0, 1.0] {
+            for y in [-1.0, 1.0] {
+                for z in [-1.0, 1.0] {
+                    points.push(Point3::new(x, y, z));
+                }
+            }
+        }
+        // Interior points, plus points lying on a face and on an edge
+        points.extend((0..50).map(|i| {
+            let t = i as f32 * 0.37;
+            Point3::new(t.sin() * 0.9, (t * 1.3).cos() * 0.9, (t * 0.7).sin() * 0.5)
+        }));
+        points.push(Point3::new(1.0, 0.0, 0.0));
+        points.push(Point3::new(1.0, 1.0, 0.0));
+        // So the corners aren't the first points seen
+        points.rotate_left(20);
+
+        let hull = Mesh::convex_hull(&points).unwrap();
+        assert_eq!(hull.face_count(), 12);
+        assert_eq!(hull.vertex_count(), 8);
+        assert!((hull.signed_volume() - 8.0).abs() < 1e-4);
+
+        assert!(Mesh::convex_hull(&points[..3]).is_err());
+        let coplanar: Vec<Point3> = (0..10).map(|i| Point3::new(i as f32, (i * i) as f32, 0.0)).collect();
+        assert!(Mesh::convex_hull(&coplanar).is_err());
+    }
+
+    #[test]
+    fn oriented_box_of_axis_aligned_box() {
+        let mut mesh = Mesh::create_cube(2.0);
+        mesh.apply_transform(&Transform::from_position_rotation_scale([1.0, 2.0, 3.0], [0.0, 0.0, 0.0, 1.0], [3.0, 1.0, 0.5]));
+
+        let (center, axes, half_extents) = mesh.oriented_bounding_box();
+
+        assert!((center.vec3 - Vec3::new(1.0, 2.0, 3.0)).length() < 1e-5);
+        // Longest axis first
+        assert!((axes[0].x.abs() - 1.0).abs() < 1e-5);
+        assert!((axes[1].y.abs() - 1.0).abs() < 1e-5);
+        assert!((axes[2].z.abs() - 1.0).abs() < 1e-5);
+        for (extent, expected) in half_extents.into_iter().zip([3.0, 1.0, 0.5]) {
+            assert!((extent - expected).abs() < 1e-5, "{half_extents:?}");
+        }
+    }
+
+    /// Triangle rotated to start at its smallest index, so equal triangles compare equal
+    fn canonical_triangle(triangle: [u32; 3]) -> [u32; 3] {
+        let first = (0..3).min_by_key(|&k| triangle[k]).unwrap();
+        std::array::from_fn(|k| triangle[(first + k) % 3])
+    }
+
+    #[test]
+    fn strips_expand_back_to_the_triangles() {
+        for mesh in [Mesh::create_cube(1.0), Mesh::create_sphere(1.0, 16, 8)] {
+            let strips = mesh.to_triangle_strips();
+
+            let mut expanded: Vec<[u32; 3]> = strips.iter()
+                .flat_map(|strip| strip.windows(3).enumerate().map(|(i, w)| {
+                    // Odd triangles of a strip have their first two corners swapped
+                    canonical_triangle(if i % 2 == 0 { [w[0], w[1], w[2]] } else { [w[1], w[0], w[2]] })
+                }))
+                .collect();
+            let mut original: Vec<[u32; 3]> = mesh.face_indices.chunks_exact(3)
+                .map(|t| canonical_triangle([t[0], t[1], t[2]]))
+                .collect();
+            expanded.sort();
+            original.sort();
+            assert_eq!(expanded, original);
+            assert!(strips.len() < mesh.face_count());
+        }
+    }
+
+    #[test]
+    fn sphere_poles_are_fans_without_slivers() {
+        for (segments, rings) in [(24, 16), (12, 8), (3, 2)] {
+            let sphere = Mesh::create_sphere(1.0, segments, rings);
+            let corner = |i: u32| glam::Vec3::from_slice(&sphere.vertex_coords[3 * i as usize..3 * i as usize + 3]);
+            for t in sphere.face_indices.chunks_exact(3) {
+                let doubled_area = (corner(t[1]) - corner(t[0])).cross(corner(t[2]) - corner(t[0])).length();
+                assert!(doubled_area > 1e-6, "{segments}x{rings} has a zero-area triangle {t:?}");
+            }
+            assert!(sphere.signed_volume() > 0.0);
+            crate::HalfEdgeMesh::from_mesh(&sphere).validate().unwrap();
+        }
+
+        // Straight down onto the pole hits the surface there, facing up
+        use crate::{geometry::{Direction3, Ray3, RaycastOptions}, model::ModelVariant, SceneGraphNode};
+        let sphere = ModelVariant::Mesh(Mesh::create_sphere(1.0, 24, 16));
+        let ray = Ray3::new(Point3::new(0.0, 5.0, 0.0), Direction3 { vec3: Vec3::new(0.0, -1.0, 0.0) });
+        let hit = SceneGraphNode::raycast_model(ray, &sphere, &Transform::identity(), 0, &RaycastOptions::default()).unwrap();
+        assert!((hit.hit_response.hit_position.vec3.y - 1.0).abs() < 1e-4);
+        assert!((hit.distance - 4.0).abs() < 1e-4);
+        assert!(hit.normal.vec3.y > 0.9);
+    }
+
+    #[test]
+    fn surface_samples_lie_on_mesh_by_area() {
+        let cube = Mesh::create_cube(1.0);
+        let samples = cube.sample_surface(2000, 7);
+        assert_eq!(samples.len(), 2000);
+        // On a face of the cube: one coordinate at +-0.5, none beyond
+        for sample in &samples {
+            let v = sample.vec3;
+            assert!((v.x.abs().max(v.y.abs()).max(v.z.abs()) - 0.5).abs() < 1e-5, "{:?}", v);
+        }
+        let again = cube.sample_surface(2000, 7);
+        assert!(samples.iter().zip(&again).all(|(a, b)| <[f32; 3]>::from(*a) == <[f32; 3]>::from(*b)));
+
+        // Apart from each other, triangles of area 1 and 3
+        let mut mesh = Mesh::new();
+        for [x, y, z] in [[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 1.0, 0.0], [10.0, 0.0, 0.0], [16.0, 0.0, 0.0], [10.0, 1.0, 0.0]] {
+            mesh.add_vertex(x, y, z);
+        }
+        mesh.add_triangle(0, 1, 2);
+        mesh.add_triangle(3, 4, 5);
+        let samples = mesh.sample_surface(20000, 1);
+        let on_large = samples.iter().filter(|sample| sample.vec3.x >= 10.0).count() as f32 / samples.len() as f32;
+        assert!((on_large - 0.75).abs() < 0.02, "{on_large}");
+        assert!(samples.iter().all(|sample| {
+            let v = sample.vec3;
+            let (x, width) = if v.x < 10.0 { (v.x, 2.0) } else { (v.x - 10.0, 6.0) };
+            x >= 0.0 && v.y >= 0.0 && x / width + v.y <= 1.0 + 1e-5
+        }));
+    }
+
+    #[test]
+    fn weld_by_normal_angle_keeps_hard_edges() {
+        let threshold = 30f32.to_radians();
+        let cube = Mesh::create_cube(1.0).compute_split_normals(threshold);
+        let mut doubled = cube.clone();
+        doubled.append(&cube);
+        assert_eq!(doubled.vertex_count(), 48);
+
+        doubled.weld_vertices_with_normal_threshold(1e-4, threshold);
+
+        // The overlapping copies merge, but each corner stays split between its three faces
+        assert_eq!(doubled.vertex_count(), 24);
+        assert_eq!(doubled.face_count(), 24);
+
+        // Welding by position alone rejoins the corners
+        let mut welded = cube.clone();
+        welded.weld_vertices(1e-4);
+        assert_eq!(welded.vertex_count(), 8);
+    }
+
+    #[test]
+    fn quantized_positions_stay_within_half_a_step() {
+        let original = Mesh::create_sphere(3.7, 24, 16);
+        let mut mesh = original.clone();
+        let (origin, scale) = mesh.quantize_positions(12);
+        // The sphere is 7.4 across, split into 4095 steps
+        assert!((scale - 7.4 / 4095.0).abs() < 1e-6);
+        for (before, after) in original.vertex_coords.iter().zip(&mesh.vertex_coords) {
+            assert!((before - after).abs() <= scale * 0.5 + 1e-5, "{before} -> {after}");
+        }
+        // Every coordinate sits on the grid: origin + q * scale for an integer q in 0..=4095
+        let origin = [origin.vec3.x, origin.vec3.y, origin.vec3.z];
+        for (i, coord) in mesh.vertex_coords.iter().enumerate() {
+            let step = (coord - origin[i % 3]) / scale;
+            assert!((step - step.round()).abs() < 1e-2 && (0.0..=4095.0).contains(&step.round()), "{step}");
+        }
+        assert_eq!(Mesh::new().quantize_positions(8).1, 0.0);
+    }
+
+    fn surface_area(mesh: &Mesh) -> f32 {
+        let corner = |i: u32| glam::Vec3::from_slice(&mesh.vertex_coords[3 * i as usize..3 * i as usize + 3]);
+        mesh.face_indices.chunks(3)
+            .map(|tri| (corner(tri[1]) - corner(tri[0])).cross(corner(tri[2]) - corner(tri[0])).length() / 2.0)
+            .sum()
+    }
+
+    #[test]
+    fn slice_cube_through_center() {
+        let cube = Mesh::create_cube(2.0);
+        let (front, back, contour) = cube.slice_by_plane(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+
+        // Each half is a 2 x 1 x 2 box without its cap: a 2 x 2 face and four 2 x 1 sides
+        let (min, max) = front.bounding_box().unwrap();
+        assert!(min.vec3.y.abs() < 1e-6 && (max.vec3.y - 1.0).abs() < 1e-6);
+        let (min, max) = back.bounding_box().unwrap();
+        assert!((min.vec3.y + 1.0).abs() < 1e-6 && max.vec3.y.abs() < 1e-6);
+        assert!((surface_area(&front) - 12.0).abs() < 1e-4);
+        assert!((surface_area(&back) - 12.0).abs() < 1e-4);
+
+        // The outline runs round the 2 x 2 square where the plane cuts the sides
+        for point in &contour {
+            let v = point.vec3;
+            assert!(v.y.abs() < 1e-6 && ((v.x.abs() - 1.0).abs() < 1e-6 || (v.z.abs() - 1.0).abs() < 1e-6), "{:?}", v);
+        }
+        let perimeter: f32 = (0..contour.len()).map(|i| (contour[(i + 1) % contour.len()] - contour[i]).length()).sum();
+        assert!((perimeter - 8.0).abs() < 1e-4, "{perimeter}");
+
+        // Capping the front half with a fan over the outline closes a box of volume 4
+        let mut capped = front.clone();
+        let base = capped.vertex_count() as u32;
+        for point in &contour {
+            capped.add_vertex(point.vec3.x, point.vec3.y, point.vec3.z);
+        }
+        for k in 1..contour.len() as u32 - 1 {
+            capped.add_triangle(base, base + k, base + k + 1);
+        }
+        assert!((capped.signed_volume().abs() - 4.0).abs() < 1e-4);
+
+        // A plane clear of the mesh leaves it all on one side
+        let (front, back, contour) = cube.slice_by_plane(Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!((front.face_count(), back.face_count(), contour.len()), (0, 12, 0));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_normals_match_serial() {
+        // Enough triangles for several workers, so buffers really are summed
+        let sphere = Mesh::create_icosphere(1.0, 7);
+        assert!(sphere.face_count() > 4 * PARALLEL_MIN_TRIANGLES);
+        let (mut serial, mut parallel) = (sphere.clone(), sphere);
+        serial.compute_smooth_normals();
+        parallel.compute_smooth_normals_parallel();
+        let (serial, parallel) = (serial.normals.unwrap(), parallel.normals.unwrap());
+        let worst = serial.iter().zip(&parallel).map(|(a, b)| (a - b).abs()).fold(0.0f32, f32::max);
+        assert!(worst < 1e-5, "{worst}");
+    }
+
+    // Timing comparison rather than a check; run with
+    // `cargo test --release --features parallel -- --ignored --nocapture`
+    #[cfg(feature = "parallel")]
+    #[test]
+    #[ignore]
+    fn bench_parallel_normals() {
+        let sphere = Mesh::create_icosphere(1.0, 9);
+        let (mut serial, mut parallel) = (sphere.clone(), sphere.clone());
+
+        let start = std::time::Instant::now();
+        serial.compute_smooth_normals();
+        let serial_time = start.elapsed();
+
+        let start = std::time::Instant::now();
+        parallel.compute_smooth_normals_parallel();
+        let parallel_time = start.elapsed();
+
+        println!(
+            "{} triangles: serial {serial_time:?}, parallel {parallel_time:?} ({:.1}x)",
+            sphere.face_count(),
+            serial_time.as_secs_f64() / parallel_time.as_secs_f64()
+        );
+    }
+
+    #[test]
+    fn lines_and_points_serialize_without_adding_faces() {
+        let mut mesh = Mesh::create_cube(1.0);
+        let faces = mesh.face_count();
+        let base = mesh.vertex_count() as u32;
+        mesh.add_vertex(0.0, 0.0, 0.0);
+        mesh.add_vertex(0.0, 2.0, 0.0);
+        mesh.add_line(base, base + 1);
+        mesh.add_point(base + 1);
+        assert_eq!(mesh.face_count(), faces);
+
+        let json = serde_json::to_string(&mesh).unwrap();
+        assert!(json.contains(&format!("\"lines\":[{},{}]", base, base + 1)));
+        let back: Mesh = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.lines, mesh.lines);
+        assert_eq!(back.points, mesh.points);
+        let old: Mesh = serde_json::from_str(r#"{"vertex_coords":[0,0,0],"face_indices":[],"normals":null}"#).unwrap();
+        assert!(old.lines.is_none() && old.points.is_none());
+
+        // Appending offsets the indices like the triangles'
+        let mut doubled = mesh.clone();
+        doubled.append(&mesh);
+        assert_eq!(doubled.lines.as_ref().unwrap()[2..], [2 * base + 2, 2 * base + 3]);
+    }
+
+    #[test]
+    fn displace_needs_one_weight_per_vertex() {
+        let mut cube = Mesh::create_cube(1.0);
+        let before = cube.vertex_coords.clone();
+        let message = cube.displace(0.1, &[1.0; 7]).unwrap_err();
+        assert!(message.contains('8') && message.contains('7'), "{message}");
+        assert_eq!(cube.vertex_coords, before);
+    }
+
+    #[test]
+    fn uniform_displacement_inflates_icosahedron() {
+        // Every icosahedron corner looks the same, so its smooth normals point straight out
+        // and a uniform displacement grows the radius, and the volume with its cube
+        let mut icosahedron = Mesh::create_icosphere(1.0, 0);
+        let volume = icosahedron.signed_volume();
+        let weights = vec![1.0; icosahedron.vertex_count()];
+        icosahedron.displace(0.25, &weights).unwrap();
+        let expected = volume * 1.25f32.powi(3);
+        assert!((icosahedron.signed_volume() - expected).abs() < 1e-4 * expected, "{} vs {expected}", icosahedron.signed_volume());
+
+        // A zero weight leaves its vertex where it was
+        let mut cube = Mesh::create_cube(1.0);
+        let before = cube.vertex_coords.clone();
+        let mut weights = vec![0.0; 8];
+        weights[6] = 1.0;
+        cube.displace(0.5, &weights).unwrap();
+        assert_eq!(cube.vertex_coords[..18], before[..18]);
+        assert_eq!(cube.vertex_coords[21..], before[21..]);
+        assert_ne!(cube.vertex_coords[18..21], before[18..21]);
+    }
+
+    #[test]
+    fn displace_computes_missing_normals() {
+        let mut cube = Mesh::create_cube(2.0);
+        assert!(cube.normals.is_none());
+        cube.displace(0.1, &[1.0; 8]).unwrap();
+        assert!(cube.vertex_coords.iter().all(|c| c.abs() > 1.0));
+
+        // The normals left behind are those of the displaced shape
+        let normals = cube.normals.clone().unwrap();
+        assert_eq!(normals.len(), cube.vertex_coords.len());
+        cube.compute_smooth_normals();
+        assert_eq!(cube.normals.unwrap(), normals);
+    }
+
+    #[test]
+    fn neutral_deformers_leave_mesh_alone() {
+        let mut sphere = Mesh::create_sphere(1.0, 12, 8);
+        let before = sphere.clone();
+        for axis in 0..3 {
+            sphere.twist(axis, 0.0);
+            sphere.taper(axis, 1.0);
+            sphere.bend(axis, 0.0);
+        }
+        assert_eq!(sphere.vertex_coords, before.vertex_coords);
+        assert!(sphere.normals.is_none());
+    }
+
+    #[test]
+    fn twist_keeps_distance_to_axis() {
+        let mut cylinder = Mesh::create_cylinder(0.5, 3.0, 16);
+        let before = cylinder.vertex_coords.clone();
+        cylinder.twist(1, 0.7);
+        for (old, new) in before.chunks_exact(3).zip(cylinder.vertex_coords.chunks_exact(3)) {
+            assert_eq!(old[1], new[1]);
+            let radius = |c: &[f32]| c[0].hypot(c[2]);
+            assert!((radius(old) - radius(new)).abs() < 1e-5);
+        }
+        // Turned by 0.7 radians per unit of height, right-handed about +Y (Z towards X)
+        let top = &cylinder.vertex_coords[6..9];
+        let expected = 0.7 * 1.5f32;
+        assert!((top[0] - 0.5 * expected.cos()).abs() < 1e-5 && (top[2] + 0.5 * expected.sin()).abs() < 1e-5, "{top:?}");
+    }
+
+    #[test]
+    fn taper_scales_far_end_by_factor() {
+        let mut cube = Mesh::create_cube(2.0);
+        cube.compute_smooth_normals();
+        let before = cube.vertex_coords.clone();
+        cube.taper(1, 0.25);
+        for (old, new) in before.chunks_exact(3).zip(cube.vertex_coords.chunks_exact(3)) {
+            let scale = if old[1] > 0.0 { 0.25 } else { 1.0 };
+            assert_eq!(new, [old[0] * scale, old[1], old[2] * scale]);
+        }
+        // Normals the mesh had are recomputed for the new shape
+        let normals = cube.normals.clone().unwrap();
+        cube.compute_smooth_normals();
+        assert_eq!(cube.normals.unwrap(), normals);
+    }
+
+    #[test]
+    fn cylinder_is_closed_and_shares_its_seam() {
+        let cylinder = Mesh::create_cylinder(1.0, 2.0, 16);
+        // Two cap centers and two rims, with no vertex repeated along the seam
+        assert_eq!(cylinder.vertex_count(), 34);
+        assert_eq!(cylinder.face_count(), 64);
+        assert!(crate::HalfEdgeMesh::from_mesh(&cylinder).is_closed());
+        assert!(cylinder.signed_volume() > 0.0);
+        // Close to the smooth cylinder's π r² h, from below
+        let volume = cylinder.signed_volume();
+        assert!(volume < 2.0 * std::f32::consts::PI && volume > 0.95 * 2.0 * std::f32::consts::PI, "{volume}");
+    }
+
+    #[test]
+    fn cone_fans_from_shared_apex_and_base_center() {
+        let cone = Mesh::create_cone(1.0, 2.0, 12);
+        assert_eq!(cone.vertex_count(), 14);
+        assert_eq!(cone.face_count(), 24);
+        // Every side triangle uses the one apex, every base triangle the one base center
+        let uses = |vertex: u32| cone.face_indices.chunks_exact(3).filter(|t| t.contains(&vertex)).count();
+        assert_eq!((uses(0), uses(1)), (12, 12));
+        assert!(crate::HalfEdgeMesh::from_mesh(&cone).is_closed());
+        assert!(cone.signed_volume() > 0.0);
+
+        // Fewer than 3 segments still make a closed, outward cone
+        for segments in [0, 1, 2] {
+            let cone = Mesh::create_cone(1.0, 2.0, segments);
+            assert_eq!(cone.vertex_count(), 5);
+            assert!(crate::HalfEdgeMesh::from_mesh(&cone).is_closed());
+            assert!(cone.signed_volume() > 0.0);
+        }
+    }
+
+    #[test]
+    fn torus_wraps_both_seams_with_outward_normals() {
+        let (major, minor) = (2.0, 0.5);
+        let torus = Mesh::create_torus(major, minor, 24, 12);
+        assert_eq!(torus.vertex_count(), 24 * 12);
+        let half_edge_mesh = crate::HalfEdgeMesh::from_mesh(&torus);
+        assert!(half_edge_mesh.is_closed());
+        assert_eq!(half_edge_mesh.genus(), Some(1));
+        assert!(torus.signed_volume() > 0.0);
+
+        // Each normal is the unit direction from the ring out to its vertex
+        let normals = torus.normals.as_ref().unwrap();
+        assert_eq!(normals.len(), torus.vertex_coords.len());
+        for (position, normal) in torus.vertex_coords.chunks_exact(3).zip(normals.chunks_exact(3)) {
+            let position = glam::Vec3::from_slice(position);
+            let ring_point = glam::Vec3::new(position.x, 0.0, position.z).normalize() * major;
+            let expected = (position - ring_point) / minor;
+            assert!(glam::Vec3::from_slice(normal).abs_diff_eq(expected, 1e-5), "{normal:?} vs {expected}");
+        }
+    }
 }
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use crate::Mesh;
+
+/// Triangle connectivity of a `Mesh`, built once so neighbour queries, flood fills and loop
+/// walks don't have to rebuild edge maps on every call. It is a snapshot: rebuild it after
+/// the mesh's indices change.
+#[derive(Debug, Clone)]
+pub struct MeshAdjacency {
+    // Per triangle, the triangle across each edge; edge k runs from corner k to corner k + 1
+    face_neighbors: Vec<[Option<usize>; 3]>,
+    // Triangles around each vertex, packed: those of vertex v are
+    // `vertex_face_list[vertex_face_offsets[v]..vertex_face_offsets[v + 1]]`
+    vertex_face_offsets: Vec<usize>,
+    vertex_face_list: Vec<usize>,
+}
+
+impl MeshAdjacency {
+    /// Edges are matched regardless of winding. Edges used by more than two triangles
+    /// (non-manifold) have no neighbour, like boundary edges.
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let triangle_count = mesh.face_count();
+        let vertex_count = mesh.vertex_count();
+
+        // Undirected edge -> the triangles using it, with the edge's slot in each
+        let mut edge_triangles: HashMap<(u32, u32), Vec<(usize, usize)>> = HashMap::with_capacity(triangle_count * 3 / 2);
+        for (face, tri) in mesh.face_indices.chunks_exact(3).enumerate() {
+            for k in 0..3 {
+                let (a, b) = (tri[k], tri[(k + 1) % 3]);
+                edge_triangles.entry((a.min(b), a.max(b))).or_default().push((face, k));
+            }
+        }
+
+        let mut face_neighbors = vec![[None; 3]; triangle_count];
+        for users in edge_triangles.values() {
+            if let [(face_a, slot_a), (face_b, slot_b)] = users[..] {
+                face_neighbors[face_a][slot_a] = Some(face_b);
+                face_neighbors[face_b][slot_b] = Some(face_a);
+            }
+        }
+
+        // Count triangles per vertex, turn the counts into offsets, then fill
+        let mut vertex_face_offsets = vec![0; vertex_count + 1];
+        for &v in &mesh.face_indices {
+            vertex_face_offsets[v as usize + 1] += 1;
+        }
+        for v in 0..vertex_count {
+            vertex_face_offsets[v + 1] += vertex_face_offsets[v];
+        }
+        let mut next = vertex_face_offsets.clone();
+        let mut vertex_face_list = vec![0; mesh.face_indices.len()];
+        for (face, tri) in mesh.face_indices.chunks_exact(3).enumerate() {
+            for &v in tri {
+                vertex_face_list[next[v as usize]] = face;
+                next[v as usize] += 1;
+            }
+        }
+
+        MeshAdjacency { face_neighbors, vertex_face_offsets, vertex_face_list }
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.face_neighbors.len()
+    }
+
+    /// Triangles across the edges of `face`, None on boundaries. Entry k is across the
+    /// edge from corner k to corner k + 1.
+    pub fn face_neighbors(&self, face: usize) -> [Option<usize>; 3] {
+        self.face_neighbors[face]
+    }
+
+    /// Triangles using vertex `v`, in ascending order
+    pub fn vertex_faces(&self, v: usize) -> &[usize] {
+        &self.vertex_face_list[self.vertex_face_offsets[v]..self.vertex_face_offsets[v + 1]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cube_neighbors_share_each_edge() {
+        let cube = Mesh::create_cube(1.0);
+        let adjacency = MeshAdjacency::from_mesh(&cube);
+        assert_eq!(adjacency.triangle_count(), 12);
+
+        let triangle = |face: usize| &cube.face_indices[3 * face..3 * face + 3];
+        for face in 0..12 {
+            for (k, neighbor) in adjacency.face_neighbors(face).into_iter().enumerate() {
+                // The cube is closed, so every edge has a triangle on the other side
+                let neighbor = neighbor.unwrap();
+                let (a, b) = (triangle(face)[k], triangle(face)[(k + 1) % 3]);
+                assert_ne!(neighbor, face);
+                assert!(triangle(neighbor).contains(&a) && triangle(neighbor).contains(&b));
+                assert!(adjacency.face_neighbors(neighbor).contains(&Some(face)));
+            }
+        }
+        for v in 0..cube.vertex_count() {
+            let expected: Vec<usize> = (0..12).filter(|&face| triangle(face).contains(&(v as u32))).collect();
+            assert_eq!(adjacency.vertex_faces(v), &expected[..]);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+use serde::Serialize;
+use crate::{CompactMap, FaceIndex, HalfEdgeIndex, VertexIndex};
+
+/// Element selection inside the mesh of the object being edited
+/// (as opposed to the object selection in the scene graph)
+#[derive(Debug, Clone, Default)]
+pub struct MeshSelection {
+    pub faces: HashSet<FaceIndex>,
+    pub edges: HashSet<HalfEdgeIndex>,
+    pub vertices: HashSet<VertexIndex>,
+}
+
+/// Number of selected elements of each kind, for JavaScript
+#[derive(Serialize)]
+pub struct MeshSelectionCounts {
+    pub faces: usize,
+    pub edges: usize,
+    pub vertices: usize,
+}
+
+impl MeshSelection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty() && self.edges.is_empty() && self.vertices.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.faces.clear();
+        self.edges.clear();
+        self.vertices.clear();
+    }
+
+    /// Follow the mesh through `HalfEdgeMesh::compact`, dropping elements it removed
+    pub fn remap(&mut self, map: &CompactMap) {
+        self.faces = self.faces.iter().filter_map(|&face| map.face(face)).collect();
+        self.edges = self.edges.iter().filter_map(|&edge| map.half_edge(edge)).collect();
+        self.vertices = self.vertices.iter().filter_map(|&vertex| map.vertex(vertex)).collect();
+    }
+
+    pub fn counts(&self) -> MeshSelectionCounts {
+        MeshSelectionCounts {
+            faces: self.faces.len(),
+            edges: self.edges.len(),
+            vertices: self.vertices.len(),
+        }
+    }
+}
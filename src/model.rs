@@ -1,15 +1,27 @@
-use crate::{HalfEdgeMesh, Mesh, ModelWrapper};
+use crate::{CatmullRomSpline, FaceIndex, HalfEdgeMesh, Mesh, ModelWrapper, Transform, Transformable};
 use std::string::String;
 
 /// Trait for mesh representations that can be edited and rendered
 pub trait ToMesh: Clone {
     fn to_mesh(&self) -> Mesh;
+
+    /// Render indices of a single face, matching the triangles `to_mesh` emits for it
+    /// Used for incremental updates; models without per-face triangulation return None
+    fn face_triangles(&self, _face: FaceIndex) -> Option<Vec<u32>> {
+        None
+    }
+
+    /// Position of a vertex, as `to_mesh` writes it into the render mesh
+    fn vertex_position(&self, _vertex: u32) -> Option<[f32; 3]> {
+        None
+    }
 }
 
 #[derive(Clone)]
 pub enum ModelVariant {
-    HalfEdgeMesh(ModelWrapper<HalfEdgeMesh>),
+    HalfEdgeMesh(Box<ModelWrapper<HalfEdgeMesh>>),
     Mesh(Mesh),
+    Curve(Box<ModelWrapper<CatmullRomSpline>>),
 }
 
 #[derive(Clone)]
@@ -23,15 +35,85 @@ impl ModelVariant {
         match self {
             ModelVariant::HalfEdgeMesh(hemw) => hemw.get_mesh(),
             ModelVariant::Mesh(m) => m,
+            ModelVariant::Curve(curve) => curve.get_mesh(),
+        }
+    }
+
+    /// Number of triangles the model renders as. Counted from the face loops for editable
+    /// models, so it is current even while the render mesh is waiting to be synced.
+    pub fn triangle_count(&self) -> usize {
+        match self {
+            ModelVariant::HalfEdgeMesh(hemw) => hemw.model().triangle_count(),
+            ModelVariant::Mesh(m) => m.face_count(),
+            ModelVariant::Curve(curve) => curve.get_mesh().face_count(),
+        }
+    }
+
+    /// Approximate heap size in bytes, including the render mesh of editable models
+    pub fn memory_estimate(&self) -> usize {
+        match self {
+            ModelVariant::HalfEdgeMesh(hemw) => hemw.model().memory_estimate() + hemw.get_mesh().memory_estimate(),
+            ModelVariant::Mesh(m) => m.memory_estimate(),
+            ModelVariant::Curve(curve) => curve.model().control_points.len() * std::mem::size_of::<crate::Point3>() + curve.get_mesh().memory_estimate(),
+        }
+    }
+
+    /// Bake a transform into the model's geometry. Mirroring transforms also flip the
+    /// orientation so faces stay outward-facing.
+    pub fn apply_transform(&mut self, transform: &Transform) {
+        match self {
+            ModelVariant::HalfEdgeMesh(hemw) => {
+                let mesh = hemw.model_mut();
+                for vertex in &mut mesh.vertices {
+                    vertex.position = vertex.position.transform(transform);
+                }
+                if transform.matrix().determinant() < 0.0 {
+                    mesh.flip_orientation();
+                }
+            }
+            ModelVariant::Mesh(m) => m.apply_transform(transform),
+            ModelVariant::Curve(curve) => curve.model_mut().apply_transform(transform),
         }
     }
 
     pub fn sync_render_mesh(&mut self) {
         match self {
             ModelVariant::HalfEdgeMesh(hemw) => hemw.sync_render_mesh(),
+            ModelVariant::Curve(curve) => curve.sync_render_mesh(),
             ModelVariant::Mesh(_) => {
                 // No-op: raw Mesh is already in render format
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::HalfEdgeIndex;
+
+    #[test]
+    fn triangle_count_matches_render_mesh() {
+        // A quad, a triangle and a pentagon
+        let vertex_coords: Vec<f32> = (0..8)
+            .flat_map(|i| {
+                let angle = i as f32;
+                [angle.cos(), angle.sin(), (angle * 0.3).sin()]
+            })
+            .collect();
+        let mesh = HalfEdgeMesh::from_polygons(&vertex_coords, &[vec![0, 1, 2, 3], vec![3, 2, 4], vec![4, 5, 6, 7, 3]]).unwrap();
+        let mut wrapper = ModelWrapper::new(mesh);
+        let model = ModelVariant::HalfEdgeMesh(Box::new(wrapper.clone()));
+        assert_eq!(model.triangle_count(), 6);
+        assert_eq!(model.triangle_count(), model.get_mesh().face_count());
+
+        // Counted from the face loops, so it's current before the render mesh syncs
+        wrapper.model_mut().split_edge(HalfEdgeIndex(0));
+        let model = ModelVariant::HalfEdgeMesh(Box::new(wrapper));
+        assert_eq!(model.triangle_count(), 7);
+        assert_eq!(model.triangle_count(), model.get_mesh().face_count() + 1);
+
+        assert_eq!(ModelVariant::Mesh(Mesh::create_cube(1.0)).triangle_count(), 12);
+    }
+}
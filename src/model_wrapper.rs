@@ -1,18 +1,42 @@
-use crate::{Mesh, model::ToMesh};
+use std::collections::HashSet;
+use crate::{FaceIndex, Mesh, model::ToMesh};
+
+/// Faces meeting at less than this angle (radians) share normals in flat mode,
+/// so coplanar triangles of one polygon face stay welded
+const FLAT_SHADING_ANGLE: f32 = 0.01;
+
+/// How the render mesh's normals are generated when it is rebuilt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalMode {
+    /// Area-weighted vertex normals, vertices shared between faces
+    Smooth,
+    /// One normal per face, vertices duplicated where faces meet at an angle
+    Flat,
+}
 
 #[derive(Clone)]
 pub struct ModelWrapper<M: ToMesh> {
     model: M,
     render_mesh: Mesh,
     dirty: bool,
+    // Faces touched by local edits, patched in place on the next sync
+    dirty_faces: HashSet<FaceIndex>,
+    // Start of each face's triangles in the render mesh indices, plus a trailing end offset.
+    // Computed on the first incremental sync after a full rebuild
+    face_offsets: Option<Vec<usize>>,
+    normal_mode: NormalMode,
 }
 
 impl<M: ToMesh> ModelWrapper<M> {
     pub fn new(model: M) -> Self {
+        let normal_mode = NormalMode::Smooth;
         ModelWrapper {
-            render_mesh: model.to_mesh(),
-            model: model,
+            render_mesh: Self::build_render_mesh(&model, normal_mode),
+            model,
             dirty: false,
+            dirty_faces: HashSet::new(),
+            face_offsets: None,
+            normal_mode,
         }
     }
 
@@ -20,11 +44,143 @@ impl<M: ToMesh> ModelWrapper<M> {
         &self.render_mesh
     }
 
+    /// The wrapped editable model
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+
+    /// Mutable access to the wrapped model. Marks the render mesh for rebuilding.
+    pub fn model_mut(&mut self) -> &mut M {
+        self.dirty = true;
+        &mut self.model
+    }
+
+    /// Mutable access for a local edit confined to `faces`: their vertices may move and they may
+    /// be re-triangulated, but no vertices or faces may be added or removed. Only these faces are
+    /// patched on the next sync; edits that turn out not to fit fall back to a full rebuild.
+    pub fn model_mut_faces(&mut self, faces: impl IntoIterator<Item = FaceIndex>) -> &mut M {
+        self.dirty_faces.extend(faces);
+        &mut self.model
+    }
+
+    pub fn normal_mode(&self) -> NormalMode {
+        self.normal_mode
+    }
+
+    /// Switch between smooth and flat normals. Takes effect on the next sync.
+    pub fn set_normal_mode(&mut self, normal_mode: NormalMode) {
+        if self.normal_mode != normal_mode {
+            self.normal_mode = normal_mode;
+            self.dirty = true;
+        }
+    }
+
     pub fn sync_render_mesh(&mut self) {
+        if !self.dirty && !self.dirty_faces.is_empty() && !self.patch_dirty_faces() {
+            self.dirty = true;
+        }
+
         if self.dirty {
-            // TODO: this is optimizable
-            self.render_mesh = self.model.to_mesh();
+            self.render_mesh = Self::build_render_mesh(&self.model, self.normal_mode);
+            self.face_offsets = None;
             self.dirty = false;
         }
+        self.dirty_faces.clear();
+    }
+
+    /// Re-triangulate only the dirty faces and patch their indices and vertex positions into the
+    /// render mesh. Returns false if the render mesh can't be patched and needs a full rebuild.
+    fn patch_dirty_faces(&mut self) -> bool {
+        // Flat shading splits vertices, so render indices no longer match the model's
+        if self.normal_mode != NormalMode::Smooth {
+            return false;
+        }
+
+        let model = &self.model;
+        let offsets = self.face_offsets.get_or_insert_with(|| Self::face_offsets(model));
+        let vertex_count = self.render_mesh.vertex_count();
+
+        for &face in &self.dirty_faces {
+            let (Some(triangles), Some(&start), Some(&end)) = (
+                model.face_triangles(face),
+                offsets.get(face.0),
+                offsets.get(face.0 + 1),
+            ) else {
+                return false;
+            };
+            // The face must keep its triangle count, and use only existing vertices
+            if triangles.len() != end - start || triangles.iter().any(|&v| v as usize >= vertex_count) {
+                return false;
+            }
+
+            self.render_mesh.face_indices[start..end].copy_from_slice(&triangles);
+            for &vertex in &triangles {
+                let Some(position) = model.vertex_position(vertex) else { return false };
+                let base = vertex as usize * 3;
+                self.render_mesh.vertex_coords[base..base + 3].copy_from_slice(&position);
+            }
+        }
+
+        // Moved vertices bend the normals of every neighbouring face too
+        self.render_mesh.compute_smooth_normals();
+        true
     }
-}
\ No newline at end of file
+
+    /// Index offsets of every face's triangles in the render mesh, in `to_mesh` order
+    fn face_offsets(model: &M) -> Vec<usize> {
+        let mut offsets = vec![0];
+        let mut end = 0;
+        while let Some(triangles) = model.face_triangles(FaceIndex(offsets.len() - 1)) {
+            end += triangles.len();
+            offsets.push(end);
+        }
+        offsets
+    }
+
+    /// Convert the model and compute shade-ready normals
+    fn build_render_mesh(model: &M, normal_mode: NormalMode) -> Mesh {
+        let mut mesh = model.to_mesh();
+        match normal_mode {
+            NormalMode::Smooth => mesh.compute_smooth_normals(),
+            NormalMode::Flat => mesh = mesh.compute_split_normals(FLAT_SHADING_ANGLE),
+        }
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HalfEdgeMesh;
+
+    #[test]
+    fn one_face_edit_patches_only_that_face() {
+        let grid = HalfEdgeMesh::from_quad_grid(25, 40, 1.0);
+        assert_eq!(grid.faces.len(), 1000);
+        let mut wrapper = ModelWrapper::new(grid);
+        let before = wrapper.get_mesh().clone();
+
+        let face = FaceIndex(417);
+        let seed = wrapper.model().face(face).seed_half_edge;
+        let vertex = wrapper.model().half_edge(seed).target_vertex_index;
+        wrapper.model_mut_faces([face]).vertices[vertex.0].position.vec3.y = 2.0;
+        wrapper.sync_render_mesh();
+        let after = wrapper.get_mesh();
+
+        // Same result as a full rebuild
+        let full = wrapper.model().to_mesh();
+        assert_eq!(after.face_indices, full.face_indices);
+        assert_eq!(after.vertex_coords, full.vertex_coords);
+
+        // Only the moved vertex's height changed, and only the edited face's slice was rewritten
+        let changed: Vec<usize> = (0..after.vertex_coords.len())
+            .filter(|&i| after.vertex_coords[i] != before.vertex_coords[i])
+            .collect();
+        assert_eq!(changed, vec![vertex.0 * 3 + 1]);
+        let offsets = wrapper.face_offsets.as_ref().unwrap();
+        let (start, end) = (offsets[face.0], offsets[face.0 + 1]);
+        assert_eq!(after.face_indices[..start], before.face_indices[..start]);
+        assert_eq!(after.face_indices[end..], before.face_indices[end..]);
+        assert_ne!(after.normals, before.normals);
+    }
+}
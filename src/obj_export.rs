@@ -0,0 +1,50 @@
+use crate::Mesh;
+use std::fmt::Write;
+
+/// Write a `Mesh` as OBJ text: positions, normals when present (`v//vn` faces), `s`
+/// statements wherever the smoothing group changes between consecutive triangles, and
+/// `l` and `p` statements for lines and points.
+pub fn mesh_to_obj(mesh: &Mesh) -> String {
+    let mut out = String::from("# DeltaBrush OBJ export\n");
+
+    for v in mesh.vertex_coords.chunks_exact(3) {
+        let _ = writeln!(out, "v {} {} {}", v[0], v[1], v[2]);
+    }
+    let normals = mesh.normals.as_ref().filter(|normals| normals.len() == mesh.vertex_coords.len());
+    if let Some(normals) = normals {
+        for n in normals.chunks_exact(3) {
+            let _ = writeln!(out, "vn {} {} {}", n[0], n[1], n[2]);
+        }
+    }
+
+    let groups = mesh.smoothing_groups.as_ref().filter(|groups| groups.len() == mesh.face_count());
+    let mut current_group = None;
+    for (triangle, tri) in mesh.face_indices.chunks_exact(3).enumerate() {
+        if let Some(groups) = groups {
+            let group = groups[triangle];
+            if current_group != Some(group) {
+                match group {
+                    0 => out.push_str("s off\n"),
+                    _ => { let _ = writeln!(out, "s {group}"); }
+                }
+                current_group = Some(group);
+            }
+        }
+
+        // OBJ indices are 1-based
+        let [a, b, c] = [tri[0] + 1, tri[1] + 1, tri[2] + 1];
+        let _ = match normals {
+            Some(_) => writeln!(out, "f {a}//{a} {b}//{b} {c}//{c}"),
+            None => writeln!(out, "f {a} {b} {c}"),
+        };
+    }
+
+    for line in mesh.lines.as_deref().unwrap_or_default().chunks_exact(2) {
+        let _ = writeln!(out, "l {} {}", line[0] + 1, line[1] + 1);
+    }
+    for point in mesh.points.iter().flatten() {
+        let _ = writeln!(out, "p {}", point + 1);
+    }
+
+    out
+}
@@ -1,4 +1,4 @@
-use crate::Mesh;
+use crate::{console_log, Mesh};
 
 use ahash::AHashMap;
 use std::io::Cursor;
@@ -10,7 +10,21 @@ use std::io::Cursor;
 /// - Requests single-index output.
 /// - Merges all models/shapes into one `Mesh`.
 /// - Ignores UVs/normals/materials.
+/// - Reads `s` smoothing groups: when present, vertices are split between groups and
+///   normals are averaged only within a group (`s off` faces are flat shaded).
 pub fn parse_obj_to_mesh(obj_text: &str) -> Result<Mesh, String> {
+	parse_obj_to_mesh_with_progress(obj_text, &mut |_| true)
+}
+
+/// `parse_obj_to_mesh` for large files: `progress` is called with the fraction done (0..1)
+/// between the parsing stages and returns false to cancel, which returns an error. Parsing
+/// the text itself is a single step, so the reports are coarse.
+pub fn parse_obj_to_mesh_with_progress(obj_text: &str, progress: &mut dyn FnMut(f32) -> bool) -> Result<Mesh, String> {
+	const CANCELLED: &str = "OBJ import cancelled";
+	if !progress(0.0) {
+		return Err(CANCELLED.to_string());
+	}
+
 	let mut reader = Cursor::new(obj_text.as_bytes());
 
 	let load_options = tobj::LoadOptions {
@@ -26,10 +40,14 @@ pub fn parse_obj_to_mesh(obj_text: &str) -> Result<Mesh, String> {
 		|_| Ok((Vec::new(), AHashMap::new())),
 	)
 	.map_err(|e| format!("OBJ parse failed: {e}"))?;
+	if !progress(0.6) {
+		return Err(CANCELLED.to_string());
+	}
 
 	let mut out = Mesh::new();
 
-	for model in models {
+	let model_count = models.len();
+	for (i, model) in models.into_iter().enumerate() {
 		let positions = &model.mesh.positions;
 		if positions.len() % 3 != 0 {
 			return Err("OBJ positions are not a multiple of 3".to_string());
@@ -45,7 +63,98 @@ pub fn parse_obj_to_mesh(obj_text: &str) -> Result<Mesh, String> {
 
 		out.face_indices
 			.extend(indices.iter().map(|i| i + base_vertex));
+
+		if !progress(0.6 + 0.2 * (i + 1) as f32 / model_count as f32) {
+			return Err(CANCELLED.to_string());
+		}
 	}
 
+	if let Some(groups) = read_smoothing_groups(obj_text) {
+		if groups.len() == out.face_count() {
+			out.smoothing_groups = Some(groups);
+			// Only the groups decide where normals split, whatever the angle between faces
+			out = out.compute_split_normals(std::f32::consts::PI);
+		} else {
+			console_log!("OBJ smoothing groups don't line up with the triangulated faces, ignoring them");
+		}
+	}
+
+	progress(1.0);
 	Ok(out)
 }
+
+/// Smoothing group of every triangle the faces triangulate into, in file order, or None if
+/// the file has no `s` statements. Faces before the first `s` are in group 0 (unsmoothed).
+fn read_smoothing_groups(obj_text: &str) -> Option<Vec<u32>> {
+	let mut groups = Vec::new();
+	let mut current = 0;
+	let mut seen = false;
+
+	for line in obj_text.lines() {
+		let mut tokens = line.split_whitespace();
+		match tokens.next() {
+			Some("s") => {
+				seen = true;
+				current = match tokens.next() {
+					Some("off") | None => 0,
+					Some(group) => group.parse().unwrap_or(0),
+				};
+			}
+			// A polygon with n corners is fanned into n - 2 triangles
+			Some("f") => {
+				let corners = tokens.count();
+				if corners >= 3 {
+					groups.extend(std::iter::repeat_n(current, corners - 2));
+				}
+			}
+			_ => {}
+		}
+	}
+
+	seen.then_some(groups)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn smoothing_groups_split_normals_at_shared_edge() {
+		// Two quads folded along their shared edge (vertices 3 and 4), in different groups,
+		// and a third continuing the second's group
+		let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 1 2 1\nv 0 2 1\nv 1 3 1.5\nv 0 3 1.5\n\
+			s 1\nf 1 2 3 4\ns 2\nf 4 3 5 6\nf 6 5 7 8\n";
+		let mesh = parse_obj_to_mesh(obj).unwrap();
+		assert_eq!(mesh.smoothing_groups.as_deref(), Some(&[1, 1, 2, 2, 2, 2][..]));
+
+		// The shared edge's vertices are split, one copy per group, with different normals;
+		// the edge inside group 2 stays welded
+		assert_eq!(mesh.vertex_count(), 10);
+		let normals = mesh.normals.as_ref().unwrap();
+		let copies: Vec<usize> = (0..mesh.vertex_count())
+			.filter(|&i| mesh.vertex_coords[3 * i..3 * i + 3] == [1.0, 1.0, 0.0])
+			.collect();
+		assert_eq!(copies.len(), 2);
+		let normal = |i: usize| &normals[3 * i..3 * i + 3];
+		assert!(normal(copies[0]).iter().zip(normal(copies[1])).any(|(a, b)| (a - b).abs() > 0.1));
+
+		// Written back on export
+		let exported = parse_obj_to_mesh(&crate::obj_export::mesh_to_obj(&mesh)).unwrap();
+		assert_eq!(exported.smoothing_groups, mesh.smoothing_groups);
+
+		let plain = parse_obj_to_mesh("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+		assert!(plain.smoothing_groups.is_none() && plain.normals.is_none());
+	}
+
+	#[test]
+	fn cancelled_import_stops_early() {
+		let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+		assert!(parse_obj_to_mesh_with_progress(obj, &mut |fraction| fraction < 0.5).is_err());
+
+		let mut reports = Vec::new();
+		let mesh = parse_obj_to_mesh_with_progress(obj, &mut |fraction| { reports.push(fraction); true }).unwrap();
+		assert_eq!(mesh.face_count(), 1);
+		assert_eq!(reports.first(), Some(&0.0));
+		assert!(reports.windows(2).all(|pair| pair[0] <= pair[1]));
+	}
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// Flat mesh that keeps quad faces as quads, for quad-aware renderers.
+/// Faces with other vertex counts are fan-triangulated into `triangle_indices`.
+/// Both buffers use the same winding as `Mesh`.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct QuadMesh {
+    pub vertex_coords: Vec<f32>,
+    pub quad_indices: Vec<u32>,     // 4 per quad
+    pub triangle_indices: Vec<u32>, // 3 per triangle
+}
+
+impl QuadMesh {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_coords.len() / 3
+    }
+
+    #[inline]
+    pub fn quad_count(&self) -> usize {
+        self.quad_indices.len() / 4
+    }
+
+    #[inline]
+    pub fn triangle_count(&self) -> usize {
+        self.triangle_indices.len() / 3
+    }
+}
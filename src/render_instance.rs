@@ -1,5 +1,7 @@
 use serde::Serialize;
 use crate::Transform;
+use crate::scene_graph::EdgeId;
+use crate::algorithms::seeded_uuid;
 use uuid::Uuid;
 
 /// Type-safe mesh ID using UUID to prevent index fragility
@@ -10,6 +12,11 @@ impl MeshId {
     pub fn new() -> Self {
         MeshId(Uuid::new_v4())
     }
+
+    /// Deterministic ID: the same seed always gives the same ID
+    pub fn from_seed(seed: u64) -> Self {
+        MeshId(seeded_uuid(seed))
+    }
 }
 
 // Value retrieved by JavaScript
@@ -17,6 +24,9 @@ impl MeshId {
 pub struct RenderInstance {
     pub mesh_id: MeshId,
     pub transform: Transform,
-    pub id: usize,
+    pub id: usize,  // Traversal index, shifts when the hierarchy changes
+    pub stable_id: String,  // Leaf edge ID, survives reordering of the hierarchy
     pub is_selected: bool,
+    #[serde(skip)]
+    pub path: Vec<EdgeId>,  // Edge path from the root to this model
 }
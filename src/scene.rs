@@ -1,12 +1,16 @@
 use wasm_bindgen::prelude::*;
-use crate::model::{ModelVariant, ModelEntry};
-use crate::{HalfEdgeMesh, Mesh, ModelWrapper, Transform};
+use crate::model::{ModelVariant, ModelEntry, ToMesh};
+use crate::{CatmullRomSpline, FaceIndex, HalfEdgeMesh, Material, Mesh, MeshAdjacency, MeshSelection, ModelWrapper, SceneEvent, SceneVisitor, Transform, Transformable};
 use crate::scene_graph::{SceneGraphNode, SceneGraphChild, EdgeId, SceneGraphEdge};
 use crate::RenderInstance;
 use crate::render_instance::MeshId;
 use crate::{console_log, Vec3};
-use crate::geometry::{Direction3, Point3, Ray3, WorldHitResponse};
-use crate::obj_import::parse_obj_to_mesh;
+use crate::algorithms::SplitMix64;
+use crate::aabb_tree::AabbTree;
+use crate::geometry::{bounds_intersect_sphere, Direction3, Point3, Ray3, RaycastOptions, SnapMode, WorldHitResponse};
+use crate::obj_import::{parse_obj_to_mesh, parse_obj_to_mesh_with_progress};
+use crate::obj_export::mesh_to_obj;
+use crate::scene_format::{self, GeometryDocument, MeshDocument, NodeDocument, SceneDocument};
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 
@@ -22,6 +26,27 @@ pub struct SceneGraphNodeData {
     pub is_model: bool,
     pub mesh_id: Option<String>,
     pub is_selected: bool,
+    pub visible: bool,  // Models take their enclosing node's flags
+    pub locked: bool,
+}
+
+/// Summary of the current selection for the properties panel
+#[derive(Serialize)]
+pub struct SelectedInfo {
+    pub name: String,
+    pub path: Vec<String>,
+    pub triangle_count: usize,
+    pub vertex_count: usize,
+    pub material: Option<Material>,
+}
+
+/// A top-level named group, for the layers panel
+#[derive(Serialize)]
+pub struct LayerInfo {
+    pub id: String,  // Edge ID under the root
+    pub name: String,
+    pub visible: bool,
+    pub locked: bool,
 }
 
 // =================== CORE SCENE IMPLEMENTATION ===================
@@ -32,10 +57,23 @@ pub struct Scene {
     dirty: bool,
     meshes: HashMap<MeshId, ModelEntry>,
     cached_render_instances: Vec<RenderInstance>,
+    instance_tree: AabbTree,  // World bounds of the cached instances, indexing into them
     hierarchy_dirty: bool,
     selected_path: Option<Vec<EdgeId>>,  // Path of edge IDs
+    mesh_selection: MeshSelection,  // Elements selected within the selected object
+    rebuild_count: usize,  // Number of render cache rebuilds, for profiling
+    origin_offset: [f64; 3],  // World position of the scene's working origin
+    lod_levels: HashMap<MeshId, Vec<Mesh>>,  // Simplified stand-ins per mesh, finest first
+    adjacency: HashMap<MeshId, MeshAdjacency>,  // Triangle connectivity per render mesh, built on demand
+    id_generator: Option<SplitMix64>,  // Seeds new mesh and edge IDs in deterministic mode
+    events: Vec<SceneEvent>,  // Changes since the last `take_events`
+    max_depth: usize,  // Deepest nesting (edges from the root) that rendering and raycasts follow
 }
 
+/// Screen height in pixels at and above which objects render at full detail. Every halving
+/// of an object's on-screen size below this selects the next coarser level of detail.
+const LOD_FULL_DETAIL_PIXELS: f32 = 256.0;
+
 impl Scene {
     pub fn new() -> Self {
         Scene {
@@ -43,8 +81,55 @@ impl Scene {
             dirty: false,
             meshes: HashMap::new(),
             cached_render_instances: Vec::new(),
+            instance_tree: AabbTree::default(),
             hierarchy_dirty: true,
             selected_path: None,  // Path of edge IDs
+            mesh_selection: MeshSelection::new(),
+            rebuild_count: 0,
+            origin_offset: [0.0; 3],
+            lod_levels: HashMap::new(),
+            adjacency: HashMap::new(),
+            id_generator: None,
+            events: Vec::new(),
+            max_depth: 256,
+        }
+    }
+
+    /// Switch deterministic ID mode on (`Some(seed)`) or off (`None`, the default). In
+    /// deterministic mode new mesh and edge IDs are drawn from a counter seeded with `seed`
+    /// instead of being random, so building the same scene the same way gives the same IDs
+    /// and a byte-identical `to_json`. IDs loaded from a saved scene are kept either way.
+    pub fn set_id_seed(&mut self, seed: Option<u64>) {
+        self.id_generator = seed.map(SplitMix64::new);
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Limit how deep rendering and raycasts follow the graph. Anything nested more than
+    /// `max_depth` edges below the root is skipped with a log message rather than risking a
+    /// stack overflow in WASM, so raise it only for scenes known to need it. Defaults to 256.
+    ///
+    /// Only rendering and raycasts are guarded. Other walks over the graph (mesh reference
+    /// counting, world bounds, `to_json` and the like) still follow every level, and nothing
+    /// stops deeper nesting from being built, so a graph deep enough can still overflow there.
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+        self.hierarchy_dirty = true;
+    }
+
+    fn next_mesh_id(&mut self) -> MeshId {
+        match &mut self.id_generator {
+            Some(generator) => MeshId::from_seed(generator.next_u64()),
+            None => MeshId::new(),
+        }
+    }
+
+    fn next_edge_id(&mut self) -> EdgeId {
+        match &mut self.id_generator {
+            Some(generator) => EdgeId::from_seed(generator.next_u64()),
+            None => EdgeId::new(),
         }
     }
 
@@ -55,7 +140,7 @@ impl Scene {
         }
         
         // Sync all render meshes first
-        self.root.sync_render_mesh(&mut self.meshes);
+        self.ensure_synced();
         
         // Rebuild the flat cache
         let mut object_id = 0;
@@ -64,18 +149,28 @@ impl Scene {
             &mut object_id,
             &self.meshes,
             &[],  // Empty path for root
-            self.selected_path.as_ref()
+            self.selected_path.as_ref(),
+            self.max_depth
         );
+        let bounds = self.cached_render_instances.iter().enumerate()
+            .filter_map(|(index, instance)| {
+                let (min, max) = SceneGraphNode::model_world_bounds(&instance.mesh_id, &instance.transform, &self.meshes)?;
+                Some((min, max, index))
+            })
+            .collect();
+        self.instance_tree = AabbTree::build(bounds);
         
         self.hierarchy_dirty = false;
         self.dirty = true;  // Mark for JS update
+        self.rebuild_count += 1;
     }
 
     /// Add mesh to scene storage, returns mesh_id
     fn add_mesh(&mut self, model: ModelVariant, name: String) -> MeshId {
-        let mesh_id = MeshId::new();
+        let mesh_id = self.next_mesh_id();
         let entry = ModelEntry { model, name };
         self.meshes.insert(mesh_id, entry);
+        self.events.push(SceneEvent::MeshAdded { mesh_id });
         mesh_id
     }
 
@@ -107,7 +202,7 @@ impl Scene {
 
     pub fn add_cube(&mut self, size: f32) -> MeshId {
         let half_edge_mesh = HalfEdgeMesh::create_cube(size);
-        let model = ModelVariant::HalfEdgeMesh(ModelWrapper::new(half_edge_mesh));
+        let model = ModelVariant::HalfEdgeMesh(Box::new(ModelWrapper::new(half_edge_mesh)));
         self.add_mesh(model, "cube".to_string())
     }
 
@@ -116,7 +211,7 @@ impl Scene {
         // Keep tessellation modest for interactive performance.
         let sphere_mesh = Mesh::create_sphere(radius, 24, 16);
         let half_edge_mesh = HalfEdgeMesh::from_mesh(&sphere_mesh);
-        let model = ModelVariant::HalfEdgeMesh(ModelWrapper::new(half_edge_mesh));
+        let model = ModelVariant::HalfEdgeMesh(Box::new(ModelWrapper::new(half_edge_mesh)));
         self.add_mesh(model, "sphere".to_string())
     }
 
@@ -132,10 +227,16 @@ impl Scene {
 
     pub fn add_plane(&mut self, size: f32) -> MeshId {
         let half_edge_mesh = HalfEdgeMesh::create_plane(size);
-        let model = ModelVariant::HalfEdgeMesh(ModelWrapper::new(half_edge_mesh));
+        let model = ModelVariant::HalfEdgeMesh(Box::new(ModelWrapper::new(half_edge_mesh)));
         self.add_mesh(model, "plane".to_string())
     }
 
+    /// Add a tube swept along a spline. The spline stays editable; its mesh is rebuilt on sync.
+    pub fn add_curve(&mut self, curve: CatmullRomSpline) -> MeshId {
+        let model = ModelVariant::Curve(Box::new(ModelWrapper::new(curve)));
+        self.add_mesh(model, "curve".to_string())
+    }
+
     fn name_from_obj(filename: &str) -> String {
         let lower = filename.to_ascii_lowercase();
         if let Some(stripped) = lower.strip_suffix(".obj") {
@@ -147,7 +248,8 @@ impl Scene {
 
     pub fn remove_object(&mut self, id: usize) -> bool {
         if id < self.root.edges.len() {
-            self.root.edges.remove(id);
+            let edge = self.root.edges.remove(id);
+            self.events.push(SceneEvent::ObjectRemoved { id: edge.edge_id, path: Vec::new() });
             self.hierarchy_dirty = true;
             true
         } else {
@@ -157,242 +259,1395 @@ impl Scene {
 
     pub fn update_transform(&mut self, id: usize, transform: Transform) -> bool {
         if id < self.root.edges.len() {
-            if let SceneGraphChild::Node(node) = &mut self.root.edges[id].child {
-                node.transform = transform;
-                self.dirty = true;
+            let edge = &mut self.root.edges[id];
+            if let SceneGraphChild::Node(node) = &mut edge.child {
+                node.transform = transform.clone();
+                self.events.push(SceneEvent::TransformChanged { path: vec![edge.edge_id], transform });
+                self.hierarchy_dirty = true;  // World transforms of the cached instances changed
                 return true;
             }
         }
         false
     }
 
-    pub fn raycast_closest_hit(&self, ray: Ray3) -> Option<WorldHitResponse> {
-        let identity_transform = Transform::identity();
-        let mut object_id = 0;
-        let mut current_path = Vec::new();
-        self.root.raycast_closest_hit(ray, &identity_transform, &mut object_id, &self.meshes, &mut current_path)
-    }
+    /// Resolve an edge path to the child it ends at, along with the world transform of the
+    /// node holding that child (i.e. the space the child lives in).
+    fn resolve_path(&self, path: &[EdgeId]) -> Option<(Transform, &SceneGraphChild)> {
+        let (&last, ancestors) = path.split_last()?;
 
-    // Getters
-    pub fn is_dirty(&self) -> bool { 
-        self.dirty || self.hierarchy_dirty
-    }
-    pub fn clear_dirty(&mut self) { self.dirty = false; }
-    pub fn object_count(&self) -> usize { self.root.edges.len() }
-    
-    /// Get flattened render instances for JavaScript
-    pub fn get_render_instances(&mut self) -> &Vec<RenderInstance> {
-        self.rebuild_cache();
-        &self.cached_render_instances
+        let mut current = &self.root;
+        let mut chain = vec![self.root.transform.clone()];
+        for &edge_id in ancestors {
+            let edge = current.edges.iter().find(|e| e.edge_id == edge_id)?;
+            match &edge.child {
+                SceneGraphChild::Node(node) => {
+                    chain.push(node.transform.clone());
+                    current = node;
+                }
+                // Models are leaf nodes, nothing can follow them in a path
+                SceneGraphChild::Model(_) => return None,
+            }
+        }
+
+        let edge = current.edges.iter().find(|e| e.edge_id == last)?;
+        Some((Transform::compose_chain(&chain), &edge.child))
     }
-    
-    pub fn clear(&mut self) {
-        self.root = SceneGraphNode::new();
-        self.meshes.clear();
-        self.cached_render_instances.clear();
-        self.hierarchy_dirty = true;
-        self.selected_path = None;
+
+    /// World transform of the object at `path`: a node's own transform within its ancestors,
+    /// or for a model, that of its enclosing node
+    pub fn world_transform(&self, path: &[EdgeId]) -> Option<Transform> {
+        let (parent_transform, child) = self.resolve_path(path)?;
+        match child {
+            SceneGraphChild::Node(node) => Some(node.transform.compose_with_parent(&parent_transform)),
+            SceneGraphChild::Model(_) => Some(parent_transform),
+        }
     }
 
-    /// Get mesh data by ID for JavaScript
-    pub fn get_mesh(&self, mesh_id: MeshId) -> Option<&crate::Mesh> {
-        self.meshes.get(&mesh_id).map(|entry| entry.model.get_mesh())
+    /// Transform applied to the whole scene, above every object
+    pub fn root_transform(&self) -> &Transform {
+        &self.root.transform
     }
 
-    /// Get list of all models (id + name) for UI display
-    pub fn get_model_list(&self) -> Vec<(MeshId, String)> {
-        self.meshes.iter()
-            .map(|(id, entry)| (*id, entry.name.clone()))
-            .collect()
+    /// Transform the whole scene at once, e.g. to turn a Z-up import Y-up
+    pub fn set_root_transform(&mut self, transform: Transform) {
+        self.root.transform = transform.clone();
+        self.events.push(SceneEvent::TransformChanged { path: Vec::new(), transform });
+        self.hierarchy_dirty = true;  // World transforms of the cached instances changed
     }
-    
-    /// Select an item by edge ID path
-    pub fn select_by_edge_path(&mut self, path: Vec<EdgeId>) -> bool {
-        if self.edge_path_is_valid(&path) {
-            self.selected_path = Some(path);
-            self.hierarchy_dirty = true;  // Need to rebuild to mark selected instances
-            true
-        } else {
-            false
+
+    /// Path of the node holding the transform of the object at `path`: the node itself, or for
+    /// a model, its enclosing node. Models directly under the root have no node of their own.
+    fn object_node_path<'a>(&self, path: &'a [EdgeId]) -> Option<&'a [EdgeId]> {
+        match self.resolve_path(path)?.1 {
+            SceneGraphChild::Node(_) => Some(path),
+            SceneGraphChild::Model(_) if path.len() > 1 => Some(&path[..path.len() - 1]),
+            SceneGraphChild::Model(_) => None,
         }
     }
-    
-    /// Deselect current selection
-    pub fn deselect(&mut self) {
-        if self.selected_path.is_some() {
-            self.selected_path = None;
-            self.hierarchy_dirty = true;  // Need to rebuild to unmark instances
+
+    /// The node at the end of a path of node edges; the root for an empty path
+    fn node_mut(&mut self, path: &[EdgeId]) -> Option<&mut SceneGraphNode> {
+        let mut node = &mut self.root;
+        for &edge_id in path {
+            let edge = node.edges.iter_mut().find(|e| e.edge_id == edge_id)?;
+            match &mut edge.child {
+                SceneGraphChild::Node(child_node) => node = child_node,
+                SceneGraphChild::Model(_) => return None,
+            }
         }
+        Some(node)
     }
-    
-    /// Check if an edge ID path is valid in the hierarchy
-    fn edge_path_is_valid(&self, path: &[EdgeId]) -> bool {
-        if path.is_empty() {
-            return false;
-        }
-        
-        let mut current = &self.root;
-        for (i, &edge_id) in path.iter().enumerate() {
-            // Find edge with matching ID
-            let edge = current.edges.iter().find(|e| e.edge_id == edge_id);
-            match edge {
-                Some(e) => match &e.child {
-                    SceneGraphChild::Node(node) => {
-                        current = node;
-                    }
-                    SceneGraphChild::Model(_) => {
-                        // Models are leaf nodes, path should end here
-                        return i == path.len() - 1;
-                    }
-                }
-                None => return false,
-            }
+
+    /// Record the current local transform of the node at `node_path` (the root if empty)
+    fn record_transform_changed(&mut self, node_path: &[EdgeId]) {
+        if let Some(node) = self.node_mut(node_path) {
+            let transform = node.transform.clone();
+            self.events.push(SceneEvent::TransformChanged { path: node_path.to_vec(), transform });
         }
-        true
     }
-    
-    /// Get the currently selected path
-    pub fn get_selected_path(&self) -> Option<&Vec<EdgeId>> {
-        self.selected_path.as_ref()
+
+    /// The node holding the transform of the object at `path` (see `object_node_path`)
+    fn object_node_mut(&mut self, path: &[EdgeId]) -> Option<&mut SceneGraphNode> {
+        let node_path = self.object_node_path(path)?;
+        self.node_mut(node_path)
     }
-    
-    /// Select parent of currently selected item
-    pub fn select_parent(&mut self) -> bool {
-        if let Some(path) = &self.selected_path {
-            if path.len() > 1 {
-                let parent_path = path[..path.len()-1].to_vec();
-                self.selected_path = Some(parent_path);
-                self.hierarchy_dirty = true;
-                return true;
+
+    // =================== FLOATING ORIGIN ===================
+    //
+    // Geometry and transforms are stored in f32, which only resolves about 0.06 units at a
+    // million units from the origin. For large-coordinate scenes (site plans, GIS data) the
+    // scene keeps its working coordinates near zero and remembers, in f64, where that working
+    // origin sits in the world. Positions are rebased with `world_to_scene` when they enter
+    // the scene and restored with `scene_to_world` when they leave it; everything in between
+    // (rendering, raycasts, edits) stays in small, precise working coordinates.
+
+    /// World position of the working origin
+    pub fn origin_offset(&self) -> [f64; 3] {
+        self.origin_offset
+    }
+
+    /// Move the working origin to `offset` (in world coordinates). Top-level objects are shifted
+    /// so they keep their world position; models directly under the root have no transform
+    /// to shift and move with the origin. Set the offset before adding far-away content, since
+    /// anything already stored far from zero has lost its precision.
+    pub fn set_origin_offset(&mut self, offset: [f64; 3]) {
+        let delta = [
+            (self.origin_offset[0] - offset[0]) as f32,
+            (self.origin_offset[1] - offset[1]) as f32,
+            (self.origin_offset[2] - offset[2]) as f32,
+        ];
+        let shift = Transform::from_position(delta);
+        for edge in &mut self.root.edges {
+            if let SceneGraphChild::Node(node) = &mut edge.child {
+                node.transform = node.transform.compose_with_parent(&shift);
             }
         }
-        false
+        self.origin_offset = offset;
+        self.events.push(SceneEvent::OriginChanged { offset });
+        self.hierarchy_dirty = true;
     }
-    
-    /// Get scene graph hierarchy for UI visualization
-    pub fn get_scene_graph(&self) -> Vec<SceneGraphNodeData> {
-        self.root.edges.iter().map(|edge| {
-            self.serialize_edge(edge, &[], self.selected_path.as_ref())
-        }).collect()
+
+    /// Convert a world position to working scene coordinates
+    pub fn world_to_scene(&self, world: [f64; 3]) -> Point3 {
+        Point3::new(
+            (world[0] - self.origin_offset[0]) as f32,
+            (world[1] - self.origin_offset[1]) as f32,
+            (world[2] - self.origin_offset[2]) as f32,
+        )
     }
-    
-    fn serialize_edge(
-        &self,
-        edge: &SceneGraphEdge,
-        current_path: &[EdgeId],
-        selected_path: Option<&Vec<EdgeId>>
-    ) -> SceneGraphNodeData {
-        let mut path = current_path.to_vec();
-        path.push(edge.edge_id);
-        
-        let is_selected = selected_path.map_or(false, |sel| sel == &path);
-        
-        match &edge.child {
-            SceneGraphChild::Node(node) => {
-                SceneGraphNodeData {
-                    edge_id: edge.edge_id.to_string(),
-                    name: "Group".to_string(),
-                    transform: node.transform.clone(),
-                    children: node.edges.iter().map(|child_edge| {
-                        self.serialize_edge(child_edge, &path, selected_path)
-                    }).collect(),
-                    is_model: false,
-                    mesh_id: None,
-                    is_selected,
-                }
-            }
-            SceneGraphChild::Model(mesh_id) => {
-                let name = self.meshes.get(mesh_id)
-                    .map(|entry| entry.name.clone())
-                    .unwrap_or_else(|| "Unknown".to_string());
-                
-                SceneGraphNodeData {
-                    edge_id: edge.edge_id.to_string(),
-                    name,
-                    transform: Transform::identity(), // Models use parent transform
-                    children: vec![],
-                    is_model: true,
-                    mesh_id: Some(mesh_id.0.to_string()),
-                    is_selected,
-                }
-            }
-        }
+
+    /// Convert a working scene position back to world coordinates
+    pub fn scene_to_world(&self, point: Point3) -> [f64; 3] {
+        [
+            point.vec3.x as f64 + self.origin_offset[0],
+            point.vec3.y as f64 + self.origin_offset[1],
+            point.vec3.z as f64 + self.origin_offset[2],
+        ]
     }
-}
 
-// =================== JS INTERFACE LAYER ===================
+    /// Add an empty named layer: a group directly under the root whose visibility and
+    /// lock state apply to everything placed in it
+    pub fn add_layer(&mut self, name: &str) -> EdgeId {
+        let mut layer = SceneGraphNode::new();
+        layer.name = Some(name.to_string());
+        self.hierarchy_dirty = true;
+        let edge_id = self.next_edge_id();
+        self.events.push(SceneEvent::ObjectAdded { id: edge_id, path: Vec::new() });
+        self.root.add_child_with_id(edge_id, SceneGraphChild::Node(Box::new(layer)))
+    }
 
-/// JavaScript interface - handles conversions and WASM bindings
-#[wasm_bindgen]
-pub struct SceneAPI {
-    core: Scene,
-}
+    /// The layer node with the given edge ID. Only named groups under the root are layers.
+    fn layer_mut(&mut self, layer_id: EdgeId) -> Option<&mut SceneGraphNode> {
+        self.root.edges.iter_mut()
+            .find(|edge| edge.edge_id == layer_id)
+            .and_then(|edge| match &mut edge.child {
+                SceneGraphChild::Node(node) if node.name.is_some() => Some(node.as_mut()),
+                _ => None,
+            })
+    }
 
-// Structs for passing information to the front end
-#[derive(Serialize, Deserialize)]
-struct HitPosition {
-    x: f32,
-    y: f32,
-    z: f32,
-}
+    /// Show or hide a layer. Hidden layers are skipped by rendering and raycasts.
+    pub fn set_layer_visible(&mut self, layer_id: EdgeId, visible: bool) -> bool {
+        let Some(layer) = self.layer_mut(layer_id) else {
+            return false;
+        };
+        layer.visible = visible;
+        self.events.push(SceneEvent::VisibilityChanged { path: vec![layer_id], visible });
+        self.hierarchy_dirty = true;
+        true
+    }
 
-#[derive(Serialize, Deserialize)]
-struct HitData {
-    position: HitPosition,
-    object_id: usize,
-    selection_path: Vec<String>,  // Edge IDs as strings for JavaScript
-}
+    /// Lock or unlock a layer. Locked layers still render but can't be picked.
+    pub fn set_layer_locked(&mut self, layer_id: EdgeId, locked: bool) -> bool {
+        let Some(layer) = self.layer_mut(layer_id) else {
+            return false;
+        };
+        layer.locked = locked;
+        self.events.push(SceneEvent::LockChanged { path: vec![layer_id], locked });
+        true
+    }
 
+    /// All layers, in scene order
+    pub fn layers(&self) -> Vec<LayerInfo> {
+        self.root.edges.iter()
+            .filter_map(|edge| match &edge.child {
+                SceneGraphChild::Node(node) => node.name.as_ref().map(|name| LayerInfo {
+                    id: edge.edge_id.to_string(),
+                    name: name.clone(),
+                    visible: node.visible,
+                    locked: node.locked,
+                }),
+                SceneGraphChild::Model(_) => None,
+            })
+            .collect()
+    }
 
-// Public functions are exposed to the front end (JS) and handle conversions,
-// private functions handle actual scene management
-#[wasm_bindgen]
-impl SceneAPI {
-    #[wasm_bindgen(constructor)]
-    pub fn new() -> SceneAPI {
-        console_log!("Creating new Rust Scene");
-        SceneAPI {
-            core: Scene::new(),
+    /// Set the transforms of several objects at once, marking the scene dirty only once.
+    /// Returns how many of the paths were valid and updated.
+    pub fn update_transforms(&mut self, updates: Vec<(Vec<EdgeId>, Transform)>) -> usize {
+        let mut updated = 0;
+        for (path, transform) in updates {
+            let Some(node_path) = self.object_node_path(&path) else {
+                continue;
+            };
+            if let Some(node) = self.node_mut(node_path) {
+                node.transform = transform.clone();
+                self.events.push(SceneEvent::TransformChanged { path: node_path.to_vec(), transform });
+                updated += 1;
+            }
         }
-    }
 
-    /// Add a cube to the scene
-    pub fn add_cube(&mut self, size: f32) -> String {
-        let mesh_id = self.core.add_cube(size);
-        console_log!("Created cube with mesh_id {}", mesh_id.0);
-        mesh_id.0.to_string()
+        if updated > 0 {
+            self.hierarchy_dirty = true;  // World transforms of the cached instances changed
+        }
+        updated
     }
 
-    /// Add a sphere to the scene
-    pub fn add_sphere(&mut self, radius: f32) -> String {
-        let mesh_id = self.core.add_sphere(radius);
-        console_log!("Created sphere with mesh_id {}", mesh_id.0);
-        mesh_id.0.to_string()
+    /// Snap the object's translation to a grid and its rotation to angle steps.
+    /// A grid or step of 0 leaves that channel untouched.
+    pub fn snap_object(&mut self, path: &[EdgeId], translation_grid: f32, rotation_step: f32) -> bool {
+        let Some(node) = self.object_node_mut(path) else {
+            return false;
+        };
+        node.transform = node.transform
+            .snap_translation(translation_grid)
+            .snap_rotation(rotation_step);
+        self.record_transform_changed(self.object_node_path(path).unwrap_or_default());
+        self.hierarchy_dirty = true;  // World transforms of the cached instances changed
+        true
     }
 
-    /// Add a plane to the scene
-    pub fn add_plane(&mut self, size: f32) -> String {
-        let mesh_id = self.core.add_plane(size);
-        console_log!("Created plane with mesh_id {}", mesh_id.0);
-        mesh_id.0.to_string()
+    /// Mirror an object across a world-space plane. The reflection is applied to the object's
+    /// transform rather than baked into its mesh, so instances sharing the mesh are unaffected;
+    /// the transform's negative determinant makes renderers and `Mesh::apply_transform` flip
+    /// the winding so faces stay outward-facing. Parents are assumed to scale uniformly.
+    /// Returns false for invalid paths, models directly under the root, or a zero normal.
+    pub fn mirror_object(&mut self, path: &[EdgeId], plane_point: Point3, plane_normal: Vec3) -> bool {
+        let Some(reflection) = Transform::from_reflection(
+            [plane_point.vec3.x, plane_point.vec3.y, plane_point.vec3.z],
+            [plane_normal.x, plane_normal.y, plane_normal.z],
+        ) else {
+            return false;
+        };
+        let Some((parent_world, _)) = self.object_node_path(path).and_then(|node_path| self.resolve_path(node_path)) else {
+            return false;
+        };
+        let Some(node) = self.object_node_mut(path) else {
+            return false;
+        };
+
+        // Reflect in world space, then express the result relative to the parent again
+        node.transform = node.transform
+            .compose_with_parent(&parent_world)
+            .compose_with_parent(&reflection)
+            .compose_with_parent(&parent_world.inverse());
+        self.record_transform_changed(self.object_node_path(path).unwrap_or_default());
+        self.hierarchy_dirty = true;
+        true
     }
 
-    pub fn import_obj(&mut self, filename: String, obj_text: String) -> Result<String, JsValue> {
-        let mesh = parse_obj_to_mesh(&obj_text).map_err(|e| JsValue::from_str(&e))?;
-        let name = Scene::name_from_obj(&filename);
-        let mesh_id = self.core.add_raw_mesh_named(mesh, name);
-        console_log!("Imported OBJ '{}' with mesh_id {}", filename, mesh_id.0);
-        Ok(mesh_id.0.to_string())
+    /// Lay out the object at `path` as a row of `count` instances: the object itself is
+    /// instance 0, and copy `k` (from 1 to `count - 1`) is its node translated by `k * offset`
+    /// in the parent's space, added next to it under the same parent. Copies clone the node's
+    /// subtree, so they share its meshes rather than duplicating geometry, but every edge in a
+    /// copy gets a fresh ID so stable IDs stay unique. Returns the paths of the new nodes, or
+    /// nothing for invalid paths and models directly under the root (which have no transform
+    /// to offset).
+    pub fn duplicate_array(&mut self, path: &[EdgeId], count: u32, offset: [f32; 3]) -> Vec<Vec<EdgeId>> {
+        let Some(node_path) = self.object_node_path(path).map(<[EdgeId]>::to_vec) else {
+            return Vec::new();
+        };
+        let Some(SceneGraphChild::Node(source)) = self.resolve_path(&node_path).map(|(_, child)| child.clone()) else {
+            return Vec::new();
+        };
+        let (_, parent_path) = node_path.split_last().unwrap();
+        let copies: Vec<(EdgeId, Box<SceneGraphNode>)> = (1..count)
+            .map(|k| {
+                let shift = Transform::from_position(offset.map(|axis| axis * k as f32));
+                let mut copy = source.clone();
+                copy.transform = copy.transform.compose_with_parent(&shift);
+                self.assign_new_edge_ids(&mut copy);
+                (self.next_edge_id(), copy)
+            })
+            .collect();
+        let parent = self.node_mut(parent_path).unwrap();
+
+        let paths = copies.into_iter()
+            .map(|(edge_id, copy)| {
+                parent.add_child_with_id(edge_id, SceneGraphChild::Node(copy));
+                parent_path.iter().copied().chain([edge_id]).collect()
+            })
+            .collect::<Vec<Vec<EdgeId>>>();
+        for (&edge_id, parent_path) in paths.iter().filter_map(|path| path.split_last()) {
+            self.events.push(SceneEvent::ObjectAdded { id: edge_id, path: parent_path.to_vec() });
+        }
+        self.hierarchy_dirty = true;
+        paths
     }
 
-    pub fn remove_object(&mut self, id: usize) -> bool {
-        let success = self.core.remove_object(id);
-        if success {
-            console_log!("Removed object with id {}", id);
-        } else {
-            console_log!("Failed to remove object with id {}: not found", id);
+    /// Give every edge in a cloned subtree a new ID, so the clone doesn't share stable IDs
+    /// with the original
+    fn assign_new_edge_ids(&mut self, node: &mut SceneGraphNode) {
+        for edge in &mut node.edges {
+            edge.edge_id = self.next_edge_id();
+            if let SceneGraphChild::Node(child) = &mut edge.child {
+                self.assign_new_edge_ids(child);
+            }
         }
-        success
+    }
+
+    /// Attach app-defined metadata to the node of the object at `path` (see
+    /// `object_node_path`), replacing what was there. The scene never interprets it; it is
+    /// only stored and saved. Returns false for invalid paths or models directly under the root.
+    pub fn set_metadata(&mut self, path: &[EdgeId], metadata: serde_json::Value) -> bool {
+        let Some(node_path) = self.object_node_path(path) else {
+            return false;
+        };
+        match self.node_mut(node_path) {
+            Some(node) => {
+                node.metadata = metadata;
+                self.events.push(SceneEvent::MetadataChanged { path: node_path.to_vec() });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Metadata of the node of the object at `path`; null if none was set
+    pub fn metadata(&self, path: &[EdgeId]) -> Option<&serde_json::Value> {
+        match self.resolve_path(self.object_node_path(path)?)?.1 {
+            SceneGraphChild::Node(node) => Some(&node.metadata),
+            SceneGraphChild::Model(_) => None,
+        }
+    }
+
+    /// World-space axis-aligned bounds (min, max) of the object at the given path.
+    /// For a group, the bounds enclose every model underneath it.
+    /// Returns None for invalid paths or objects without geometry.
+    pub fn object_bounds(&self, path: &[EdgeId]) -> Option<(Point3, Point3)> {
+        let (parent_transform, child) = self.resolve_path(path)?;
+        match child {
+            SceneGraphChild::Node(node) => node.world_bounds(&parent_transform, &self.meshes),
+            // Models use their parent's transform
+            SceneGraphChild::Model(mesh_id) => {
+                SceneGraphNode::model_world_bounds(mesh_id, &parent_transform, &self.meshes)
+            }
+        }
+    }
+
+    /// How far from the object's center a camera with the given rotation (a quaternion) and
+    /// field of view (in radians) must sit for the sphere around its world bounds to fill the
+    /// view without being cut off. The rotation is currently unused: a sphere looks the same
+    /// from every side, so the distance doesn't depend on it. It's taken so a tighter fit to
+    /// the bounds themselves can be added without changing callers.
+    /// Returns None for invalid paths, objects without geometry, or a field of view outside (0, pi).
+    pub fn framing_distance(&self, camera_rotation: [f32; 4], fov_radians: f32, path: &[EdgeId]) -> Option<f32> {
+        let _ = camera_rotation;  // Unused for the bounding sphere, see above
+        if !(fov_radians > 0.0 && fov_radians < std::f32::consts::PI) {
+            return None;
+        }
+        let (min, max) = self.object_bounds(path)?;
+        let radius = (max - min).vec3.length() * 0.5;
+        Some(radius / (fov_radians * 0.5).sin())
+    }
+
+    /// Ids of the objects whose world-space bounds overlap a sphere
+    pub fn objects_in_sphere(&mut self, center: Point3, radius: f32) -> Vec<usize> {
+        self.ensure_synced();
+        self.rebuild_cache();
+        self.cached_render_instances.iter()
+            .filter(|instance| {
+                SceneGraphNode::model_world_bounds(&instance.mesh_id, &instance.transform, &self.meshes)
+                    .is_some_and(|(min, max)| bounds_intersect_sphere(min, max, center, radius))
+            })
+            .map(|instance| instance.id)
+            .collect()
+    }
+
+    /// World-space copy of the object's geometry (vertices already transformed).
+    /// For a group, all models underneath it are merged into one mesh.
+    /// Returns None for invalid paths.
+    pub fn world_mesh(&self, path: &[EdgeId]) -> Option<Mesh> {
+        let (parent_transform, child) = self.resolve_path(path)?;
+        match child {
+            SceneGraphChild::Node(node) => Some(node.flatten_subtree_to_mesh(&parent_transform, &self.meshes)),
+            SceneGraphChild::Model(mesh_id) => {
+                let mut mesh = self.meshes.get(mesh_id)?.model.get_mesh().clone();
+                mesh.apply_transform(&parent_transform);
+                Some(mesh)
+            }
+        }
+    }
+
+    /// Swap the geometry of the object at `path` for `mesh`, keeping its place in the graph,
+    /// its transform and the object selection. The path may end at a model, or at a node
+    /// holding exactly one model. Editable models stay editable.
+    ///
+    /// The new geometry is always stored under a fresh mesh id and only this object is
+    /// pointed at it: other instances sharing the old mesh keep their geometry, and the old
+    /// mesh (with its LOD levels) is dropped once nothing references it. The element
+    /// selection is cleared if it belonged to the replaced object, since its indices no longer
+    /// apply. Returns the new mesh id, or None if the path doesn't lead to a single model.
+    pub fn replace_mesh(&mut self, path: &[EdgeId], mesh: Mesh) -> Option<MeshId> {
+        let new_id = self.next_mesh_id();
+        let slot = Self::model_slot(&mut self.root, path)?;
+        let old_id = std::mem::replace(slot, new_id);
+
+        let old_entry = self.meshes.get(&old_id);
+        let name = old_entry.map(|entry| entry.name.clone()).unwrap_or_default();
+        let model = match old_entry.map(|entry| &entry.model) {
+            Some(ModelVariant::HalfEdgeMesh(_)) => {
+                ModelVariant::HalfEdgeMesh(Box::new(ModelWrapper::new(HalfEdgeMesh::from_mesh(&mesh))))
+            }
+            _ => ModelVariant::Mesh(mesh),
+        };
+        self.meshes.insert(new_id, ModelEntry { model, name });
+        self.events.push(SceneEvent::MeshAdded { mesh_id: new_id });
+        self.events.push(SceneEvent::GeometryChanged { path: path.to_vec(), mesh_id: new_id });
+
+        self.release_mesh_if_unused(old_id);
+        if self.selected_path.as_deref() == Some(path) {
+            self.mesh_selection.clear();
+        }
+        self.hierarchy_dirty = true;
+        Some(new_id)
+    }
+
+    /// Bake `transform` into the geometry of the object at `path` while keeping it in place:
+    /// the vertices are transformed and the object's node takes the inverse, so e.g. baking
+    /// the node's scale leaves it with unit scale and resized geometry (Blender's "apply
+    /// scale"). Child nodes of the object are compensated so they don't move either.
+    ///
+    /// The baked geometry is stored under a fresh mesh id: a mesh shared with other instances
+    /// is copied first so they keep their geometry, and an unshared one is moved over (its LOD
+    /// levels are dropped, as they no longer match). Returns false for invalid paths, objects
+    /// whose node doesn't hold exactly one model, models directly under the root (which have
+    /// no transform to compensate) and non-invertible transforms.
+    pub fn apply_transform_to_geometry(&mut self, path: &[EdgeId], transform: &Transform) -> bool {
+        if transform.matrix().determinant().abs() <= f32::EPSILON {
+            return false;
+        }
+        let Some(node_path) = self.object_node_path(path).map(<[EdgeId]>::to_vec) else {
+            return false;
+        };
+        let Some(old_id) = Self::model_slot(&mut self.root, &node_path).map(|slot| *slot) else {
+            return false;
+        };
+        let shared = Self::mesh_references(&self.root, old_id) > 1;
+        let entry = match shared {
+            true => self.meshes.get(&old_id).cloned(),
+            false => {
+                self.lod_levels.remove(&old_id);
+                self.adjacency.remove(&old_id);
+                self.meshes.remove(&old_id)
+            }
+        };
+        let Some(mut entry) = entry else {
+            return false;
+        };
+
+        entry.model.apply_transform(transform);
+        let new_id = self.next_mesh_id();
+        self.meshes.insert(new_id, entry);
+        *Self::model_slot(&mut self.root, &node_path).unwrap() = new_id;
+
+        let node = self.node_mut(&node_path).unwrap();
+        node.transform = transform.inverse().compose_with_parent(&node.transform);
+        let mut changed = vec![node_path.clone()];
+        for edge in &mut node.edges {
+            if let SceneGraphChild::Node(child) = &mut edge.child {
+                child.transform = child.transform.compose_with_parent(transform);
+                changed.push(node_path.iter().copied().chain([edge.edge_id]).collect());
+            }
+        }
+        self.events.push(SceneEvent::MeshAdded { mesh_id: new_id });
+        self.events.push(SceneEvent::GeometryChanged { path: node_path, mesh_id: new_id });
+        if !shared {
+            self.events.push(SceneEvent::MeshRemoved { mesh_id: old_id });
+        }
+        for path in changed {
+            self.record_transform_changed(&path);
+        }
+        self.hierarchy_dirty = true;
+        true
+    }
+
+    /// The mesh id slot of the single model a path leads to: the path may end at a model,
+    /// or at a node holding exactly one model
+    fn model_slot<'a>(mut node: &'a mut SceneGraphNode, path: &[EdgeId]) -> Option<&'a mut MeshId> {
+        let (&last, ancestors) = path.split_last()?;
+        for &edge_id in ancestors {
+            match &mut node.edges.iter_mut().find(|e| e.edge_id == edge_id)?.child {
+                SceneGraphChild::Node(child_node) => node = child_node,
+                SceneGraphChild::Model(_) => return None,
+            }
+        }
+        match &mut node.edges.iter_mut().find(|e| e.edge_id == last)?.child {
+            SceneGraphChild::Model(mesh_id) => Some(mesh_id),
+            SceneGraphChild::Node(group) => {
+                let mut models = group.edges.iter_mut().filter_map(|edge| match &mut edge.child {
+                    SceneGraphChild::Model(mesh_id) => Some(mesh_id),
+                    SceneGraphChild::Node(_) => None,
+                });
+                let only = models.next()?;
+                models.next().is_none().then_some(only)
+            }
+        }
+    }
+
+    /// Number of models in the subtree that use the given mesh
+    fn mesh_references(node: &SceneGraphNode, mesh_id: MeshId) -> usize {
+        node.edges.iter().map(|edge| match &edge.child {
+            SceneGraphChild::Model(id) => usize::from(*id == mesh_id),
+            SceneGraphChild::Node(child) => Self::mesh_references(child, mesh_id),
+        }).sum()
+    }
+
+    /// Drop a mesh (and its LOD levels and adjacency) from storage once no model in the graph references it
+    fn release_mesh_if_unused(&mut self, mesh_id: MeshId) {
+        if Self::mesh_references(&self.root, mesh_id) == 0 {
+            self.lod_levels.remove(&mesh_id);
+            self.adjacency.remove(&mesh_id);
+            if self.meshes.remove(&mesh_id).is_some() {
+                self.events.push(SceneEvent::MeshRemoved { mesh_id });
+            }
+        }
+    }
+
+    /// Merge several objects into one (like Blender's join). Each object's geometry is baked
+    /// into world space and appended into a single raw mesh, the objects are removed, and a new
+    /// node holding the result is added under their deepest common parent, positioned so the
+    /// geometry stays where it was. An object is its transform node as in `object_node_path`
+    /// (or a model directly under the root); objects inside another joined object are only
+    /// counted once. Meshes no longer used are dropped, and the selection is cleared if it was
+    /// removed. Returns the new node's path, or None if no path was valid.
+    pub fn join(&mut self, paths: &[Vec<EdgeId>]) -> Option<Vec<EdgeId>> {
+        fn collect_mesh_ids(child: &SceneGraphChild, mesh_ids: &mut Vec<MeshId>) {
+            match child {
+                SceneGraphChild::Model(mesh_id) => mesh_ids.push(*mesh_id),
+                SceneGraphChild::Node(node) => {
+                    for edge in &node.edges {
+                        collect_mesh_ids(&edge.child, mesh_ids);
+                    }
+                }
+            }
+        }
+
+        let mut objects: Vec<Vec<EdgeId>> = Vec::new();
+        for path in paths {
+            let object = match self.object_node_path(path) {
+                Some(node_path) => node_path.to_vec(),
+                None if path.len() == 1 && self.edge_path_is_valid(path) => path.clone(),
+                None => continue,
+            };
+            if !objects.contains(&object) {
+                objects.push(object);
+            }
+        }
+        let nested = |path: &Vec<EdgeId>| objects.iter().any(|other| other.len() < path.len() && path.starts_with(other));
+        let objects: Vec<Vec<EdgeId>> = objects.iter().filter(|path| !nested(path)).cloned().collect();
+        if objects.is_empty() {
+            return None;
+        }
+
+        let mut merged = Mesh::new();
+        for path in &objects {
+            merged.append(&self.world_mesh(path)?);
+        }
+
+        // Longest prefix shared by every object's parent path
+        let mut parent_path = objects[0][..objects[0].len() - 1].to_vec();
+        for path in &objects[1..] {
+            let shared = parent_path.iter().zip(path).take_while(|(a, b)| a == b).count();
+            parent_path.truncate(shared.min(path.len() - 1));
+        }
+        let parent_world = match parent_path.is_empty() {
+            true => self.root.transform.clone(),
+            false => self.world_transform(&parent_path)?,
+        };
+        merged.apply_transform(&parent_world.inverse());
+
+        let mut released = Vec::new();
+        for path in &objects {
+            let (&edge_id, object_parent) = path.split_last().unwrap();
+            let parent = self.node_mut(object_parent)?;
+            let index = parent.edges.iter().position(|edge| edge.edge_id == edge_id)?;
+            collect_mesh_ids(&parent.edges.remove(index).child, &mut released);
+            self.events.push(SceneEvent::ObjectRemoved { id: edge_id, path: object_parent.to_vec() });
+        }
+        for mesh_id in released {
+            self.release_mesh_if_unused(mesh_id);
+        }
+        if self.selected_path.as_ref().is_some_and(|selected| !self.edge_path_is_valid(selected)) {
+            self.deselect();
+        }
+
+        let mesh_id = self.add_raw_mesh_named(merged, "joined".to_string());
+        let mut node = SceneGraphNode::new();
+        node.add_child_with_id(self.next_edge_id(), SceneGraphChild::Model(mesh_id));
+        let edge_id = self.next_edge_id();
+        self.node_mut(&parent_path)?.add_child_with_id(edge_id, SceneGraphChild::Node(Box::new(node)));
+        self.events.push(SceneEvent::ObjectAdded { id: edge_id, path: parent_path.clone() });
+        self.hierarchy_dirty = true;
+        Some(parent_path.into_iter().chain([edge_id]).collect())
+    }
+
+    /// Closest hit along the ray, or `None` on a miss. A ray with a zero (or non-finite)
+    /// direction never hits anything.
+    ///
+    /// While the render cache is current, only instances whose world bounds the ray passes
+    /// through are tested, nearest first, stopping once the remaining ones start beyond the
+    /// closest hit. Otherwise every model in the graph is tested.
+    pub fn raycast_closest_hit(&self, ray: Ray3, options: &RaycastOptions) -> Option<WorldHitResponse> {
+        if ray.is_degenerate() {
+            return None;
+        }
+        if !self.hierarchy_dirty {
+            let mut closest: Option<WorldHitResponse> = None;
+            for (entry, index) in self.instance_tree.ray_candidates(&ray) {
+                if closest.as_ref().is_some_and(|hit| entry > hit.distance) {
+                    break;
+                }
+                if let Some(hit) = self.raycast_instance(index, ray, options) {
+                    // Ties go to the lower object id, as in a traversal of the graph
+                    if closest.as_ref().is_none_or(|best| (hit.distance, hit.object_id) < (best.distance, best.object_id)) {
+                        closest = Some(hit);
+                    }
+                }
+            }
+            return closest;
+        }
+
+        let identity_transform = Transform::identity();
+        let mut object_id = 0;
+        let mut current_path = Vec::new();
+        self.root.raycast_closest_hit(ray, &identity_transform, &mut object_id, &self.meshes, &mut current_path, options, self.max_depth)
+    }
+
+    /// Every object the ray hits, nearest first, each with its closest hit. Hidden, locked
+    /// and ignored objects are skipped as for `raycast_closest_hit`.
+    pub fn raycast_all_hits(&self, ray: Ray3, options: &RaycastOptions) -> Vec<WorldHitResponse> {
+        let mut hits = Vec::new();
+        if ray.is_degenerate() {
+            return hits;
+        }
+        if self.hierarchy_dirty {
+            let mut object_id = 0;
+            let mut current_path = Vec::new();
+            self.root.raycast_each_model(ray, &Transform::identity(), &mut object_id, &self.meshes, &mut current_path, options, self.max_depth, &mut |hit| hits.push(hit));
+        } else {
+            hits.extend(self.instance_tree.ray_candidates(&ray).into_iter()
+                .filter_map(|(_, index)| self.raycast_instance(index, ray, options)));
+        }
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance).then(a.object_id.cmp(&b.object_id)));
+        hits
+    }
+
+    /// Closest hit of the ray on a cached render instance, unless it is ignored or locked
+    fn raycast_instance(&self, index: usize, ray: Ray3, options: &RaycastOptions) -> Option<WorldHitResponse> {
+        let instance = &self.cached_render_instances[index];
+        if options.ignore.contains(&instance.id) || self.path_is_locked(&instance.path) {
+            return None;
+        }
+        let entry = self.meshes.get(&instance.mesh_id)?;
+        let mut hit = SceneGraphNode::raycast_model(ray, &entry.model, &instance.transform, instance.id, options)?;
+        hit.selection_path = instance.path.clone();
+        Some(hit)
+    }
+
+    /// Whether any node along the path is locked
+    fn path_is_locked(&self, path: &[EdgeId]) -> bool {
+        let mut node = &self.root;
+        for &edge_id in path {
+            match node.edges.iter().find(|edge| edge.edge_id == edge_id).map(|edge| &edge.child) {
+                Some(SceneGraphChild::Node(child)) if child.locked => return true,
+                Some(SceneGraphChild::Node(child)) => node = child,
+                _ => return false,
+            }
+        }
+        false
+    }
+
+    /// Click-through picking for overlapping objects: the id of the next object behind
+    /// `current` along the ray, wrapping round to the nearest after the furthest. Without a
+    /// current object, or if the ray doesn't pass through it, the nearest object is picked.
+    /// Returns None if the ray hits nothing.
+    pub fn pick_cycle(&self, ray: Ray3, current: Option<usize>) -> Option<usize> {
+        let hits = self.raycast_all_hits(ray, &RaycastOptions::default());
+        let next = current
+            .and_then(|current| hits.iter().position(|hit| hit.object_id == current))
+            .map_or(0, |index| (index + 1) % hits.len());
+        hits.get(next).map(|hit| hit.object_id)
+    }
+
+    /// Raycast, then move the hit point to the nearest feature of the element that was hit:
+    /// a corner, an edge midpoint, or the center. For editable models the element is the
+    /// half-edge face the hit triangle belongs to, so quads snap to their real edges and center
+    /// rather than those of the triangle. Returns the raw hit with the snapped world position.
+    pub fn raycast_snap(&self, ray: Ray3, snap: SnapMode) -> Option<(WorldHitResponse, Point3)> {
+        let hit = self.raycast_closest_hit(ray, &RaycastOptions::default())?;
+        let hit_position = hit.hit_response.hit_position;
+        if snap == SnapMode::None {
+            return Some((hit, hit_position));
+        }
+
+        let Some((world_transform, SceneGraphChild::Model(mesh_id))) = self.resolve_path(&hit.selection_path) else {
+            return Some((hit, hit_position));
+        };
+        let Some(entry) = self.meshes.get(mesh_id) else {
+            return Some((hit, hit_position));
+        };
+
+        let corners: Vec<Point3> = match &entry.model {
+            ModelVariant::HalfEdgeMesh(hemw) => {
+                let mesh = hemw.model();
+                mesh.triangle_face(hit.triangle_index)
+                    .map(|face| mesh.face_vertices(face).into_iter().map(|v| mesh.vertex(v).position).collect())
+                    .unwrap_or_default()
+            }
+            // Other models snap to the corners of the render triangle
+            model => {
+                let mesh = model.get_mesh();
+                mesh.face_indices.chunks_exact(3).nth(hit.triangle_index)
+                    .map(|tri| tri.iter().map(|&i| {
+                        let i = i as usize;
+                        Point3::new(mesh.vertex_coords[3 * i], mesh.vertex_coords[3 * i + 1], mesh.vertex_coords[3 * i + 2])
+                    }).collect())
+                    .unwrap_or_default()
+            }
+        };
+        let corners: Vec<Vec3> = corners.iter().map(|corner| corner.transform(&world_transform).vec3).collect();
+        if corners.is_empty() {
+            return Some((hit, hit_position));
+        }
+
+        let candidates: Vec<Vec3> = match snap {
+            SnapMode::None | SnapMode::Vertex => corners,
+            SnapMode::EdgeMidpoint => (0..corners.len())
+                .map(|i| (corners[i] + corners[(i + 1) % corners.len()]) * 0.5)
+                .collect(),
+            SnapMode::FaceCenter => {
+                let sum = corners.iter().fold(Vec3::new(0.0, 0.0, 0.0), |sum, &corner| sum + corner);
+                vec![sum * (1.0 / corners.len() as f32)]
+            }
+        };
+        let snapped = candidates.into_iter()
+            .min_by(|a, b| (*a - hit_position.vec3).length().total_cmp(&(*b - hit_position.vec3).length()))
+            .map_or(hit_position, |vec3| Point3 { vec3 });
+        Some((hit, snapped))
+    }
+
+    /// Rough estimate of the scene's memory use in bytes: mesh buffers, graph nodes and the
+    /// render instance cache. Consistent rather than exact, it is derived from `Vec` lengths.
+    pub fn memory_estimate(&self) -> usize {
+        let mesh_bytes: usize = self.meshes.values()
+            .map(|entry| {
+                std::mem::size_of::<MeshId>() + std::mem::size_of::<ModelEntry>()
+                    + entry.name.len() + entry.model.memory_estimate()
+            })
+            .sum();
+
+        mesh_bytes
+            + self.root.memory_estimate()
+            + self.cached_render_instances.len() * std::mem::size_of::<RenderInstance>()
+    }
+
+    // Getters
+    pub fn is_dirty(&self) -> bool { 
+        self.dirty || self.hierarchy_dirty
+    }
+    pub fn clear_dirty(&mut self) { self.dirty = false; }
+    pub fn object_count(&self) -> usize { self.root.edges.len() }
+    pub fn rebuild_count(&self) -> usize { self.rebuild_count }
+
+    /// Walk the whole scene graph depth-first with a visitor, starting at the root
+    pub fn accept<V: SceneVisitor + ?Sized>(&self, visitor: &mut V) {
+        self.root.accept(&Transform::identity(), visitor);
+    }
+
+    /// Total rendered triangles over every placed model instance
+    pub fn triangle_count(&self) -> usize {
+        struct TriangleCounter<'a> {
+            meshes: &'a HashMap<MeshId, ModelEntry>,
+            total: usize,
+        }
+
+        impl SceneVisitor for TriangleCounter<'_> {
+            fn visit_model(&mut self, mesh_id: MeshId, _world_transform: &Transform) {
+                if let Some(entry) = self.meshes.get(&mesh_id) {
+                    self.total += entry.model.triangle_count();
+                }
+            }
+        }
+
+        let mut counter = TriangleCounter { meshes: &self.meshes, total: 0 };
+        self.accept(&mut counter);
+        counter.total
+    }
+    
+    /// Bring every model's render mesh up to date with its pending edits, placed in the
+    /// graph or not. Read paths call this first so they never hand out stale geometry.
+    pub fn ensure_synced(&mut self) {
+        for entry in self.meshes.values_mut() {
+            entry.model.sync_render_mesh();
+        }
+    }
+
+    /// Get flattened render instances for JavaScript
+    pub fn get_render_instances(&mut self) -> &Vec<RenderInstance> {
+        self.ensure_synced();
+        self.rebuild_cache();
+        &self.cached_render_instances
+    }
+    
+    /// Edge path of the render instance with the given object id (as assigned by
+    /// `flatten_to_render_instances` and reported by raycasts)
+    pub fn path_for_object_id(&mut self, object_id: usize) -> Option<Vec<EdgeId>> {
+        self.rebuild_cache();
+        self.cached_render_instances.iter()
+            .find(|instance| instance.id == object_id)
+            .map(|instance| instance.path.clone())
+    }
+
+    pub fn clear(&mut self) {
+        self.root = SceneGraphNode::new();
+        self.meshes.clear();
+        self.lod_levels.clear();
+        self.adjacency.clear();
+        self.cached_render_instances.clear();
+        self.hierarchy_dirty = true;
+        self.selected_path = None;
+        self.mesh_selection.clear();
+        self.events.push(SceneEvent::Reset);
+    }
+
+    /// Register simplified versions of a mesh, ordered from finest to coarsest. Level 0 is
+    /// always the mesh itself; `levels[i]` is level `i + 1`. Returns false for an unknown mesh.
+    pub fn set_lod_levels(&mut self, mesh_id: MeshId, levels: Vec<Mesh>) -> bool {
+        if !self.meshes.contains_key(&mesh_id) {
+            return false;
+        }
+        self.lod_levels.insert(mesh_id, levels);
+        true
+    }
+
+    /// Build the coarser levels of detail of a mesh by simplifying it to each of `ratios`
+    /// (fractions of its faces, finest first) and register them as `set_lod_levels` does.
+    /// Returns false for an unknown mesh.
+    pub fn generate_lod_levels(&mut self, mesh_id: MeshId, ratios: &[f32]) -> bool {
+        let Some(entry) = self.meshes.get(&mesh_id) else {
+            return false;
+        };
+        let converted;
+        let source = match &entry.model {
+            ModelVariant::HalfEdgeMesh(hemw) => hemw.model(),
+            model => {
+                converted = HalfEdgeMesh::from_mesh(model.get_mesh());
+                &converted
+            }
+        };
+        let levels = ratios.iter()
+            .map(|&ratio| {
+                let mut level = source.simplify_to_ratio(ratio).to_mesh();
+                level.compute_smooth_normals();
+                level
+            })
+            .collect();
+        self.set_lod_levels(mesh_id, levels)
+    }
+
+    /// Render mesh of a mesh at a level of detail as numbered by `select_lod`
+    pub fn lod_mesh(&self, mesh_id: MeshId, level: usize) -> Option<&Mesh> {
+        match level {
+            0 => self.get_mesh(mesh_id),
+            _ => self.lod_levels.get(&mesh_id)?.get(level - 1),
+        }
+    }
+
+    /// Pick a level of detail for every render instance from how tall its world bounds appear
+    /// on screen, given the camera's column-major view-projection matrix and the viewport height
+    /// in pixels. Returns (object id, level) pairs; level 0 is full detail, and objects too small
+    /// on screen for their coarsest registered level use that level. Objects reaching behind
+    /// the camera are treated as close and get full detail.
+    pub fn select_lod(&mut self, view_proj: [f32; 16], screen_height: f32) -> Vec<(usize, usize)> {
+        self.ensure_synced();
+        self.rebuild_cache();
+        let view_proj = glam::Mat4::from_cols_array(&view_proj);
+
+        self.cached_render_instances.iter()
+            .map(|instance| {
+                let coarsest = self.lod_levels.get(&instance.mesh_id).map_or(0, Vec::len);
+                let Some((min, max)) = SceneGraphNode::model_world_bounds(&instance.mesh_id, &instance.transform, &self.meshes) else {
+                    return (instance.id, 0);
+                };
+
+                // Extent of the projected box corners in normalized device coordinates
+                let mut ndc_min = glam::Vec2::splat(f32::INFINITY);
+                let mut ndc_max = glam::Vec2::splat(f32::NEG_INFINITY);
+                for corner in 0..8 {
+                    let clip = view_proj * glam::Vec4::new(
+                        if corner & 1 == 0 { min.vec3.x } else { max.vec3.x },
+                        if corner & 2 == 0 { min.vec3.y } else { max.vec3.y },
+                        if corner & 4 == 0 { min.vec3.z } else { max.vec3.z },
+                        1.0,
+                    );
+                    if clip.w <= f32::EPSILON {
+                        return (instance.id, 0);
+                    }
+                    let ndc = glam::Vec2::new(clip.x, clip.y) / clip.w;
+                    ndc_min = ndc_min.min(ndc);
+                    ndc_max = ndc_max.max(ndc);
+                }
+
+                // NDC spans 2 units over the screen height
+                let pixels = (ndc_max - ndc_min).max_element() * 0.5 * screen_height;
+                let level = if pixels >= LOD_FULL_DETAIL_PIXELS {
+                    0
+                } else if pixels <= 0.0 {
+                    coarsest
+                } else {
+                    ((LOD_FULL_DETAIL_PIXELS / pixels).log2().floor() as usize).min(coarsest)
+                };
+                (instance.id, level)
+            })
+            .collect()
+    }
+
+    /// Serialize the graph and mesh storage into a versioned JSON document.
+    /// Selection and render caches are not saved.
+    pub fn to_json(&self) -> Result<String, String> {
+        // Sorted by ID, so the output doesn't depend on hash map order
+        let mut meshes: Vec<(&MeshId, &ModelEntry)> = self.meshes.iter().collect();
+        meshes.sort_by_key(|(mesh_id, _)| mesh_id.0);
+        let document = SceneDocument {
+            format_version: scene_format::FORMAT_VERSION,
+            meshes: meshes.into_iter()
+                .map(|(mesh_id, entry)| MeshDocument {
+                    id: mesh_id.0,
+                    name: entry.name.clone(),
+                    geometry: GeometryDocument::from_model(&entry.model),
+                })
+                .collect(),
+            root: NodeDocument::from_node(&self.root),
+            origin_offset: self.origin_offset,
+        };
+        serde_json::to_string(&document).map_err(|e| format!("Failed to serialize scene: {e}"))
+    }
+
+    /// Load a scene saved by `to_json`. Fails with a descriptive error for unsupported format
+    /// versions, malformed documents, or models referencing meshes that weren't saved.
+    pub fn from_json(json: &str) -> Result<Scene, String> {
+        struct MeshRefs(Vec<MeshId>);
+        impl SceneVisitor for MeshRefs {
+            fn visit_model(&mut self, mesh_id: MeshId, _world_transform: &Transform) {
+                self.0.push(mesh_id);
+            }
+        }
+
+        let document = scene_format::parse_document(json)?;
+
+        let mut scene = Scene::new();
+        for mesh in document.meshes {
+            let model = mesh.geometry.into_model()
+                .map_err(|e| format!("Invalid mesh '{}': {e}", mesh.name))?;
+            scene.meshes.insert(MeshId(mesh.id), ModelEntry { model, name: mesh.name });
+        }
+        scene.root = document.root.into_node();
+        scene.origin_offset = document.origin_offset;
+
+        let mut refs = MeshRefs(Vec::new());
+        scene.accept(&mut refs);
+        if let Some(missing) = refs.0.iter().find(|mesh_id| !scene.meshes.contains_key(mesh_id)) {
+            return Err(format!("Scene references missing mesh {}", missing.0));
+        }
+
+        // Anyone following the previous scene's events has to start over
+        scene.events.push(SceneEvent::Reset);
+        Ok(scene)
+    }
+
+    /// Remove every object from the graph but keep the mesh storage, so the same
+    /// models can be placed again cheaply
+    pub fn clear_objects(&mut self) {
+        self.root = SceneGraphNode::new();
+        self.cached_render_instances.clear();
+        self.hierarchy_dirty = true;
+        self.selected_path = None;
+        self.mesh_selection.clear();
+        self.events.push(SceneEvent::Reset);
+    }
+
+    /// Changes made since the last call, oldest first (see `SceneEvent`). Events accumulate
+    /// until taken, so a scene that is being followed should be polled regularly.
+    pub fn take_events(&mut self) -> Vec<SceneEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Get mesh data by ID for JavaScript
+    /// Returns the last synced render mesh; call `ensure_synced` first to include pending edits
+    pub fn get_mesh(&self, mesh_id: MeshId) -> Option<&crate::Mesh> {
+        self.meshes.get(&mesh_id).map(|entry| entry.model.get_mesh())
+    }
+
+    /// Triangle connectivity of a mesh's render mesh, built on first use and kept until the
+    /// mesh is dropped. Most edits store geometry under a fresh mesh id, but those that change a
+    /// mesh in place (like `extrude_selected_faces`) must remove its entry from `adjacency`
+    /// themselves, or it goes stale. Built from the last synced render mesh, like `get_mesh`.
+    pub fn mesh_adjacency(&mut self, mesh_id: MeshId) -> Option<&MeshAdjacency> {
+        let mesh = self.meshes.get(&mesh_id)?.model.get_mesh();
+        Some(self.adjacency.entry(mesh_id).or_insert_with(|| MeshAdjacency::from_mesh(mesh)))
+    }
+
+    /// Get list of all models (id + name) for UI display
+    pub fn get_model_list(&self) -> Vec<(MeshId, String)> {
+        self.meshes.iter()
+            .map(|(id, entry)| (*id, entry.name.clone()))
+            .collect()
+    }
+    
+    /// Select an item by edge ID path
+    pub fn select_by_edge_path(&mut self, path: Vec<EdgeId>) -> bool {
+        if self.edge_path_is_valid(&path) {
+            if self.selected_path.as_ref() != Some(&path) {
+                // Element selection belongs to the previously edited object
+                self.mesh_selection.clear();
+            }
+            self.selected_path = Some(path);
+            self.hierarchy_dirty = true;  // Need to rebuild to mark selected instances
+            true
+        } else {
+            false
+        }
+    }
+    
+    /// Deselect current selection
+    pub fn deselect(&mut self) {
+        if self.selected_path.is_some() {
+            self.selected_path = None;
+            self.mesh_selection.clear();
+            self.hierarchy_dirty = true;  // Need to rebuild to unmark instances
+        }
+    }
+    
+    /// Check if an edge ID path is valid in the hierarchy
+    fn edge_path_is_valid(&self, path: &[EdgeId]) -> bool {
+        if path.is_empty() {
+            return false;
+        }
+        
+        let mut current = &self.root;
+        for (i, &edge_id) in path.iter().enumerate() {
+            // Find edge with matching ID
+            let edge = current.edges.iter().find(|e| e.edge_id == edge_id);
+            match edge {
+                Some(e) => match &e.child {
+                    SceneGraphChild::Node(node) => {
+                        current = node;
+                    }
+                    SceneGraphChild::Model(_) => {
+                        // Models are leaf nodes, path should end here
+                        return i == path.len() - 1;
+                    }
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+    
+    /// Get the currently selected path
+    pub fn get_selected_path(&self) -> Option<&Vec<EdgeId>> {
+        self.selected_path.as_ref()
+    }
+    
+    /// Select parent of currently selected item
+    pub fn select_parent(&mut self) -> bool {
+        if let Some(path) = &self.selected_path {
+            if path.len() > 1 {
+                let parent_path = path[..path.len()-1].to_vec();
+                self.selected_path = Some(parent_path);
+                self.mesh_selection.clear();
+                self.hierarchy_dirty = true;
+                return true;
+            }
+        }
+        false
+    }
+    
+    /// Name, path and geometry counts of the current selection, or None if nothing is selected.
+    /// A selected group reports the summed counts of every model underneath it.
+    pub fn selected_info(&self) -> Option<SelectedInfo> {
+        fn count(child: &SceneGraphChild, meshes: &HashMap<MeshId, ModelEntry>, totals: &mut (usize, usize)) {
+            match child {
+                SceneGraphChild::Node(node) => {
+                    for edge in &node.edges {
+                        count(&edge.child, meshes, totals);
+                    }
+                }
+                SceneGraphChild::Model(mesh_id) => {
+                    if let Some(entry) = meshes.get(mesh_id) {
+                        let mesh = entry.model.get_mesh();
+                        totals.0 += mesh.face_count();
+                        totals.1 += mesh.vertex_count();
+                    }
+                }
+            }
+        }
+
+        let path = self.selected_path.as_ref()?;
+        let (_, child) = self.resolve_path(path)?;
+
+        let name = match child {
+            SceneGraphChild::Node(_) => "Group".to_string(),
+            SceneGraphChild::Model(mesh_id) => self.meshes.get(mesh_id)
+                .map(|entry| entry.name.clone())
+                .unwrap_or_else(|| "Unknown".to_string()),
+        };
+
+        let mut totals = (0, 0);
+        count(child, &self.meshes, &mut totals);
+
+        Some(SelectedInfo {
+            name,
+            path: path.iter().map(|edge_id| edge_id.to_string()).collect(),
+            triangle_count: totals.0,
+            vertex_count: totals.1,
+            // Objects don't carry materials yet
+            material: None,
+        })
+    }
+
+    /// The half-edge mesh of the selected object, if the selection is an editable model
+    fn edited_half_edge_mesh(&self) -> Option<&HalfEdgeMesh> {
+        let path = self.selected_path.as_ref()?;
+        match self.resolve_path(path)?.1 {
+            SceneGraphChild::Model(mesh_id) => match &self.meshes.get(mesh_id)?.model {
+                ModelVariant::HalfEdgeMesh(hemw) => Some(hemw.model()),
+                ModelVariant::Mesh(_) | ModelVariant::Curve(_) => None,
+            },
+            SceneGraphChild::Node(_) => None,
+        }
+    }
+
+    /// Add a face of the edited object to the element selection.
+    /// Fails if no editable model is selected or the face doesn't exist.
+    pub fn select_face(&mut self, face: FaceIndex) -> bool {
+        let Some(mesh) = self.edited_half_edge_mesh() else {
+            return false;
+        };
+        if face.0 >= mesh.faces.len() || mesh.is_face_removed(face) {
+            return false;
+        }
+        self.mesh_selection.faces.insert(face);
+        true
+    }
+
+    /// Add every face connected to `face` to the element selection, selecting the whole
+    /// piece of the edited object it belongs to. Returns the number of faces in that piece.
+    pub fn select_linked(&mut self, face: FaceIndex) -> usize {
+        let Some(mesh) = self.edited_half_edge_mesh() else {
+            return 0;
+        };
+        if face.0 >= mesh.faces.len() || mesh.is_face_removed(face) {
+            return 0;
+        }
+        let linked = mesh.linked_faces(face);
+        let count = linked.len();
+        self.mesh_selection.faces.extend(linked);
+        count
+    }
+
+    /// Extrude the selected faces of the edited object by `distance` along their normals
+    /// (see `HalfEdgeMesh::extrude_faces`). The mesh is edited in place, so other objects
+    /// sharing it change as well, and the selection stays on the lifted faces.
+    /// Returns the number of wall faces added: 0 if no faces of an editable model are selected.
+    pub fn extrude_selected_faces(&mut self, distance: f32) -> usize {
+        if self.mesh_selection.faces.is_empty() {
+            return 0;
+        }
+        let Some(path) = self.selected_path.clone() else {
+            return 0;
+        };
+        let Some((_, SceneGraphChild::Model(mesh_id))) = self.resolve_path(&path) else {
+            return 0;
+        };
+        let mesh_id = *mesh_id;
+        let Some(ModelVariant::HalfEdgeMesh(hemw)) = self.meshes.get_mut(&mesh_id).map(|entry| &mut entry.model) else {
+            return 0;
+        };
+
+        let walls = hemw.model_mut().extrude_faces(&self.mesh_selection.faces, distance);
+        self.lod_levels.remove(&mesh_id);
+        self.adjacency.remove(&mesh_id);
+        self.events.push(SceneEvent::GeometryChanged { path, mesh_id });
+        self.hierarchy_dirty = true;
+        walls.len()
+    }
+
+    /// Remove a face from the element selection, returns whether it was selected
+    pub fn deselect_face(&mut self, face: FaceIndex) -> bool {
+        self.mesh_selection.faces.remove(&face)
+    }
+
+    pub fn clear_mesh_selection(&mut self) {
+        self.mesh_selection.clear();
+    }
+
+    /// Elements selected within the selected object
+    pub fn get_mesh_selection(&self) -> &MeshSelection {
+        &self.mesh_selection
+    }
+
+    /// Get scene graph hierarchy for UI visualization: the tree of edges with names, transforms,
+    /// visibility and lock flags, and mesh ids for models (but no geometry)
+    pub fn get_scene_graph(&self) -> Vec<SceneGraphNodeData> {
+        self.root.edges.iter().map(|edge| {
+            self.serialize_edge(edge, &[], self.selected_path.as_ref(), &self.root)
+        }).collect()
+    }
+    
+    fn serialize_edge(
+        &self,
+        edge: &SceneGraphEdge,
+        current_path: &[EdgeId],
+        selected_path: Option<&Vec<EdgeId>>,
+        parent: &SceneGraphNode,
+    ) -> SceneGraphNodeData {
+        let mut path = current_path.to_vec();
+        path.push(edge.edge_id);
+        
+        let is_selected = selected_path.map_or(false, |sel| sel == &path);
+        
+        match &edge.child {
+            SceneGraphChild::Node(node) => {
+                SceneGraphNodeData {
+                    edge_id: edge.edge_id.to_string(),
+                    name: node.name.clone().unwrap_or_else(|| "Group".to_string()),
+                    transform: node.transform.clone(),
+                    children: node.edges.iter().map(|child_edge| {
+                        self.serialize_edge(child_edge, &path, selected_path, node)
+                    }).collect(),
+                    is_model: false,
+                    mesh_id: None,
+                    is_selected,
+                    visible: node.visible,
+                    locked: node.locked,
+                }
+            }
+            SceneGraphChild::Model(mesh_id) => {
+                let name = self.meshes.get(mesh_id)
+                    .map(|entry| entry.name.clone())
+                    .unwrap_or_else(|| "Unknown".to_string());
+                
+                SceneGraphNodeData {
+                    edge_id: edge.edge_id.to_string(),
+                    name,
+                    transform: Transform::identity(), // Models use parent transform
+                    children: vec![],
+                    is_model: true,
+                    mesh_id: Some(mesh_id.0.to_string()),
+                    is_selected,
+                    visible: parent.visible,
+                    locked: parent.locked,
+                }
+            }
+        }
+    }
+}
+
+// =================== JS INTERFACE LAYER ===================
+
+/// JavaScript interface - handles conversions and WASM bindings
+#[wasm_bindgen]
+pub struct SceneAPI {
+    core: Scene,
+}
+
+// Structs for passing information to the front end
+#[derive(Serialize, Deserialize)]
+struct HitPosition {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BoundsData {
+    min: [f32; 3],
+    max: [f32; 3],
+}
+
+#[derive(Serialize, Deserialize)]
+struct HitData {
+    position: HitPosition,
+    object_id: usize,
+    selection_path: Vec<String>,  // Edge IDs as strings for JavaScript
+    normal: Direction3,  // World-space surface normal at the hit
+}
+
+#[derive(Serialize)]
+struct SnapHitData {
+    #[serde(flatten)]
+    hit: HitData,
+    snapped_position: Point3,
+}
+
+
+// Public functions are exposed to the front end (JS) and handle conversions,
+// private functions handle actual scene management
+#[wasm_bindgen]
+impl SceneAPI {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SceneAPI {
+        console_log!("Creating new Rust Scene");
+        SceneAPI {
+            core: Scene::new(),
+        }
+    }
+
+    /// Add a cube to the scene
+    pub fn add_cube(&mut self, size: f32) -> String {
+        let mesh_id = self.core.add_cube(size);
+        console_log!("Created cube with mesh_id {}", mesh_id.0);
+        mesh_id.0.to_string()
+    }
+
+    /// Add a sphere to the scene
+    pub fn add_sphere(&mut self, radius: f32) -> String {
+        let mesh_id = self.core.add_sphere(radius);
+        console_log!("Created sphere with mesh_id {}", mesh_id.0);
+        mesh_id.0.to_string()
+    }
+
+    /// Add a plane to the scene
+    pub fn add_plane(&mut self, size: f32) -> String {
+        let mesh_id = self.core.add_plane(size);
+        console_log!("Created plane with mesh_id {}", mesh_id.0);
+        mesh_id.0.to_string()
+    }
+
+    /// Add a tube of `radius` swept along a spline through `points` (xyz per point), with
+    /// `radial_segments` corners around and `samples_per_segment` rings between points
+    pub fn add_curve(&mut self, points: Vec<f32>, radius: f32, radial_segments: u32, samples_per_segment: u32) -> Result<String, JsValue> {
+        if points.len() < 6 || !points.len().is_multiple_of(3) {
+            return Err(JsValue::from_str("A curve needs at least two points, given as xyz triples"));
+        }
+        let control_points = points.chunks_exact(3).map(|p| Point3::new(p[0], p[1], p[2])).collect();
+        let mesh_id = self.core.add_curve(CatmullRomSpline::new(control_points, radius, radial_segments, samples_per_segment));
+        console_log!("Created curve with mesh_id {}", mesh_id.0);
+        Ok(mesh_id.0.to_string())
+    }
+
+    pub fn import_obj(&mut self, filename: String, obj_text: String) -> Result<String, JsValue> {
+        let mesh = parse_obj_to_mesh(&obj_text).map_err(|e| JsValue::from_str(&e))?;
+        let name = Scene::name_from_obj(&filename);
+        let mesh_id = self.core.add_raw_mesh_named(mesh, name);
+        console_log!("Imported OBJ '{}' with mesh_id {}", filename, mesh_id.0);
+        Ok(mesh_id.0.to_string())
+    }
+
+    /// `import_obj` for large files: `progress(fraction)` is called as the import proceeds,
+    /// and returning `false` from it cancels the import, which then throws
+    pub fn import_obj_with_progress(&mut self, filename: String, obj_text: String, progress: js_sys::Function) -> Result<String, JsValue> {
+        let mut report = |fraction: f32| {
+            // Anything but an explicit `false` (including no return value) keeps going
+            !matches!(progress.call1(&JsValue::NULL, &JsValue::from_f64(fraction as f64)), Ok(value) if value == JsValue::FALSE)
+        };
+        let mesh = parse_obj_to_mesh_with_progress(&obj_text, &mut report).map_err(|e| JsValue::from_str(&e))?;
+        let name = Scene::name_from_obj(&filename);
+        let mesh_id = self.core.add_raw_mesh_named(mesh, name);
+        console_log!("Imported OBJ '{}' with mesh_id {}", filename, mesh_id.0);
+        Ok(mesh_id.0.to_string())
+    }
+
+    pub fn remove_object(&mut self, id: usize) -> bool {
+        let success = self.core.remove_object(id);
+        if success {
+            console_log!("Removed object with id {}", id);
+        } else {
+            console_log!("Failed to remove object with id {}: not found", id);
+        }
+        success
     }
 
     pub fn update_transform(&mut self, id: usize, position: Vec<f32>, rotation: Vec<f32>, scale: Vec<f32>) {
@@ -407,50 +1662,574 @@ impl SceneAPI {
         }
     }
 
-    pub fn is_dirty(&self) -> bool { self.core.is_dirty() }
-    pub fn clear_dirty(&mut self) { self.core.clear_dirty(); }
-    pub fn object_count(&self) -> usize { self.core.object_count() }
-    
-    pub fn clear(&mut self) {
-        console_log!("Clearing scene");
-        self.core.clear();
+    /// Like `update_transform`, but first snaps to `steps` = [translate, rotate (radians), scale]
+    /// increments. A step of 0 disables snapping for that channel.
+    pub fn update_transform_snapped(&mut self, id: usize, position: Vec<f32>, rotation: Vec<f32>, scale: Vec<f32>, steps: Vec<f32>) {
+        let transform = Transform::from_position_rotation_scale(
+            [position[0], position[1], position[2]],
+            [rotation[0], rotation[1], rotation[2], rotation[3]],
+            [scale[0], scale[1], scale[2]],
+        ).snap(steps[0], steps[1], steps[2]);
+
+        if self.core.update_transform(id, transform) {
+            console_log!("Updated snapped transform for object {}", id);
+        }
+    }
+
+    /// Update many object transforms in one call, rebuilding the scene only once.
+    /// Flattened arguments: `path_lengths[i]` edge IDs of `path_edges` form the i-th path, and
+    /// `transforms` holds 10 floats per object: position (3), rotation quaternion (4), scale (3).
+    /// Returns how many objects were updated.
+    pub fn update_transforms(&mut self, path_lengths: Vec<u32>, path_edges: Vec<String>, transforms: Vec<f32>) -> usize {
+        if transforms.len() != path_lengths.len() * 10 {
+            console_log!("update_transforms: expected {} transform values, got {}", path_lengths.len() * 10, transforms.len());
+            return 0;
+        }
+
+        let mut edges = path_edges.into_iter();
+        let mut updates = Vec::with_capacity(path_lengths.len());
+        for (&length, t) in path_lengths.iter().zip(transforms.chunks_exact(10)) {
+            let path_strings: Vec<String> = edges.by_ref().take(length as usize).collect();
+            let Some(path) = Self::parse_edge_path(path_strings) else {
+                continue;
+            };
+            let transform = Transform::from_position_rotation_scale(
+                [t[0], t[1], t[2]],
+                [t[3], t[4], t[5], t[6]],
+                [t[7], t[8], t[9]],
+            );
+            updates.push((path, transform));
+        }
+
+        self.core.update_transforms(updates)
+    }
+
+    /// Join several objects into one; paths are flattened as in `update_transforms`.
+    /// Returns the new object's path, or null if no path was valid.
+    pub fn join(&mut self, path_lengths: Vec<u32>, path_edges: Vec<String>) -> JsValue {
+        self.core.ensure_synced();
+        let mut edges = path_edges.into_iter();
+        let paths: Vec<Vec<EdgeId>> = path_lengths.iter()
+            .filter_map(|&length| Self::parse_edge_path(edges.by_ref().take(length as usize).collect()))
+            .collect();
+
+        match self.core.join(&paths) {
+            Some(path) => {
+                let path: Vec<String> = path.iter().map(|edge_id| edge_id.to_string()).collect();
+                serde_wasm_bindgen::to_value(&path).unwrap()
+            }
+            None => JsValue::NULL,
+        }
+    }
+
+    pub fn get_rebuild_count(&self) -> usize { self.core.rebuild_count() }
+
+    pub fn is_dirty(&self) -> bool { self.core.is_dirty() }
+    pub fn clear_dirty(&mut self) { self.core.clear_dirty(); }
+    pub fn object_count(&self) -> usize { self.core.object_count() }
+    pub fn get_memory_estimate(&mut self) -> usize {
+        self.core.ensure_synced();
+        self.core.memory_estimate()
+    }
+
+    pub fn get_triangle_count(&self) -> usize {
+        self.core.triangle_count()
+    }
+    
+    pub fn clear(&mut self) {
+        console_log!("Clearing scene");
+        self.core.clear();
+    }
+
+    pub fn clear_objects(&mut self) {
+        console_log!("Clearing scene objects (keeping meshes)");
+        self.core.clear_objects();
+    }
+
+    /// Changes since the last call as `[{ kind, ... }]`, oldest first; see `SceneEvent`
+    pub fn take_events(&mut self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.core.take_events()).unwrap()
+    }
+
+    /// Save the scene as versioned JSON
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        self.core.to_json().map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Replace the scene with one saved by `to_json`. On error the current scene is kept.
+    pub fn load_json(&mut self, json: String) -> Result<(), JsValue> {
+        let scene = Scene::from_json(&json).map_err(|e| JsValue::from_str(&e))?;
+        console_log!("Loaded scene with {} objects", scene.object_count());
+        self.core = scene;
+        Ok(())
+    }
+
+    pub fn get_scene_data(&mut self) -> JsValue {
+        serde_wasm_bindgen::to_value(self.core.get_render_instances()).unwrap()
+    }
+
+    /// Get mesh data by ID for JavaScript
+    pub fn get_mesh_data(&mut self, mesh_id_str: String) -> JsValue {
+        self.core.ensure_synced();
+        if let Some(mesh) = Self::parse_mesh_id(&mesh_id_str).and_then(|mesh_id| self.core.get_mesh(mesh_id)) {
+            return serde_wasm_bindgen::to_value(mesh).unwrap();
+        }
+        JsValue::NULL
+    }
+
+    /// Vertex positions (xyz per vertex) of a mesh as a Float32Array, or undefined for unknown ids.
+    /// The array is a copy in JS memory, so it stays valid after the mesh changes or WASM memory grows.
+    pub fn get_mesh_positions(&mut self, mesh_id_str: String) -> Option<js_sys::Float32Array> {
+        self.core.ensure_synced();
+        let mesh = self.core.get_mesh(Self::parse_mesh_id(&mesh_id_str)?)?;
+        Some(js_sys::Float32Array::from(mesh.vertex_coords.as_slice()))
+    }
+
+    /// Triangle indices of a mesh as a Uint32Array (a copy, like `get_mesh_positions`),
+    /// or undefined for unknown ids
+    pub fn get_mesh_indices(&mut self, mesh_id_str: String) -> Option<js_sys::Uint32Array> {
+        self.core.ensure_synced();
+        let mesh = self.core.get_mesh(Self::parse_mesh_id(&mesh_id_str)?)?;
+        Some(js_sys::Uint32Array::from(mesh.face_indices.as_slice()))
+    }
+
+    /// Vertex normals (xyz per vertex) of a mesh as a Float32Array (a copy, like
+    /// `get_mesh_positions`), or undefined for unknown ids and meshes without normals
+    pub fn get_mesh_normals(&mut self, mesh_id_str: String) -> Option<js_sys::Float32Array> {
+        self.core.ensure_synced();
+        let mesh = self.core.get_mesh(Self::parse_mesh_id(&mesh_id_str)?)?;
+        let normals = mesh.normals.as_ref().filter(|normals| normals.len() == mesh.vertex_coords.len())?;
+        Some(js_sys::Float32Array::from(normals.as_slice()))
+    }
+
+    /// Line segment index pairs of a mesh as a Uint32Array (empty if it has no lines),
+    /// or undefined for unknown ids
+    pub fn get_mesh_line_indices(&mut self, mesh_id_str: String) -> Option<js_sys::Uint32Array> {
+        self.core.ensure_synced();
+        let mesh = self.core.get_mesh(Self::parse_mesh_id(&mesh_id_str)?)?;
+        Some(js_sys::Uint32Array::from(mesh.lines.as_deref().unwrap_or_default()))
+    }
+
+    /// Point indices of a mesh as a Uint32Array (empty if it has no points),
+    /// or undefined for unknown ids
+    pub fn get_mesh_point_indices(&mut self, mesh_id_str: String) -> Option<js_sys::Uint32Array> {
+        self.core.ensure_synced();
+        let mesh = self.core.get_mesh(Self::parse_mesh_id(&mesh_id_str)?)?;
+        Some(js_sys::Uint32Array::from(mesh.points.as_deref().unwrap_or_default()))
+    }
+
+    /// Get the world-space baked mesh of the object at a path, or null for invalid paths
+    pub fn get_world_mesh(&mut self, path_strings: Vec<String>) -> JsValue {
+        self.core.ensure_synced();
+        let Some(path) = Self::parse_edge_path(path_strings) else {
+            return JsValue::NULL;
+        };
+
+        match self.core.world_mesh(&path) {
+            Some(mesh) => serde_wasm_bindgen::to_value(&mesh).unwrap(),
+            None => JsValue::NULL,
+        }
+    }
+
+    /// OBJ text of the object at a path in world space, smoothing groups included, or undefined
+    pub fn export_obj(&mut self, path_strings: Vec<String>) -> Option<String> {
+        self.core.ensure_synced();
+        let path = Self::parse_edge_path(path_strings)?;
+        self.core.world_mesh(&path).map(|mesh| mesh_to_obj(&mesh))
+    }
+
+    /// Replace the geometry of the object at a path with an OBJ file, keeping its placement.
+    /// Returns the new mesh id; see `Scene::replace_mesh`.
+    pub fn replace_mesh_obj(&mut self, path_strings: Vec<String>, obj_text: String) -> Result<String, JsValue> {
+        let path = Self::parse_edge_path(path_strings).ok_or_else(|| JsValue::from_str("Invalid path"))?;
+        let mesh = parse_obj_to_mesh(&obj_text).map_err(|e| JsValue::from_str(&e))?;
+        let mesh_id = self.core.replace_mesh(&path, mesh)
+            .ok_or_else(|| JsValue::from_str("Path does not lead to a single model"))?;
+        Ok(mesh_id.0.to_string())
+    }
+
+    /// Bake a transform (position, quaternion rotation, scale, as for `update_transform`) into
+    /// the geometry of the object at a path, keeping it in place; see
+    /// `Scene::apply_transform_to_geometry`. Returns false if the transform could not be applied.
+    pub fn apply_transform_to_geometry(&mut self, path_strings: Vec<String>, position: Vec<f32>, rotation: Vec<f32>, scale: Vec<f32>) -> bool {
+        let (Ok(position), Ok(rotation), Ok(scale)) = (<[f32; 3]>::try_from(position), <[f32; 4]>::try_from(rotation), <[f32; 3]>::try_from(scale)) else {
+            return false;
+        };
+        let Some(path) = Self::parse_edge_path(path_strings) else {
+            return false;
+        };
+        let transform = Transform::from_position_rotation_scale(position, rotation, scale);
+        self.core.apply_transform_to_geometry(&path, &transform)
+    }
+
+    pub fn raycast_closest_hit(&mut self, origin: Vec<f32>, direction: Vec<f32>) -> JsValue {
+        self.raycast(origin, direction, &RaycastOptions::default())
+    }
+
+    /// Raycast with options, e.g. `{ t_min: 0.001 }` to ignore hits right at the ray origin,
+    /// or `{ ignore: [id] }` to cast through the object being dragged.
+    /// Missing fields use their defaults.
+    pub fn raycast_with_options(&mut self, origin: Vec<f32>, direction: Vec<f32>, options: JsValue) -> Result<JsValue, JsValue> {
+        let options: RaycastOptions = serde_wasm_bindgen::from_value(options)
+            .map_err(|e| JsValue::from_str(&format!("Invalid raycast options: {e}")))?;
+        Ok(self.raycast(origin, direction, &options))
+    }
+    
+    /// Raycast and snap the hit to a feature of the element hit. `snap` is one of `"none"`,
+    /// `"vertex"`, `"edge_midpoint"` or `"face_center"`. Returns the usual hit data plus
+    /// `snapped_position: [x, y, z]`, or null on a miss.
+    pub fn raycast_snap(&mut self, origin: Vec<f32>, direction: Vec<f32>, snap: JsValue) -> Result<JsValue, JsValue> {
+        let snap: SnapMode = serde_wasm_bindgen::from_value(snap)
+            .map_err(|e| JsValue::from_str(&format!("Invalid snap mode: {e}")))?;
+        let origin = Vec3::new_from_vec(origin).map_err(|e| JsValue::from_str(&e))?;
+        let direction = Vec3::new_from_vec(direction).map_err(|e| JsValue::from_str(&e))?;
+        self.core.ensure_synced();
+        self.core.rebuild_cache();
+
+        let ray = Ray3::new(Point3 { vec3: origin }, Direction3 { vec3: direction });
+        Ok(match self.core.raycast_snap(ray, snap) {
+            Some((world_hit, snapped_position)) => {
+                let data = SnapHitData { hit: Self::hit_data(&world_hit), snapped_position };
+                serde_wasm_bindgen::to_value(&data).unwrap()
+            }
+            None => JsValue::NULL,
+        })
+    }
+
+    /// Object id to select when clicking again at the same spot: the next one behind
+    /// `current` along the ray, wrapping round to the nearest; undefined on a miss
+    pub fn pick_cycle(&mut self, origin: Vec<f32>, direction: Vec<f32>, current: Option<usize>) -> Option<usize> {
+        let origin = Vec3::new_from_vec(origin).ok()?;
+        let direction = Vec3::new_from_vec(direction).ok()?;
+        self.core.ensure_synced();
+        self.core.rebuild_cache();
+        self.core.pick_cycle(Ray3::new(Point3 { vec3: origin }, Direction3 { vec3: direction }), current)
+    }
+
+    pub fn select_by_edge_path(&mut self, path_strings: Vec<String>) -> bool {
+        match Self::parse_edge_path(path_strings) {
+            Some(path) => self.core.select_by_edge_path(path),
+            None => false,
+        }
+    }
+
+    /// Ids of the objects whose world-space bounds overlap the sphere at (x, y, z)
+    pub fn objects_in_sphere(&mut self, x: f32, y: f32, z: f32, radius: f32) -> Vec<usize> {
+        self.core.objects_in_sphere(Point3::new(x, y, z), radius)
+    }
+
+    /// Get the world-space bounds `{ min, max }` of the object at a path, or null
+    pub fn get_object_bounds(&mut self, path_strings: Vec<String>) -> JsValue {
+        self.core.ensure_synced();
+        let Some(path) = Self::parse_edge_path(path_strings) else {
+            return JsValue::NULL;
+        };
+
+        match self.core.object_bounds(&path) {
+            Some((min, max)) => {
+                let bounds_data = BoundsData {
+                    min: [min.vec3.x, min.vec3.y, min.vec3.z],
+                    max: [max.vec3.x, max.vec3.y, max.vec3.z],
+                };
+                serde_wasm_bindgen::to_value(&bounds_data).unwrap()
+            }
+            None => JsValue::NULL,
+        }
+    }
+    
+    /// Distance from the object's center at which a camera with the given rotation
+    /// (quaternion [x, y, z, w]) and field of view (in radians) frames the whole object,
+    /// or undefined
+    pub fn get_framing_distance(&mut self, camera_rotation: Vec<f32>, fov_radians: f32, path_strings: Vec<String>) -> Option<f32> {
+        self.core.ensure_synced();
+        let camera_rotation = <[f32; 4]>::try_from(camera_rotation).ok()?;
+        let path = Self::parse_edge_path(path_strings)?;
+        self.core.framing_distance(camera_rotation, fov_radians, &path)
+    }
+
+    pub fn deselect(&mut self) {
+        self.core.deselect();
+    }
+    
+    pub fn select_parent(&mut self) -> bool {
+        self.core.select_parent()
+    }
+    
+    pub fn get_selected_path(&self) -> JsValue {
+        if let Some(path) = self.core.get_selected_path() {
+            // Convert EdgeIds to strings for JavaScript
+            let string_path: Vec<String> = path.iter().map(|edge_id| edge_id.to_string()).collect();
+            serde_wasm_bindgen::to_value(&string_path).unwrap()
+        } else {
+            JsValue::NULL
+        }
+    }
+
+    /// Get the edge path (as strings) of the object with the given id, or null
+    pub fn get_path_for_object_id(&mut self, object_id: usize) -> JsValue {
+        match self.core.path_for_object_id(object_id) {
+            Some(path) => {
+                let string_path: Vec<String> = path.iter().map(|edge_id| edge_id.to_string()).collect();
+                serde_wasm_bindgen::to_value(&string_path).unwrap()
+            }
+            None => JsValue::NULL,
+        }
+    }
+
+    /// World transform `{ translation, rotation, scale }` of the object at a path, or null
+    pub fn get_world_transform(&self, path_strings: Vec<String>) -> JsValue {
+        match Self::parse_edge_path(path_strings).and_then(|path| self.core.world_transform(&path)) {
+            Some(transform) => serde_wasm_bindgen::to_value(&transform).unwrap(),
+            None => JsValue::NULL,
+        }
+    }
+
+    /// Set the transform of the whole scene from position, quaternion rotation and scale
+    pub fn set_root_transform(&mut self, position: Vec<f32>, rotation: Vec<f32>, scale: Vec<f32>) {
+        let transform = Transform::from_position_rotation_scale(
+            [position[0], position[1], position[2]],
+            [rotation[0], rotation[1], rotation[2], rotation[3]],
+            [scale[0], scale[1], scale[2]],
+        );
+        self.core.set_root_transform(transform);
+    }
+
+    /// Transform `{ translation, rotation, scale }` of the whole scene
+    pub fn get_root_transform(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(self.core.root_transform()).unwrap()
+    }
+
+    /// Snap an object's translation to `translation_grid` and rotation to `rotation_step` radians
+    pub fn snap_object(&mut self, path_strings: Vec<String>, translation_grid: f32, rotation_step: f32) -> bool {
+        match Self::parse_edge_path(path_strings) {
+            Some(path) => self.core.snap_object(&path, translation_grid, rotation_step),
+            None => false,
+        }
+    }
+
+    /// Make the object at a path the first of `count` shared-mesh instances in a row, each
+    /// shifted by a further (x, y, z). Returns the new copies' paths as arrays of edge id strings.
+    pub fn duplicate_array(&mut self, path_strings: Vec<String>, count: u32, x: f32, y: f32, z: f32) -> JsValue {
+        let Some(path) = Self::parse_edge_path(path_strings) else {
+            return JsValue::NULL;
+        };
+        let paths: Vec<Vec<String>> = self.core.duplicate_array(&path, count, [x, y, z]).iter()
+            .map(|path| path.iter().map(|edge_id| edge_id.to_string()).collect())
+            .collect();
+        serde_wasm_bindgen::to_value(&paths).unwrap()
+    }
+
+    /// Store a JSON string as the metadata of the object at a path. Returns false for invalid
+    /// paths; errors if the string isn't valid JSON.
+    pub fn set_metadata(&mut self, path_strings: Vec<String>, json: String) -> Result<bool, JsValue> {
+        let metadata = serde_json::from_str(&json).map_err(|e| JsValue::from_str(&format!("Invalid metadata JSON: {e}")))?;
+        Ok(Self::parse_edge_path(path_strings).is_some_and(|path| self.core.set_metadata(&path, metadata)))
+    }
+
+    /// Metadata of the object at a path as a JSON string ("null" if none was set), or undefined
+    pub fn get_metadata(&self, path_strings: Vec<String>) -> Option<String> {
+        let path = Self::parse_edge_path(path_strings)?;
+        self.core.metadata(&path).map(|metadata| metadata.to_string())
+    }
+
+    /// Move the working origin to world position (x, y, z); see `Scene::set_origin_offset`
+    pub fn set_origin_offset(&mut self, x: f64, y: f64, z: f64) {
+        self.core.set_origin_offset([x, y, z]);
+    }
+
+    pub fn get_origin_offset(&self) -> Vec<f64> {
+        self.core.origin_offset().to_vec()
+    }
+
+    /// Convert a world position `[x, y, z]` to the working coordinates used by every other call
+    pub fn world_to_scene(&self, world: Vec<f64>) -> Result<Vec<f32>, JsValue> {
+        let world: [f64; 3] = world.try_into()
+            .map_err(|_| JsValue::from_str("Expected a world position [x, y, z]"))?;
+        let point = self.core.world_to_scene(world).vec3;
+        Ok(vec![point.x, point.y, point.z])
+    }
+
+    /// Convert working coordinates `[x, y, z]` back to a full-precision world position
+    pub fn scene_to_world(&self, point: Vec<f32>) -> Result<Vec<f64>, JsValue> {
+        let point = Vec3::new_from_vec(point).map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.core.scene_to_world(Point3 { vec3: point }).to_vec())
+    }
+
+    /// Make new mesh and edge IDs deterministic, drawn from `seed`, or random again with
+    /// undefined; see `Scene::set_id_seed`
+    pub fn set_id_seed(&mut self, seed: Option<u64>) {
+        self.core.set_id_seed(seed);
+    }
+
+    pub fn get_max_depth(&self) -> usize {
+        self.core.max_depth()
+    }
+
+    /// Limit how deep rendering and raycasts follow the graph; see `Scene::set_max_depth`
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.core.set_max_depth(max_depth);
+    }
+
+    /// Add an empty layer and return its edge ID
+    pub fn add_layer(&mut self, name: String) -> String {
+        self.core.add_layer(&name).to_string()
+    }
+
+    pub fn set_layer_visible(&mut self, layer_id: String, visible: bool) -> bool {
+        match EdgeId::from_string(&layer_id) {
+            Ok(layer_id) => self.core.set_layer_visible(layer_id, visible),
+            Err(_) => false,
+        }
+    }
+
+    pub fn set_layer_locked(&mut self, layer_id: String, locked: bool) -> bool {
+        match EdgeId::from_string(&layer_id) {
+            Ok(layer_id) => self.core.set_layer_locked(layer_id, locked),
+            Err(_) => false,
+        }
+    }
+
+    /// Get `[{ id, name, visible, locked }]` for every layer
+    pub fn get_layers(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.core.layers()).unwrap()
+    }
+
+    /// Mirror an object across the plane through `plane_point` with normal `plane_normal`
+    pub fn mirror_object(&mut self, path_strings: Vec<String>, plane_point: Vec<f32>, plane_normal: Vec<f32>) -> bool {
+        let (Some(path), Ok(point), Ok(normal)) = (
+            Self::parse_edge_path(path_strings),
+            Vec3::new_from_vec(plane_point),
+            Vec3::new_from_vec(plane_normal),
+        ) else {
+            return false;
+        };
+        self.core.mirror_object(&path, Point3 { vec3: point }, normal)
+    }
+
+    /// Register simplified meshes (`{ vertex_coords, face_indices, ... }`, finest first) as the
+    /// coarser levels of detail of a mesh
+    pub fn set_lod_levels(&mut self, mesh_id_str: String, levels: JsValue) -> Result<bool, JsValue> {
+        let levels: Vec<Mesh> = serde_wasm_bindgen::from_value(levels)
+            .map_err(|e| JsValue::from_str(&format!("Invalid LOD meshes: {e}")))?;
+        Ok(Self::parse_mesh_id(&mesh_id_str).is_some_and(|mesh_id| self.core.set_lod_levels(mesh_id, levels)))
+    }
+
+    /// Simplify a mesh to each fraction of its faces in `ratios` (finest first, e.g.
+    /// `[0.5, 0.25, 0.1]`) and register the results as its coarser levels of detail
+    pub fn generate_lod_levels(&mut self, mesh_id_str: String, ratios: Vec<f32>) -> bool {
+        Self::parse_mesh_id(&mesh_id_str).is_some_and(|mesh_id| self.core.generate_lod_levels(mesh_id, &ratios))
+    }
+
+    /// Mesh data for a level returned by `select_lod`, or null
+    pub fn get_lod_mesh_data(&mut self, mesh_id_str: String, level: usize) -> JsValue {
+        self.core.ensure_synced();
+        match Self::parse_mesh_id(&mesh_id_str).and_then(|mesh_id| self.core.lod_mesh(mesh_id, level)) {
+            Some(mesh) => serde_wasm_bindgen::to_value(mesh).unwrap(),
+            None => JsValue::NULL,
+        }
+    }
+
+    /// Triangles across the three edges of a triangle of a mesh as `[t0, t1, t2]`, with null on
+    /// boundary edges; entry k is across the edge from corner k to corner k + 1. Null if the
+    /// mesh or triangle doesn't exist.
+    pub fn get_triangle_neighbors(&mut self, mesh_id_str: String, triangle: usize) -> JsValue {
+        self.core.ensure_synced();
+        let neighbors = Self::parse_mesh_id(&mesh_id_str)
+            .and_then(|mesh_id| self.core.mesh_adjacency(mesh_id))
+            .filter(|adjacency| triangle < adjacency.triangle_count())
+            .map(|adjacency| adjacency.face_neighbors(triangle));
+        match neighbors {
+            Some(neighbors) => serde_wasm_bindgen::to_value(&neighbors).unwrap(),
+            None => JsValue::NULL,
+        }
+    }
+
+    /// Level of detail per object as `[[object_id, level], ...]`; see `Scene::select_lod`.
+    /// `view_proj` is the camera's `projectionMatrix * matrixWorldInverse` elements.
+    pub fn select_lod(&mut self, view_proj: Vec<f32>, screen_height: f32) -> Result<JsValue, JsValue> {
+        let view_proj: [f32; 16] = view_proj.try_into()
+            .map_err(|_| JsValue::from_str("Expected a 4x4 view-projection matrix (16 elements)"))?;
+        Ok(serde_wasm_bindgen::to_value(&self.core.select_lod(view_proj, screen_height)).unwrap())
+    }
+
+    /// Get `{ name, path, triangle_count, vertex_count, material }` for the current selection, or null
+    pub fn get_selected_info(&mut self) -> JsValue {
+        self.core.ensure_synced();
+        match self.core.selected_info() {
+            Some(info) => serde_wasm_bindgen::to_value(&info).unwrap(),
+            None => JsValue::NULL,
+        }
+    }
+
+    pub fn select_face(&mut self, face: usize) -> bool {
+        self.core.select_face(FaceIndex(face))
+    }
+
+    /// Select the whole piece of the edited object containing `face`, returns its face count
+    pub fn select_linked(&mut self, face: usize) -> usize {
+        self.core.select_linked(FaceIndex(face))
+    }
+
+    pub fn deselect_face(&mut self, face: usize) -> bool {
+        self.core.deselect_face(FaceIndex(face))
+    }
+
+    /// Extrude the selected faces along their normals, returns the number of wall faces added
+    pub fn extrude_selected_faces(&mut self, distance: f32) -> usize {
+        self.core.extrude_selected_faces(distance)
+    }
+
+    pub fn clear_mesh_selection(&mut self) {
+        self.core.clear_mesh_selection();
+    }
+
+    /// Get the number of selected faces, edges and vertices in the edited object
+    pub fn get_mesh_selection_counts(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.core.get_mesh_selection().counts()).unwrap()
     }
 
-    pub fn get_scene_data(&mut self) -> JsValue {
-        serde_wasm_bindgen::to_value(self.core.get_render_instances()).unwrap()
+    /// Get list of all models with their IDs and names
+    pub fn get_model_list(&self) -> JsValue {
+        let models: Vec<(String, String)> = self.core.get_model_list()
+            .into_iter()
+            .map(|(id, name)| (id.0.to_string(), name))
+            .collect();
+        serde_wasm_bindgen::to_value(&models).unwrap()
     }
+    
+    /// Get the scene graph tree for the outliner; see `Scene::get_scene_graph`
+    pub fn get_scene_graph(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.core.get_scene_graph()).unwrap()
+    }
+}
 
-    /// Get mesh data by ID for JavaScript
-    pub fn get_mesh_data(&self, mesh_id_str: String) -> JsValue {
-        // Parse UUID string back into MeshId
-        if let Ok(uuid) = uuid::Uuid::parse_str(&mesh_id_str) {
-            let mesh_id = MeshId(uuid);
-            if let Some(mesh) = self.core.get_mesh(mesh_id) {
-                return serde_wasm_bindgen::to_value(mesh).unwrap();
-            }
+// Helpers not visible to WASM interface
+impl SceneAPI {
+    fn hit_data(world_hit: &WorldHitResponse) -> HitData {
+        HitData {
+            position: HitPosition {
+                x: world_hit.hit_response.hit_position.vec3.x,
+                y: world_hit.hit_response.hit_position.vec3.y,
+                z: world_hit.hit_response.hit_position.vec3.z,
+            },
+            object_id: world_hit.object_id,
+            selection_path: world_hit.selection_path.iter().map(|edge_id| edge_id.to_string()).collect(),
+            normal: world_hit.normal,
         }
-        JsValue::NULL
     }
 
-    pub fn raycast_closest_hit(&self, origin: Vec<f32>, direction: Vec<f32>) -> JsValue {
+    fn raycast(&mut self, origin: Vec<f32>, direction: Vec<f32>, options: &RaycastOptions) -> JsValue {
+        self.core.ensure_synced();
+        self.core.rebuild_cache();
         if let (Ok(origin_vec3), Ok(direction_vec3)) = (Vec3::new_from_vec(origin), Vec3::new_from_vec(direction)) {
             let ray = Ray3::new(
                 Point3 { vec3: origin_vec3 },
                 Direction3 { vec3: direction_vec3 }
             );
             
-            if let Some(world_hit) = self.core.raycast_closest_hit(ray) {
+            if let Some(world_hit) = self.core.raycast_closest_hit(ray, options) {
                 // Return hit position and object ID for JS
-                let hit_data = HitData {
-                    position: HitPosition {
-                        x: world_hit.hit_response.hit_position.vec3.x,
-                        y: world_hit.hit_response.hit_position.vec3.y,
-                        z: world_hit.hit_response.hit_position.vec3.z,
-                    },
-                    object_id: world_hit.object_id,
-                    selection_path: world_hit.selection_path.iter().map(|edge_id| edge_id.to_string()).collect(),
-                };
-                return serde_wasm_bindgen::to_value(&hit_data).unwrap();
+                serde_wasm_bindgen::to_value(&Self::hit_data(&world_hit)).unwrap()
             } else {
                 // No response. Object was not hit.
                 JsValue::NULL
@@ -460,51 +2239,948 @@ impl SceneAPI {
             JsValue::NULL
         }
     }
-    
-    pub fn select_by_edge_path(&mut self, path_strings: Vec<String>) -> bool {
-        // Parse EdgeId strings
-        let mut path = Vec::new();
+
+    /// Parse a MeshId UUID string coming from JavaScript
+    fn parse_mesh_id(mesh_id_str: &str) -> Option<MeshId> {
+        uuid::Uuid::parse_str(mesh_id_str).ok().map(MeshId)
+    }
+
+    /// Parse EdgeId strings coming from JavaScript into a path
+    fn parse_edge_path(path_strings: Vec<String>) -> Option<Vec<EdgeId>> {
+        let mut path = Vec::with_capacity(path_strings.len());
         for s in path_strings {
             match EdgeId::from_string(&s) {
                 Ok(edge_id) => path.push(edge_id),
                 Err(_) => {
                     console_log!("Invalid EdgeId in path: {}", s);
-                    return false;
+                    return None;
                 }
             }
         }
-        self.core.select_by_edge_path(path)
+        Some(path)
     }
-    
-    pub fn deselect(&mut self) {
-        self.core.deselect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Scene with one shared unit cube placed under its own node at each position
+    fn scene_with_cubes(positions: &[[f32; 3]]) -> Scene {
+        let mut scene = Scene::new();
+        let mesh_id = scene.add_cube(1.0);
+        for &position in positions {
+            place_model(&mut scene, mesh_id, position);
+        }
+        scene
     }
-    
-    pub fn select_parent(&mut self) -> bool {
-        self.core.select_parent()
+
+    /// Put a model in the graph under its own node at `position`
+    fn place_model(scene: &mut Scene, mesh_id: MeshId, position: [f32; 3]) {
+        let mut node = SceneGraphNode::with_transform(Transform::from_position(position));
+        node.add_child(SceneGraphChild::Model(mesh_id));
+        scene.root.add_child(SceneGraphChild::Node(Box::new(node)));
+        scene.hierarchy_dirty = true;
     }
-    
-    pub fn get_selected_path(&self) -> JsValue {
-        if let Some(path) = self.core.get_selected_path() {
-            // Convert EdgeIds to strings for JavaScript
-            let string_path: Vec<String> = path.iter().map(|edge_id| edge_id.to_string()).collect();
-            serde_wasm_bindgen::to_value(&string_path).unwrap()
-        } else {
-            JsValue::NULL
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn object_bounds_centered_on_world_position() {
+        let mut scene = scene_with_cubes(&[[3.0, -1.0, 2.0]]);
+        let path = scene.path_for_object_id(0).unwrap();
+
+        let (min, max) = scene.object_bounds(&path).unwrap();
+        let center = (min.vec3 + max.vec3) * 0.5;
+        assert_close(center.x, 3.0);
+        assert_close(center.y, -1.0);
+        assert_close(center.z, 2.0);
+        assert_close(max.vec3.x - min.vec3.x, 1.0);
+
+        assert!(scene.object_bounds(&[EdgeId::new()]).is_none());
+    }
+
+    #[test]
+    fn memory_estimate_grows_with_mesh_size() {
+        let cubes = |count: usize| {
+            let mut mesh = Mesh::new();
+            for _ in 0..count {
+                mesh.append(&Mesh::create_cube(1.0));
+            }
+            mesh
+        };
+        let mut scene = Scene::new();
+        let empty = scene.memory_estimate();
+        scene.add_raw_mesh(cubes(100));
+        let small = scene.memory_estimate() - empty;
+        scene.add_raw_mesh(cubes(400));
+        let large = scene.memory_estimate() - empty - small;
+
+        let ratio = large as f32 / small as f32;
+        assert!((3.8..=4.2).contains(&ratio), "ratio {ratio}");
+    }
+
+    #[test]
+    fn clear_objects_keeps_meshes() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0]]);
+        let mesh_id = scene.get_render_instances()[0].mesh_id;
+        assert_eq!(scene.object_count(), 2);
+
+        scene.clear_objects();
+
+        assert_eq!(scene.object_count(), 0);
+        assert!(scene.get_render_instances().is_empty());
+        assert_eq!(scene.get_mesh(mesh_id).unwrap().face_count(), 12);
+    }
+
+    #[test]
+    fn extrude_uses_face_selection() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [5.0, 0.0, 0.0]]);
+        assert_eq!(scene.extrude_selected_faces(1.0), 0);
+        let path = scene.path_for_object_id(0).unwrap();
+        scene.select_by_edge_path(path.clone());
+        assert!(scene.select_face(FaceIndex(0)));
+        assert!(scene.select_face(FaceIndex(3)));
+        assert_eq!(scene.get_mesh_selection().counts().faces, 2);
+
+        let walls = scene.extrude_selected_faces(1.0);
+
+        assert!(walls > 0);
+        let mesh_id = scene.get_render_instances()[0].mesh_id;
+        let edited = match &scene.meshes[&mesh_id].model {
+            ModelVariant::HalfEdgeMesh(hemw) => hemw.model(),
+            _ => unreachable!(),
+        };
+        edited.validate().unwrap();
+        assert_eq!(edited.faces.len(), 6 + walls);
+        assert_eq!(scene.get_mesh_selection().faces, [FaceIndex(0), FaceIndex(3)].into());
+        let (min, max) = scene.object_bounds(&path).unwrap();
+        assert!(max.vec3.x - min.vec3.x > 1.5 || max.vec3.y - min.vec3.y > 1.5 || max.vec3.z - min.vec3.z > 1.5);
+    }
+
+    #[test]
+    fn snap_object_rounds_translation_to_grid() {
+        let mut scene = scene_with_cubes(&[[1.3, 0.0, 0.0]]);
+        let path = scene.path_for_object_id(0).unwrap();
+
+        assert!(scene.snap_object(&path, 0.5, 0.0));
+
+        let translation = scene.world_transform(&path).unwrap().matrix().w_axis.truncate();
+        assert_eq!(translation.to_array(), [1.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn object_id_maps_back_to_path() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [4.0, 0.0, 0.0]]);
+        let second = scene.get_render_instances()[1].clone();
+
+        let path = scene.path_for_object_id(second.id).unwrap();
+
+        assert_eq!(path, second.path);
+        let (min, _) = scene.object_bounds(&path).unwrap();
+        assert_eq!(min.vec3.x, 3.5);
+        assert!(scene.path_for_object_id(2).is_none());
+    }
+
+    #[test]
+    fn stable_ids_survive_insertion_at_front() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0]]);
+        let before: Vec<(String, Vec<EdgeId>)> = scene.get_render_instances().iter()
+            .map(|instance| (instance.stable_id.clone(), instance.path.clone()))
+            .collect();
+
+        let mesh_id = scene.add_sphere(1.0);
+        scene.root.edges.insert(0, SceneGraphEdge { edge_id: EdgeId::new(), child: SceneGraphChild::Model(mesh_id) });
+        scene.hierarchy_dirty = true;
+
+        let after = scene.get_render_instances();
+        assert_eq!(after.len(), 3);
+        assert_eq!(after[1].id, 1);
+        for (instance, (stable_id, path)) in after[1..].iter().zip(&before) {
+            assert_eq!(&instance.stable_id, stable_id);
+            assert_eq!(&instance.path, path);
         }
     }
 
-    /// Get list of all models with their IDs and names
-    pub fn get_model_list(&self) -> JsValue {
-        let models: Vec<(String, String)> = self.core.get_model_list()
-            .into_iter()
-            .map(|(id, name)| (id.0.to_string(), name))
+    #[test]
+    fn stable_ids_are_unique() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [4.0, 0.0, 0.0]]);
+        let layer = scene.add_layer("layer");
+        let mesh_id = scene.get_render_instances()[0].mesh_id;
+        let SceneGraphChild::Node(node) = &mut scene.root.edges.last_mut().unwrap().child else { unreachable!() };
+        node.add_child(SceneGraphChild::Model(mesh_id));
+        node.add_child(SceneGraphChild::Model(mesh_id));
+        scene.hierarchy_dirty = true;
+        assert_eq!(scene.layers()[0].id, layer.to_string());
+        // Copies of a node get their own model edges too
+        let path = scene.path_for_object_id(0).unwrap();
+        assert_eq!(scene.duplicate_array(&path, 3, [0.0, 2.0, 0.0]).len(), 2);
+
+        let instances = scene.get_render_instances();
+        let stable_ids: HashSet<&str> = instances.iter().map(|instance| instance.stable_id.as_str()).collect();
+        assert_eq!(instances.len(), 7);
+        assert_eq!(stable_ids.len(), instances.len());
+    }
+
+    #[test]
+    fn bulk_transform_update_rebuilds_once() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0]]);
+        scene.get_render_instances();
+        let rebuilds = scene.rebuild_count();
+        let updates = (0..2)
+            .map(|id| {
+                let path = scene.path_for_object_id(id).unwrap();
+                (path[..1].to_vec(), Transform::from_position([id as f32, 5.0, 0.0]))
+            })
             .collect();
-        serde_wasm_bindgen::to_value(&models).unwrap()
+
+        assert_eq!(scene.update_transforms(updates), 2);
+
+        let heights: Vec<f32> = scene.get_render_instances().iter()
+            .map(|instance| instance.transform.matrix().w_axis.y)
+            .collect();
+        assert_eq!(heights, [5.0, 5.0]);
+        assert_eq!(scene.rebuild_count(), rebuilds + 1);
     }
-    
-    /// Get scene graph hierarchy for UI visualization
-    pub fn get_scene_graph(&self) -> JsValue {
-        serde_wasm_bindgen::to_value(&self.core.get_scene_graph()).unwrap()
+
+    #[test]
+    fn visitor_counts_every_model() {
+        struct ModelCounter {
+            nodes: usize,
+            models: usize,
+        }
+        impl SceneVisitor for ModelCounter {
+            fn visit_node(&mut self, _node: &SceneGraphNode, _world_transform: &Transform) {
+                self.nodes += 1;
+            }
+            fn visit_model(&mut self, _mesh_id: MeshId, _world_transform: &Transform) {
+                self.models += 1;
+            }
+        }
+        let scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [4.0, 0.0, 0.0]]);
+
+        let mut counter = ModelCounter { nodes: 0, models: 0 };
+        scene.accept(&mut counter);
+
+        assert_eq!(counter.models, scene.object_count());
+        assert_eq!(counter.nodes, 4);
+    }
+
+    #[test]
+    fn raycast_ignores_listed_objects() {
+        let scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [0.0, 0.0, -5.0]]);
+        let ray = Ray3::new(Point3::new(0.0, 0.0, 10.0), Direction3::from([0.0, 0.0, -1.0]));
+
+        let front = scene.raycast_closest_hit(ray, &RaycastOptions::default()).unwrap();
+        assert_eq!(front.object_id, 0);
+
+        let options = RaycastOptions { ignore: vec![0], ..Default::default() };
+        let behind = scene.raycast_closest_hit(ray, &options).unwrap();
+        assert_eq!(behind.object_id, 1);
+        assert_close(behind.distance, 14.5);
+    }
+
+    // The typed arrays themselves only exist under WASM; this covers the buffers they copy
+    #[test]
+    fn mesh_buffers_match_counts() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0]]);
+        let mesh_id = scene.get_render_instances()[0].mesh_id;
+        scene.ensure_synced();
+
+        let mesh = scene.get_mesh(mesh_id).unwrap();
+        assert_eq!(mesh.vertex_coords.len(), 3 * mesh.vertex_count());
+        assert_eq!(mesh.face_indices.len(), 3 * mesh.face_count());
+        assert_eq!(mesh.normals.as_ref().unwrap().len(), mesh.vertex_coords.len());
+        assert!(mesh.face_indices.iter().all(|&index| (index as usize) < mesh.vertex_count()));
+    }
+
+    #[test]
+    fn objects_in_sphere_only_returns_nearby_cubes() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [5.0, 0.0, 0.0], [20.0, 0.0, 0.0]]);
+        let ids: Vec<usize> = scene.get_render_instances().iter().map(|instance| instance.id).collect();
+
+        // Reaches the second cube's near face at x = 4.5, but not the third
+        let mut found = scene.objects_in_sphere(Point3::new(1.0, 0.0, 0.0), 3.6);
+        found.sort();
+        assert_eq!(found, vec![ids[0], ids[1]]);
+
+        assert_eq!(scene.objects_in_sphere(Point3::new(20.0, 3.0, 0.0), 2.6), vec![ids[2]]);
+        assert!(scene.objects_in_sphere(Point3::new(12.0, 0.0, 0.0), 1.0).is_empty());
+    }
+
+    #[test]
+    fn future_format_version_is_rejected() {
+        let json = scene_with_cubes(&[[0.0, 0.0, 0.0]]).to_json().unwrap();
+        let current = format!("\"format_version\":{}", scene_format::FORMAT_VERSION);
+        assert!(json.contains(&current));
+        assert!(Scene::from_json(&json).is_ok());
+        assert!(Scene::from_json(&json.replace(&current, "\"format_version\":1")).is_ok());
+
+        let error = Scene::from_json(&json.replace(&current, "\"format_version\":99")).err().unwrap();
+        assert!(error.contains("99") && error.contains("newer"), "{error}");
+    }
+
+    #[test]
+    fn version_2_nodes_load_unnamed_visible_and_unlocked() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0]]);
+        let mesh_id = scene.get_render_instances()[0].mesh_id;
+        let layer_id = scene.add_layer("props");
+        scene.layer_mut(layer_id).unwrap().add_child(SceneGraphChild::Model(mesh_id));
+        scene.set_layer_visible(layer_id, false);
+        scene.set_layer_locked(layer_id, true);
+
+        // Drop the node fields version 3 added, as a version 2 build would have written them
+        fn strip_node_fields(value: &mut serde_json::Value) {
+            if let Some(object) = value.as_object_mut() {
+                if object.contains_key("transform") {
+                    object.remove("name");
+                    object.remove("visible");
+                    object.remove("locked");
+                }
+                object.values_mut().for_each(strip_node_fields);
+            } else if let Some(array) = value.as_array_mut() {
+                array.iter_mut().for_each(strip_node_fields);
+            }
+        }
+        let mut value: serde_json::Value = serde_json::from_str(&scene.to_json().unwrap()).unwrap();
+        strip_node_fields(&mut value);
+        value["format_version"] = 2.into();
+
+        let mut loaded = Scene::from_json(&value.to_string()).unwrap();
+        assert_eq!(loaded.get_render_instances().len(), 2);
+        assert!(!loaded.path_is_locked(&[layer_id]));
+        let layer = loaded.root.edges.iter().find(|edge| edge.edge_id == layer_id).unwrap();
+        assert!(matches!(&layer.child, SceneGraphChild::Node(node) if node.name.is_none()));
+    }
+
+    #[test]
+    fn version_3_scene_loads_with_origin_at_zero() {
+        let mut scene = scene_with_cubes(&[[1.0, 2.0, 3.0]]);
+        scene.set_origin_offset([500.0, 0.0, 0.0]);
+
+        // A version 3 build wrote no origin offset
+        let mut value: serde_json::Value = serde_json::from_str(&scene.to_json().unwrap()).unwrap();
+        value.as_object_mut().unwrap().remove("origin_offset");
+        value["format_version"] = 3.into();
+
+        let loaded = Scene::from_json(&value.to_string()).unwrap();
+        assert_eq!(loaded.origin_offset(), [0.0; 3]);
+    }
+
+    #[test]
+    fn get_mesh_after_sync_reflects_edit() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0]]);
+        let mesh_id = scene.get_render_instances()[0].mesh_id;
+
+        // Edit the model directly, leaving its render mesh stale
+        let Some(ModelEntry { model: ModelVariant::HalfEdgeMesh(wrapper), .. }) = scene.meshes.get_mut(&mesh_id) else {
+            panic!("cube should be a half-edge mesh");
+        };
+        wrapper.model_mut().vertices[0].position.vec3.y = 7.0;
+
+        // The read path syncs first, without a scene data request in between
+        scene.ensure_synced();
+        let mesh = scene.get_mesh(mesh_id).unwrap();
+        assert_eq!(mesh.vertex_coords[1], 7.0);
+    }
+
+    #[test]
+    fn mirror_moves_cube_to_opposite_side() {
+        let mut scene = scene_with_cubes(&[[3.0, 1.0, 0.0]]);
+        let path = scene.path_for_object_id(0).unwrap();
+        let volume = scene.world_mesh(&path).unwrap().signed_volume();
+
+        // Across the YZ plane
+        assert!(scene.mirror_object(&path, Point3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0)));
+        let (min, max) = scene.object_bounds(&path).unwrap();
+        assert_close(min.vec3.x, -3.5);
+        assert_close(max.vec3.x, -2.5);
+        assert_close(min.vec3.y, 0.5);
+        // Winding flips with the reflection, so the faces still point outward
+        assert_close(scene.world_mesh(&path).unwrap().signed_volume(), volume);
+
+        // Mirroring again through the group's path brings it back
+        assert!(scene.mirror_object(&path[..1], Point3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0)));
+        assert_close(scene.object_bounds(&path).unwrap().0.vec3.x, 2.5);
+    }
+
+    #[test]
+    fn deep_hierarchy_is_truncated_at_max_depth() {
+        let mut scene = Scene::new();
+        let mesh_id = scene.add_cube(1.0);
+        // 300 nested nodes, each holding a cube at the origin
+        let mut chain = SceneGraphNode::new();
+        chain.add_child(SceneGraphChild::Model(mesh_id));
+        for _ in 1..300 {
+            let mut parent = SceneGraphNode::new();
+            parent.add_child(SceneGraphChild::Model(mesh_id));
+            parent.add_child(SceneGraphChild::Node(Box::new(chain)));
+            chain = parent;
+        }
+        scene.root = chain;
+        scene.hierarchy_dirty = true;
+
+        assert_eq!(scene.max_depth(), 256);
+        assert_eq!(scene.get_render_instances().len(), 256);
+
+        // Raycasts stop at the same depth, through the graph and through the cached instances
+        let ray = Ray3::new(Point3::new(0.0, 0.0, 10.0), Direction3 { vec3: Vec3::new(0.0, 0.0, -1.0) });
+        scene.set_max_depth(10);
+        assert_eq!(scene.raycast_all_hits(ray, &RaycastOptions::default()).len(), 10);
+        assert_eq!(scene.get_render_instances().len(), 10);
+        assert_eq!(scene.raycast_all_hits(ray, &RaycastOptions::default()).len(), 10);
+
+        scene.set_max_depth(1000);
+        assert_eq!(scene.get_render_instances().len(), 300);
+    }
+
+    #[test]
+    fn hidden_layer_removes_its_descendants() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, -3.0]]);
+        let mesh_id = scene.get_render_instances()[0].mesh_id;
+        let layer_id = scene.add_layer("props");
+
+        // A model directly in the layer, and one in a group inside it
+        let layer = scene.layer_mut(layer_id).unwrap();
+        let mut group = SceneGraphNode::with_transform(Transform::from_position([0.0, 0.0, 2.0]));
+        group.add_child(SceneGraphChild::Model(mesh_id));
+        layer.add_child(SceneGraphChild::Node(Box::new(group)));
+        layer.add_child(SceneGraphChild::Model(mesh_id));
+        scene.hierarchy_dirty = true;
+        assert_eq!(scene.get_render_instances().len(), 3);
+
+        let ray = Ray3::new(Point3::new(0.0, 0.0, 10.0), Direction3 { vec3: Vec3::new(0.0, 0.0, -1.0) });
+        assert_eq!(scene.raycast_closest_hit(ray, &RaycastOptions::default()).unwrap().selection_path[0], layer_id);
+
+        assert!(scene.set_layer_visible(layer_id, false));
+        let instances = scene.get_render_instances();
+        assert_eq!(instances.len(), 1);
+        assert!(instances.iter().all(|instance| instance.path[0] != layer_id));
+        let hit = scene.raycast_closest_hit(ray, &RaycastOptions::default()).unwrap();
+        assert_ne!(hit.selection_path[0], layer_id);
+
+        assert!(scene.set_layer_visible(layer_id, true));
+        assert_eq!(scene.get_render_instances().len(), 3);
+    }
+
+    #[test]
+    fn floating_origin_keeps_far_cube_precise() {
+        // f32 alone can't hold this position to better than a few centimetres
+        let world_x = 1_000_000.3;
+        assert!((world_x as f32 as f64 - world_x).abs() > 1e-3);
+
+        // The cube sits at 0.3 in working coordinates, with the working origin at x = 1e6
+        let mut scene = scene_with_cubes(&[[0.3, 0.0, 0.0]]);
+        scene.origin_offset = [1_000_000.0, 0.0, 0.0];
+        assert_close(scene.world_to_scene([world_x, 0.0, 0.0]).vec3.x, 0.3);
+
+        let ray = Ray3::new(scene.world_to_scene([world_x, 0.0, 10.0]), Direction3 { vec3: Vec3::new(0.0, 0.0, -1.0) });
+        let hit = scene.raycast_closest_hit(ray, &RaycastOptions::default()).unwrap();
+        let world_hit = scene.scene_to_world(hit.hit_response.hit_position);
+        assert!((world_hit[0] - world_x).abs() < 1e-6, "{world_hit:?}");
+        assert!((world_hit[2] - 0.5).abs() < 1e-6, "{world_hit:?}");
+
+        // Rebasing keeps world positions, and the offset is saved with the scene
+        scene.set_origin_offset([999_990.0, 0.0, 0.0]);
+        let ray = Ray3::new(scene.world_to_scene([world_x, 0.0, 10.0]), Direction3 { vec3: Vec3::new(0.0, 0.0, -1.0) });
+        let hit = scene.raycast_closest_hit(ray, &RaycastOptions::default()).unwrap();
+        assert!((scene.scene_to_world(hit.hit_response.hit_position)[0] - world_x).abs() < 1e-4);
+        let loaded = Scene::from_json(&scene.to_json().unwrap()).unwrap();
+        assert_eq!(loaded.origin_offset(), [999_990.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn far_object_selects_coarser_lod() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, -3.0], [0.0, 0.0, -400.0]]);
+        let mesh_id = scene.get_render_instances()[0].mesh_id;
+        assert!(scene.set_lod_levels(mesh_id, vec![Mesh::create_cube(1.0); 6]));
+        let view_proj = glam::Mat4::perspective_rh_gl(1.0, 1.0, 0.1, 1000.0).to_cols_array();
+
+        let levels = scene.select_lod(view_proj, 1080.0);
+
+        let (near, far) = (levels[0].1, levels[1].1);
+        assert_eq!(near, 0);
+        assert!(far > near);
+        assert!(scene.lod_mesh(mesh_id, far).is_some());
+    }
+
+    #[test]
+    fn vertex_snap_finds_cube_corner() {
+        let scene = scene_with_cubes(&[[5.0, 0.0, 0.0]]);
+        // Hits the front face just inside its top right corner
+        let ray = Ray3::new(Point3::new(5.47, 0.44, 10.0), Direction3 { vec3: Vec3::new(0.0, 0.0, -1.0) });
+
+        let (hit, corner) = scene.raycast_snap(ray, SnapMode::Vertex).unwrap();
+        assert_eq!(<[f32; 3]>::from(corner), [5.5, 0.5, 0.5]);
+        assert_close(hit.hit_response.hit_position.vec3.x, 5.47);
+
+        // The quad's center and nearest edge midpoint, not those of the hit triangle
+        let (_, center) = scene.raycast_snap(ray, SnapMode::FaceCenter).unwrap();
+        assert_eq!(<[f32; 3]>::from(center), [5.0, 0.0, 0.5]);
+        let (_, midpoint) = scene.raycast_snap(ray, SnapMode::EdgeMidpoint).unwrap();
+        assert_eq!(<[f32; 3]>::from(midpoint), [5.5, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn replace_mesh_keeps_world_position() {
+        let mut scene = scene_with_cubes(&[[3.0, 1.0, 0.0], [-2.0, 0.0, 0.0]]);
+        let path = scene.path_for_object_id(0).unwrap();
+        let other = scene.path_for_object_id(1).unwrap();
+        let transform = scene.world_transform(&path).unwrap();
+
+        let mesh_id = scene.replace_mesh(&path[..1], Mesh::create_sphere(2.0, 12, 8)).unwrap();
+
+        assert_eq!(scene.world_transform(&path[..1]).unwrap().matrix(), transform.matrix());
+        let (min, max) = scene.object_bounds(&path).unwrap();
+        assert_close(min.vec3.x, 1.0);
+        assert_close(max.vec3.x, 5.0);
+        assert!(scene.get_mesh(mesh_id).is_some());
+
+        // The other instance of the cube keeps it
+        assert_close(scene.object_bounds(&other).unwrap().0.vec3.x, -2.5);
+        assert_eq!(scene.get_model_list().len(), 2);
+        // Once nothing uses the cube, it's dropped
+        scene.replace_mesh(&other, Mesh::create_cube(4.0)).unwrap();
+        assert_eq!(scene.get_model_list().len(), 2);
+    }
+
+    #[test]
+    fn metadata_survives_save_and_load() {
+        let mut scene = scene_with_cubes(&[[1.0, 0.0, 0.0]]);
+        let path = scene.path_for_object_id(0).unwrap();
+        assert!(scene.set_metadata(&path, serde_json::json!({ "tags": ["a", "b"], "author": "me" })));
+
+        let mut loaded = Scene::from_json(&scene.to_json().unwrap()).unwrap();
+
+        let path = loaded.path_for_object_id(0).unwrap();
+        let metadata = loaded.metadata(&path).unwrap();
+        assert_eq!(metadata["author"], "me");
+        assert_eq!(metadata["tags"][1], "b");
+    }
+
+    #[test]
+    fn version_4_nodes_load_without_metadata() {
+        let mut scene = scene_with_cubes(&[[1.0, 0.0, 0.0]]);
+        let path = scene.path_for_object_id(0).unwrap();
+        assert!(scene.set_metadata(&path, serde_json::json!({ "author": "me" })));
+
+        // A version 4 build wrote no metadata
+        let json = scene.to_json().unwrap();
+        let current = format!("\"format_version\":{}", scene_format::FORMAT_VERSION);
+        let json = json.replace(",\"metadata\":{\"author\":\"me\"}", "").replace(&current, "\"format_version\":4");
+        assert!(!json.contains("metadata"));
+
+        let mut loaded = Scene::from_json(&json).unwrap();
+        let path = loaded.path_for_object_id(0).unwrap();
+        assert!(loaded.metadata(&path).unwrap().is_null());
+    }
+
+    #[test]
+    fn duplicate_array_lays_out_a_row() {
+        let mut scene = scene_with_cubes(&[[1.0, 0.5, 0.0]]);
+        let path = scene.path_for_object_id(0).unwrap();
+
+        let copies = scene.duplicate_array(&path, 3, [2.0, 0.0, 0.0]);
+
+        // The original is the first of the three
+        assert_eq!(copies.len(), 2);
+        let xs: Vec<f32> = std::iter::once(&path[..1].to_vec()).chain(&copies)
+            .map(|path| scene.world_transform(path).unwrap().to_cols_array()[12])
+            .collect();
+        assert_eq!(xs, vec![1.0, 3.0, 5.0]);
+        // Sharing the cube's mesh
+        assert_eq!(scene.get_model_list().len(), 1);
+        assert_eq!(scene.object_count(), 3);
+        assert!(scene.duplicate_array(&path, 1, [2.0, 0.0, 0.0]).is_empty());
+    }
+
+    #[test]
+    fn sphere_pick_normal_is_smooth() {
+        let mut scene = Scene::new();
+        let mesh_id = scene.add_sphere(1.0);
+        place_model(&mut scene, mesh_id, [5.0, 0.0, 0.0]);
+        let origin = Point3::new(5.3, 0.37, 10.0);
+        let ray = Ray3::new(origin, Direction3 { vec3: Vec3::new(0.0, 0.0, -1.0) });
+
+        let hit = scene.raycast_closest_hit(ray, &RaycastOptions::default()).unwrap();
+
+        // Close to the true sphere normal rather than the facet's, and facing the ray origin
+        let normal = hit.normal.vec3;
+        let position = hit.hit_response.hit_position.vec3;
+        assert!(normal.dot(&Vec3::new(position.x - 5.0, position.y, position.z).normalize()) > 0.995);
+        assert!(normal.dot(&(origin - hit.hit_response.hit_position).vec3.normalize()) > 0.8);
+    }
+
+    #[test]
+    fn scene_graph_tree_keeps_groups() {
+        let mut scene = scene_with_cubes(&[[1.0, 0.0, 0.0], [3.0, 0.0, 0.0]]);
+        let mut group = SceneGraphNode::new();
+        group.name = Some("Pair".to_string());
+        group.locked = true;
+        group.edges = std::mem::take(&mut scene.root.edges);
+        let group_id = scene.root.add_child(SceneGraphChild::Node(Box::new(group)));
+
+        let tree = scene.get_scene_graph();
+
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].edge_id, group_id.to_string());
+        assert_eq!(tree[0].name, "Pair");
+        assert!(tree[0].locked && !tree[0].is_model);
+        assert_eq!(tree[0].children.len(), 2);
+        assert!(tree[0].children.iter().all(|child| {
+            !child.is_model && child.children.len() == 1 && child.children[0].is_model && child.children[0].mesh_id.is_some()
+        }));
+        // Plain JSON, ready for the outliner
+        let json = serde_json::to_value(&tree).unwrap();
+        assert_eq!(json[0]["children"][1]["children"][0]["is_model"], true);
+    }
+
+    #[test]
+    fn join_two_cubes_into_one_object() {
+        let mut scene = scene_with_cubes(&[[-3.0, 0.0, 0.0], [4.0, 1.0, 0.0]]);
+        let first = scene.path_for_object_id(0).unwrap();
+        let second = scene.path_for_object_id(1).unwrap();
+        scene.select_by_edge_path(first.clone());
+
+        let joined = scene.join(&[first, second]).unwrap();
+
+        assert_eq!(scene.object_count(), 1);
+        assert!(scene.get_selected_path().is_none());
+        // Both cubes' 12 triangles, still where they were in the world
+        let mesh = scene.world_mesh(&joined).unwrap();
+        assert_eq!(mesh.face_count(), 24);
+        assert_close(mesh.signed_volume(), 2.0);
+        let (min, max) = scene.object_bounds(&joined).unwrap();
+        assert_close(min.vec3.x, -3.5);
+        assert_close(max.vec3.x, 4.5);
+        assert_close(max.vec3.y, 1.5);
+        // The shared cube mesh was replaced by the joined one
+        assert_eq!(scene.get_model_list().len(), 1);
+    }
+
+    #[test]
+    fn zero_direction_ray_hits_nothing() {
+        let scene = scene_with_cubes(&[[0.0, 0.0, -5.0]]);
+        let origin = Point3::new(0.0, 0.0, 0.0);
+
+        let zero = Ray3::new(origin, Direction3::from([0.0, 0.0, 0.0]));
+        assert!(scene.raycast_closest_hit(zero, &RaycastOptions::default()).is_none());
+        assert!(scene.raycast_all_hits(zero, &RaycastOptions::default()).is_empty());
+        assert!(scene.raycast_snap(zero, SnapMode::Vertex).is_none());
+        let nan = Ray3::new(origin, Direction3::from([f32::NAN, 0.0, -1.0]));
+        assert!(scene.raycast_closest_hit(nan, &RaycastOptions::default()).is_none());
+
+        let ray = Ray3::new(origin, Direction3::from([0.0, 0.0, -1.0]));
+        assert!(scene.raycast_closest_hit(ray, &RaycastOptions::default()).is_some());
+    }
+
+    #[test]
+    fn baking_shared_mesh_leaves_other_instance_alone() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [5.0, 0.0, 0.0]]);
+        let first = scene.path_for_object_id(0).unwrap();
+        let second = scene.path_for_object_id(1).unwrap();
+        let doubled = Transform::from_scale([2.0, 2.0, 2.0]);
+        scene.update_transforms(vec![(first[..1].to_vec(), doubled.clone())]);
+        let (first_bounds, second_bounds) = (scene.object_bounds(&first).unwrap(), scene.object_bounds(&second).unwrap());
+
+        assert!(scene.apply_transform_to_geometry(&first, &doubled));
+        scene.ensure_synced();
+
+        // The scaled cube looks the same, now with the scale in its own copy of the mesh
+        let (min, max) = scene.object_bounds(&first).unwrap();
+        assert_close(min.vec3.x, first_bounds.0.vec3.x);
+        assert_close(max.vec3.x, first_bounds.1.vec3.x);
+        let (scale, _, _) = scene.world_transform(&first[..1]).unwrap().matrix().to_scale_rotation_translation();
+        assert!(scale.abs_diff_eq(glam::Vec3::ONE, 1e-5), "{scale}");
+        assert_eq!(scene.get_model_list().len(), 2);
+        // The other instance is still a unit cube
+        let (min, max) = scene.object_bounds(&second).unwrap();
+        assert_close(min.vec3.x, second_bounds.0.vec3.x);
+        assert_close(max.vec3.x, 5.5);
+
+        // An unshared mesh is baked in place; mirrors stay outward-facing; flattening is refused
+        assert!(scene.apply_transform_to_geometry(&first, &doubled));
+        assert_eq!(scene.get_model_list().len(), 2);
+        assert!(scene.apply_transform_to_geometry(&second, &Transform::from_scale([-1.0, 1.0, 1.0])));
+        scene.ensure_synced();
+        assert!(scene.world_mesh(&second).unwrap().signed_volume() > 0.0);
+        assert!(!scene.apply_transform_to_geometry(&second, &Transform::from_scale([0.0, 1.0, 1.0])));
+    }
+
+    #[test]
+    fn seeded_scenes_serialize_identically() {
+        let build = |seed: Option<u64>| {
+            let mut scene = Scene::new();
+            scene.set_id_seed(seed);
+            let cube = scene.add_cube(1.0);
+            scene.add_sphere(0.5);
+            // Placed by hand with ids from the scene, as `place_model` takes random ones
+            let mut node = SceneGraphNode::new();
+            node.add_child_with_id(scene.next_edge_id(), SceneGraphChild::Model(cube));
+            let edge_id = scene.next_edge_id();
+            scene.root.add_child_with_id(edge_id, SceneGraphChild::Node(Box::new(node)));
+            let layer = scene.add_layer("copies");
+            scene.duplicate_array(&[layer], 3, [1.0, 0.0, 0.0]);
+            scene.to_json().unwrap()
+        };
+        assert_eq!(build(Some(7)), build(Some(7)));
+        assert_ne!(build(Some(7)), build(Some(8)));
+        // Random ids stay the default
+        assert_ne!(build(None), build(None));
+    }
+
+    #[test]
+    fn pick_cycle_steps_through_stacked_cubes() {
+        // Object ids follow insertion order, so front to back along -Z they are 1, 2, 0
+        let scene = scene_with_cubes(&[[0.0, 0.0, -6.0], [0.0, 0.0, -2.0], [0.0, 0.0, -4.0]]);
+        let ray = Ray3::new(Point3::new(0.0, 0.0, 0.0), Direction3::from([0.0, 0.0, -1.0]));
+        let first = scene.pick_cycle(ray, None).unwrap();
+        let second = scene.pick_cycle(ray, Some(first)).unwrap();
+        let third = scene.pick_cycle(ray, Some(second)).unwrap();
+        assert_eq!((first, second, third), (1, 2, 0));
+        // After the furthest it wraps back to the nearest
+        assert_eq!(scene.pick_cycle(ray, Some(third)), Some(1));
+        // An object off the ray starts again from the nearest; a miss picks nothing
+        assert_eq!(scene.pick_cycle(ray, Some(99)), Some(1));
+        let away = Ray3::new(Point3::new(0.0, 0.0, 0.0), Direction3::from([0.0, 0.0, 1.0]));
+        assert_eq!(scene.pick_cycle(away, Some(1)), None);
+    }
+
+    #[test]
+    fn root_rotation_rotates_every_instance() {
+        let mut scene = scene_with_cubes(&[[1.0, 0.0, 0.0], [0.0, 2.0, 0.0]]);
+        let positions = |scene: &mut Scene| -> Vec<glam::Vec3> {
+            scene.get_render_instances().iter().map(|instance| instance.transform.matrix().w_axis.truncate()).collect()
+        };
+        let before = positions(&mut scene);
+
+        // Z-up to Y-up
+        let rotation = glam::Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2);
+        scene.set_root_transform(Transform::from_rotation(rotation.to_array()));
+        let after = positions(&mut scene);
+        assert_eq!(before.len(), 2);
+        for (before, after) in before.iter().zip(&after) {
+            assert!(after.abs_diff_eq(rotation * *before, 1e-5), "{before} -> {after}");
+        }
+
+        let loaded = Scene::from_json(&scene.to_json().unwrap()).unwrap();
+        assert!(loaded.root_transform().matrix().abs_diff_eq(scene.root_transform().matrix(), 1e-6));
+    }
+
+    #[test]
+    fn edits_log_events_in_order() {
+        let mut scene = scene_with_cubes(&[[0.0; 3]]);
+        scene.take_events();
+
+        let layer = scene.add_layer("helpers");
+        assert!(scene.remove_object(1));
+        match &scene.take_events()[..] {
+            [SceneEvent::ObjectAdded { id: added, path: added_under }, SceneEvent::ObjectRemoved { id: removed, path: removed_from }] => {
+                assert_eq!((*added, *removed), (layer, layer));
+                assert!(added_under.is_empty() && removed_from.is_empty());
+            }
+            events => panic!("unexpected events {}", serde_json::to_string(events).unwrap()),
+        }
+        // Draining empties the log
+        assert!(scene.take_events().is_empty());
+
+        // New geometry is announced before the model switches to it, and the old mesh is
+        // reported gone after
+        let path = scene.path_for_object_id(0).unwrap();
+        let old_id = scene.get_render_instances()[0].mesh_id;
+        let new_id = scene.replace_mesh(&path, Mesh::create_sphere(1.0, 8, 4)).unwrap();
+        match &scene.take_events()[..] {
+            [SceneEvent::MeshAdded { mesh_id: added }, SceneEvent::GeometryChanged { path: changed, mesh_id }, SceneEvent::MeshRemoved { mesh_id: removed }] => {
+                assert_eq!((*added, *mesh_id, *removed), (new_id, new_id, old_id));
+                assert_eq!(changed, &path);
+            }
+            events => panic!("unexpected events {}", serde_json::to_string(events).unwrap()),
+        }
+
+        // Baking a transform does the same, before reporting the transforms it compensated
+        assert!(scene.apply_transform_to_geometry(&path, &Transform::from_scale([2.0, 2.0, 2.0])));
+        let baked_id = scene.get_render_instances()[0].mesh_id;
+        match &scene.take_events()[..] {
+            [SceneEvent::MeshAdded { mesh_id: added }, SceneEvent::GeometryChanged { mesh_id, .. }, SceneEvent::MeshRemoved { mesh_id: removed }, SceneEvent::TransformChanged { .. }] => {
+                assert_eq!((*added, *mesh_id, *removed), (baked_id, baked_id, new_id));
+            }
+            events => panic!("unexpected events {}", serde_json::to_string(events).unwrap()),
+        }
+    }
+
+    /// `count` cubes scattered at random in a 40-unit box, randomly turned and scaled.
+    /// Every other cube goes in a shared, itself transformed, group so paths have depth.
+    fn scattered_cubes(count: usize, seed: u64) -> Scene {
+        let mut rng = SplitMix64::new(seed);
+        let mut random = |low: f32, high: f32| low + (high - low) * rng.next_f32();
+        let mut scene = Scene::new();
+        let mesh_id = scene.add_cube(1.0);
+        let mut group = SceneGraphNode::with_transform(Transform::from_position([0.0, 1.0, 0.0]));
+        for k in 0..count {
+            let position = [random(-20.0, 20.0), random(-20.0, 20.0), random(-20.0, 20.0)];
+            let rotation = glam::Quat::from_euler(glam::EulerRot::XYZ, random(0.0, 3.0), random(0.0, 3.0), random(0.0, 3.0));
+            let scale = [random(0.5, 2.0), random(0.5, 2.0), random(0.5, 2.0)];
+            let mut node = SceneGraphNode::with_transform(Transform::from_position_rotation_scale(position, rotation.to_array(), scale));
+            node.add_child(SceneGraphChild::Model(mesh_id));
+            let parent = if k % 2 == 0 { &mut scene.root } else { &mut group };
+            parent.add_child(SceneGraphChild::Node(Box::new(node)));
+        }
+        scene.root.add_child(SceneGraphChild::Node(Box::new(group)));
+        scene.hierarchy_dirty = true;
+        scene
+    }
+
+    /// Rays from a sphere of radius 60 round the origin towards random points near it
+    fn random_rays(count: usize, seed: u64) -> Vec<Ray3> {
+        let mut rng = SplitMix64::new(seed);
+        let mut random = move || glam::Vec3::new(rng.next_f32(), rng.next_f32(), rng.next_f32()) * 2.0 - 1.0;
+        (0..count)
+            .map(|_| {
+                let origin = random().normalize_or(glam::Vec3::Z) * 60.0;
+                let target = random() * 20.0;
+                Ray3::new(Point3::new(origin.x, origin.y, origin.z), Direction3::from((target - origin).to_array()))
+            })
+            .collect()
+    }
+
+    /// Cast with the render cache current, so through the instance tree, then with the
+    /// hierarchy marked dirty, so by walking the graph
+    fn tree_and_graph<T>(scene: &mut Scene, cast: impl Fn(&Scene) -> T) -> (T, T) {
+        scene.rebuild_cache();
+        assert!(!scene.hierarchy_dirty);
+        let through_tree = cast(scene);
+        scene.hierarchy_dirty = true;
+        (through_tree, cast(scene))
+    }
+
+    fn assert_same_hit(tree: &WorldHitResponse, graph: &WorldHitResponse) {
+        assert_eq!(tree.object_id, graph.object_id);
+        assert_eq!(tree.selection_path, graph.selection_path);
+        assert_eq!(tree.triangle_index, graph.triangle_index);
+        assert_close(tree.distance, graph.distance);
+    }
+
+    #[test]
+    fn instance_tree_raycasts_match_graph_traversal() {
+        let mut scene = scattered_cubes(300, 5);
+        let rays = random_rays(500, 9);
+        let options = RaycastOptions::default();
+
+        let (tree, graph) = tree_and_graph(&mut scene, |scene| {
+            rays.iter().map(|&ray| scene.raycast_closest_hit(ray, &options)).collect::<Vec<_>>()
+        });
+        assert!(graph.iter().filter(|hit| hit.is_some()).count() > 100);
+        for (tree, graph) in tree.iter().zip(&graph) {
+            match (tree, graph) {
+                (Some(tree), Some(graph)) => assert_same_hit(tree, graph),
+                (None, None) => {}
+                _ => panic!("tree hit {:?}, graph hit {:?}", tree.as_ref().map(|hit| hit.object_id), graph.as_ref().map(|hit| hit.object_id)),
+            }
+        }
+
+        let (tree, graph) = tree_and_graph(&mut scene, |scene| {
+            rays.iter().map(|&ray| scene.raycast_all_hits(ray, &options)).collect::<Vec<_>>()
+        });
+        assert!(graph.iter().any(|hits| hits.len() > 2));
+        for (tree, graph) in tree.iter().zip(&graph) {
+            assert_eq!(tree.len(), graph.len());
+            for (tree, graph) in tree.iter().zip(graph) {
+                assert_same_hit(tree, graph);
+            }
+        }
+    }
+
+    #[test]
+    fn instance_tree_stops_only_past_the_closest_hit() {
+        // Along the ray, in order of where it enters each object's bounds: the sphere's at 4,
+        // the cube's at 4.2 and the far cube's at 19.5. The ray grazes the sphere, hitting it
+        // only at about 4.56, so the near cube (id 1) must still be tested and wins; the far
+        // cube (id 2) starts past that hit and is never tested.
+        let mut scene = Scene::new();
+        let sphere = scene.add_sphere(1.0);
+        let cube = scene.add_cube(1.0);
+        place_model(&mut scene, sphere, [0.0, 0.0, -5.0]);
+        place_model(&mut scene, cube, [0.9, 0.0, -4.7]);
+        place_model(&mut scene, cube, [0.9, 0.0, -20.0]);
+        let ray = Ray3::new(Point3::new(0.9, 0.0, 0.0), Direction3::from([0.0, 0.0, -1.0]));
+
+        let (tree, graph) = tree_and_graph(&mut scene, |scene| scene.raycast_closest_hit(ray, &RaycastOptions::default()));
+        let (tree, graph) = (tree.unwrap(), graph.unwrap());
+        assert_same_hit(&tree, &graph);
+        assert_eq!(tree.object_id, 1);
+        assert_close(tree.distance, 4.2);
+
+        let (tree, graph) = tree_and_graph(&mut scene, |scene| scene.raycast_all_hits(ray, &RaycastOptions::default()));
+        let ids = |hits: &[WorldHitResponse]| hits.iter().map(|hit| hit.object_id).collect::<Vec<_>>();
+        assert_eq!(ids(&tree), [1, 0, 2]);
+        assert_eq!(ids(&graph), [1, 0, 2]);
+    }
+
+    #[test]
+    fn instance_tree_breaks_distance_ties_by_object_id() {
+        // Coincident cubes are hit at exactly the same distance; the lowest id wins
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, -5.0]; 6]);
+        let ray = Ray3::new(Point3::new(0.0, 0.0, 0.0), Direction3::from([0.0, 0.0, -1.0]));
+
+        let (tree, graph) = tree_and_graph(&mut scene, |scene| scene.raycast_closest_hit(ray, &RaycastOptions::default()));
+        assert_eq!((tree.unwrap().object_id, graph.unwrap().object_id), (0, 0));
+
+        let (tree, graph) = tree_and_graph(&mut scene, |scene| scene.raycast_all_hits(ray, &RaycastOptions::default()));
+        let ids = |hits: &[WorldHitResponse]| hits.iter().map(|hit| hit.object_id).collect::<Vec<_>>();
+        assert_eq!(ids(&tree), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(ids(&graph), [0, 1, 2, 3, 4, 5]);
+    }
+
+    // Timing comparison rather than a check; run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_instance_tree_picking() {
+        let mut scene = scattered_cubes(5000, 3);
+        let rays = random_rays(1000, 4);
+        let options = RaycastOptions::default();
+
+        let start = std::time::Instant::now();
+        scene.rebuild_cache();
+        let build = start.elapsed();
+
+        let time = |scene: &Scene| {
+            let start = std::time::Instant::now();
+            let hits = rays.iter().filter(|&&ray| scene.raycast_closest_hit(ray, &options).is_some()).count();
+            (start.elapsed(), hits)
+        };
+        let (tree, tree_hits) = time(&scene);
+        scene.hierarchy_dirty = true;
+        let (graph, graph_hits) = time(&scene);
+        assert_eq!(tree_hits, graph_hits);
+
+        println!(
+            "5000 objects, {} rays: tree {tree:?} (built in {build:?}), graph {graph:?} ({:.1}x)",
+            rays.len(),
+            graph.as_secs_f64() / tree.as_secs_f64()
+        );
+    }
+
+    #[test]
+    fn larger_object_needs_larger_framing_distance() {
+        let mut scene = scene_with_cubes(&[[0.0, 0.0, 0.0], [5.0, 0.0, 0.0]]);
+        let small = scene.path_for_object_id(0).unwrap();
+        let large = scene.path_for_object_id(1).unwrap();
+        scene.update_transforms(vec![(large[..1].to_vec(), Transform::from_position_rotation_scale([5.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0], [3.0; 3]))]);
+        let facing = [0.0, 0.0, 0.0, 1.0];
+
+        // The unit cube's bounding sphere has radius sqrt(3) / 2
+        let near = scene.framing_distance(facing, 1.0, &small).unwrap();
+        assert_close(near, 3f32.sqrt() / 2.0 / 0.5f32.sin());
+        let far = scene.framing_distance(facing, 1.0, &large).unwrap();
+        assert_close(far, 3.0 * near);
+
+        // The camera's rotation doesn't change the fit; a wider view brings it closer
+        let turned = glam::Quat::from_rotation_y(1.0).to_array();
+        assert_close(scene.framing_distance(turned, 1.0, &small).unwrap(), near);
+        assert!(scene.framing_distance(facing, 1.5, &small).unwrap() < near);
+        assert!(scene.framing_distance(facing, 0.0, &small).is_none());
     }
-}
\ No newline at end of file
+}
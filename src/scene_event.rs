@@ -0,0 +1,31 @@
+use serde::Serialize;
+use crate::Transform;
+use crate::render_instance::MeshId;
+use crate::scene_graph::EdgeId;
+
+/// A change made to the scene, recorded so an undo stack or collaborating peers can follow
+/// along. Objects are named by edge paths from the root (empty for the root itself).
+/// Selection changes are not recorded, as they aren't part of the saved scene.
+#[derive(Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SceneEvent {
+    /// A mesh was stored. Objects using it are reported separately by `ObjectAdded`.
+    MeshAdded { mesh_id: MeshId },
+    /// A mesh was dropped from storage, as no model uses it any more
+    MeshRemoved { mesh_id: MeshId },
+    /// A node or model was inserted as edge `id` under the node at `path`
+    ObjectAdded { id: EdgeId, path: Vec<EdgeId> },
+    /// Edge `id` and everything below it was removed from the node at `path`
+    ObjectRemoved { id: EdgeId, path: Vec<EdgeId> },
+    /// The node at `path` has a new local transform
+    TransformChanged { path: Vec<EdgeId>, transform: Transform },
+    /// The model at `path` now uses the mesh `mesh_id`
+    GeometryChanged { path: Vec<EdgeId>, mesh_id: MeshId },
+    VisibilityChanged { path: Vec<EdgeId>, visible: bool },
+    LockChanged { path: Vec<EdgeId>, locked: bool },
+    MetadataChanged { path: Vec<EdgeId> },
+    /// The working origin moved; top-level objects were shifted to match
+    OriginChanged { offset: [f64; 3] },
+    /// The scene was cleared or loaded as a whole; observers should resync from `to_json`
+    Reset,
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::{CatmullRomSpline, FaceIndex, HalfEdgeMesh, Mesh, ModelWrapper, SceneGraphChild, SceneGraphNode, Transform};
+use crate::model::ModelVariant;
+use crate::scene_graph::{EdgeId, SceneGraphEdge};
+
+/// Version written by `Scene::to_json`. Bump it whenever the document layout changes,
+/// and migrate the previous version in `parse_document`.
+pub const FORMAT_VERSION: u32 = 5;
+
+// =================== SAVED SCENE DOCUMENT ===================
+
+/// Top-level saved scene: mesh storage plus the graph that places it
+#[derive(Serialize, Deserialize)]
+pub struct SceneDocument {
+    pub format_version: u32,
+    pub meshes: Vec<MeshDocument>,
+    pub root: NodeDocument,
+    /// World position of the scene's working origin (see `Scene::set_origin_offset`)
+    #[serde(default)]
+    pub origin_offset: [f64; 3],
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MeshDocument {
+    pub id: Uuid,
+    pub name: String,
+    pub geometry: GeometryDocument,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GeometryDocument {
+    /// Editable half-edge mesh, stored as polygon loops so quads survive the round trip
+    HalfEdge { vertex_coords: Vec<f32>, polygons: Vec<Vec<u32>> },
+    /// Plain render mesh
+    Mesh { mesh: Mesh },
+    /// Tube swept along a spline, kept parametric
+    Curve { curve: CatmullRomSpline },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NodeDocument {
+    pub transform: [f32; 16], // Column-major local matrix
+    pub children: Vec<EdgeDocument>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub metadata: serde_json::Value,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EdgeDocument {
+    pub edge_id: Uuid,
+    pub child: ChildDocument,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChildDocument {
+    Node(NodeDocument),
+    Model { mesh_id: Uuid },
+}
+
+/// Parse a saved scene, checking its format version first so that files from newer
+/// builds fail loudly instead of loading half-understood data
+pub fn parse_document(json: &str) -> Result<SceneDocument, String> {
+    let mut value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| format!("Invalid scene JSON: {e}"))?;
+    let version = value.get("format_version")
+        .and_then(serde_json::Value::as_u64)
+        .ok_or_else(|| "Scene JSON has no format_version".to_string())?;
+
+    match version {
+        v if v > FORMAT_VERSION as u64 => return Err(format!(
+            "Scene format version {v} is newer than the supported version {FORMAT_VERSION}; update DeltaBrush to load it"
+        )),
+        0 => return Err("Scene format version 0 is not supported".to_string()),
+        _ => {}
+    }
+
+    // Older versions get upgraded here, one version at a time, before parsing
+    for from in version..FORMAT_VERSION as u64 {
+        upgrade_document(&mut value, from);
+        value["format_version"] = (from + 1).into();
+    }
+    serde_json::from_value(value)
+        .map_err(|e| format!("Malformed scene (format version {version}): {e}"))
+}
+
+/// Rewrite a document of format version `from` into version `from + 1`
+fn upgrade_document(_value: &mut serde_json::Value, from: u64) {
+    match from {
+        // Version 2 added curve geometry, which version 1 documents simply don't contain
+        1 => {}
+        // Version 3 added node names and the visible/locked flags. Older nodes were all
+        // unnamed, visible and unlocked, which is what the serde defaults fill in.
+        2 => {}
+        // Version 4 added the scene's origin offset. Older scenes stored world coordinates
+        // directly, i.e. with the origin at zero, which is the serde default.
+        3 => {}
+        // Version 5 added node metadata; older nodes had none, which loads as null
+        4 => {}
+        _ => unreachable!("no upgrade from format version {from}"),
+    }
+}
+
+impl GeometryDocument {
+    pub fn from_model(model: &ModelVariant) -> Self {
+        match model {
+            ModelVariant::HalfEdgeMesh(wrapper) => {
+                // Drop tombstoned elements so indices are dense
+                let mut half_edge_mesh = wrapper.model().clone();
+                half_edge_mesh.compact();
+
+                let vertex_coords = half_edge_mesh.vertices.iter()
+                    .flat_map(|vertex| [vertex.position.vec3.x, vertex.position.vec3.y, vertex.position.vec3.z])
+                    .collect();
+                let polygons = (0..half_edge_mesh.faces.len())
+                    .map(|face| half_edge_mesh.face_vertices(FaceIndex(face)).iter().map(|v| v.0 as u32).collect())
+                    .collect();
+                GeometryDocument::HalfEdge { vertex_coords, polygons }
+            }
+            ModelVariant::Mesh(mesh) => GeometryDocument::Mesh { mesh: mesh.clone() },
+            ModelVariant::Curve(wrapper) => GeometryDocument::Curve { curve: wrapper.model().clone() },
+        }
+    }
+
+    pub fn into_model(self) -> Result<ModelVariant, String> {
+        match self {
+            GeometryDocument::HalfEdge { vertex_coords, polygons } => {
+                let half_edge_mesh = HalfEdgeMesh::from_polygons(&vertex_coords, &polygons)?;
+                Ok(ModelVariant::HalfEdgeMesh(Box::new(ModelWrapper::new(half_edge_mesh))))
+            }
+            GeometryDocument::Mesh { mesh } => Ok(ModelVariant::Mesh(mesh)),
+            GeometryDocument::Curve { curve } => Ok(ModelVariant::Curve(Box::new(ModelWrapper::new(curve)))),
+        }
+    }
+}
+
+impl NodeDocument {
+    pub fn from_node(node: &SceneGraphNode) -> Self {
+        NodeDocument {
+            transform: node.transform.to_cols_array(),
+            children: node.edges.iter()
+                .map(|edge| EdgeDocument {
+                    edge_id: edge.edge_id.as_uuid(),
+                    child: match &edge.child {
+                        SceneGraphChild::Node(child_node) => ChildDocument::Node(NodeDocument::from_node(child_node)),
+                        SceneGraphChild::Model(mesh_id) => ChildDocument::Model { mesh_id: mesh_id.0 },
+                    },
+                })
+                .collect(),
+            name: node.name.clone(),
+            visible: node.visible,
+            locked: node.locked,
+            metadata: node.metadata.clone(),
+        }
+    }
+
+    pub fn into_node(self) -> SceneGraphNode {
+        SceneGraphNode {
+            transform: Transform::from_cols_array(self.transform),
+            edges: self.children.into_iter()
+                .map(|edge| SceneGraphEdge {
+                    edge_id: EdgeId::from_uuid(edge.edge_id),
+                    child: match edge.child {
+                        ChildDocument::Node(node) => SceneGraphChild::Node(Box::new(node.into_node())),
+                        ChildDocument::Model { mesh_id } => SceneGraphChild::Model(crate::MeshId(mesh_id)),
+                    },
+                })
+                .collect(),
+            name: self.name,
+            visible: self.visible,
+            locked: self.locked,
+            metadata: self.metadata,
+        }
+    }
+}
@@ -1,11 +1,13 @@
-use crate::{Point3, RenderInstance, Transform, Transformable, algorithms::moller_trumbore_intersection_exterior_algebra, geometry::{Ray3, WorldHitResponse}, model::{ModelVariant, ModelEntry}};
+use crate::{Mesh, Point3, RenderInstance, Transform, Transformable, algorithms::{moller_trumbore_packet, TrianglePacket}, geometry::{Direction3, HitResponse, Ray3, RaycastOptions, WorldHitResponse, transform_bounds, union_bounds}, model::{ModelVariant, ModelEntry}};
 use crate::render_instance::MeshId;
+use crate::algorithms::seeded_uuid;
+use crate::visitor::SceneVisitor;
+use serde::Serialize;
 use uuid::Uuid;
 use std::collections::HashMap;
 
-
 /// Unique identifier for an edge in the scene graph
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub struct EdgeId(Uuid);
 
 impl EdgeId {
@@ -13,7 +15,17 @@ impl EdgeId {
     pub fn new() -> Self {
         EdgeId(Uuid::new_v4())
     }
+
+    /// Deterministic edge ID: the same seed always gives the same ID
+    pub fn from_seed(seed: u64) -> Self {
+        EdgeId(seeded_uuid(seed))
+    }
     
+    /// Wrap an existing UUID, e.g. one read back from a saved scene
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        EdgeId(uuid)
+    }
+
     /// Get the underlying UUID
     pub fn as_uuid(&self) -> Uuid {
         self.0
@@ -52,15 +64,16 @@ pub struct SceneGraphEdge {
 pub struct SceneGraphNode {
     pub transform: Transform,
     pub edges: Vec<SceneGraphEdge>,  // Children accessed via edges with UUIDs
+    pub name: Option<String>,  // Display name, set for layers
+    pub visible: bool,  // Hidden subtrees are neither rendered nor hit by raycasts
+    pub locked: bool,  // Locked subtrees render but can't be picked by raycasts
+    pub metadata: serde_json::Value,  // Opaque app data (tags, source file...), only stored and saved
 }
 
 impl SceneGraphNode {
     /// Create a new scene graph node with identity transform
     pub fn new() -> Self {
-        SceneGraphNode {
-            transform: Transform::identity(),
-            edges: Vec::new(),
-        }
+        SceneGraphNode::with_transform(Transform::identity())
     }
 
     /// Create a new scene graph node with a specific transform
@@ -68,16 +81,50 @@ impl SceneGraphNode {
         SceneGraphNode {
             transform,
             edges: Vec::new(),
+            name: None,
+            visible: true,
+            locked: false,
+            metadata: serde_json::Value::Null,
         }
     }
 
     /// Add a child to this node, returns the edge ID
     pub fn add_child(&mut self, child: SceneGraphChild) -> EdgeId {
-        let edge_id = EdgeId::new();
+        self.add_child_with_id(EdgeId::new(), child)
+    }
+
+    /// Add a child under a given edge ID, returns the edge ID
+    pub fn add_child_with_id(&mut self, edge_id: EdgeId, child: SceneGraphChild) -> EdgeId {
         self.edges.push(SceneGraphEdge { edge_id, child });
         edge_id
     }
 
+    /// Number of models in this subtree that `flatten_to_render_instances` would emit
+    pub fn visible_model_count(&self) -> usize {
+        self.edges.iter()
+            .map(|edge| match &edge.child {
+                SceneGraphChild::Node(child_node) if !child_node.visible => 0,
+                SceneGraphChild::Node(child_node) => child_node.visible_model_count(),
+                SceneGraphChild::Model(_) => 1,
+            })
+            .sum()
+    }
+
+    /// Approximate size in bytes of this node and its subtree (structure only, not geometry)
+    pub fn memory_estimate(&self) -> usize {
+        let mut total = std::mem::size_of::<SceneGraphNode>()
+            + self.edges.len() * std::mem::size_of::<SceneGraphEdge>()
+            + self.name.as_ref().map_or(0, String::len);
+
+        for edge in &self.edges {
+            if let SceneGraphChild::Node(child_node) = &edge.child {
+                total += child_node.memory_estimate();
+            }
+        }
+
+        total
+    }
+
     /// Sync all render meshes in the subtree
     pub fn sync_render_mesh(&mut self, meshes: &mut HashMap<MeshId, ModelEntry>) {
         for edge in &mut self.edges {
@@ -95,15 +142,22 @@ impl SceneGraphNode {
     }
 
     /// Flatten the scene graph into a list of renderable instances
-    /// This is what JavaScript needs for rendering
+    /// This is what JavaScript needs for rendering.
+    /// Branches nested deeper than `max_depth` edges are skipped.
     pub fn flatten_to_render_instances(
         &self, 
         parent_transform: &Transform, 
         object_id: &mut usize, 
         meshes: &HashMap<MeshId, ModelEntry>,
         current_path: &[EdgeId],
-        selected_path: Option<&Vec<EdgeId>>
+        selected_path: Option<&Vec<EdgeId>>,
+        max_depth: usize
     ) -> Vec<RenderInstance> {
+        if current_path.len() >= max_depth {
+            crate::console_log!("Scene graph deeper than {} levels, skipping the rest of this branch", max_depth);
+            return Vec::new();
+        }
+
         let world_transform = self.transform.compose_with_parent(parent_transform);
         let mut instances = Vec::new();
 
@@ -112,6 +166,7 @@ impl SceneGraphNode {
             child_path.push(edge.edge_id);
             
             match &edge.child {
+                SceneGraphChild::Node(child_node) if !child_node.visible => {}
                 SceneGraphChild::Node(child_node) => {
                     // Recursively flatten child nodes
                     instances.extend(child_node.flatten_to_render_instances(
@@ -119,7 +174,8 @@ impl SceneGraphNode {
                         object_id, 
                         meshes,
                         &child_path,
-                        selected_path
+                        selected_path,
+                        max_depth
                     ));
                 }
                 SceneGraphChild::Model(mesh_id) => {
@@ -133,7 +189,9 @@ impl SceneGraphNode {
                         mesh_id: *mesh_id,
                         transform: world_transform.clone(),
                         id: *object_id,
+                        stable_id: edge.edge_id.to_string(),
                         is_selected,
+                        path: child_path,
                     });
                     *object_id += 1;
                 }
@@ -143,50 +201,155 @@ impl SceneGraphNode {
         instances
     }
 
+    /// Walk this subtree depth-first, visiting each node before its children
+    /// Models are visited with their enclosing node's world transform
+    pub fn accept<V: SceneVisitor + ?Sized>(&self, parent_transform: &Transform, visitor: &mut V) {
+        let world_transform = self.transform.compose_with_parent(parent_transform);
+        visitor.visit_node(self, &world_transform);
+
+        for edge in &self.edges {
+            match &edge.child {
+                SceneGraphChild::Node(child_node) => child_node.accept(&world_transform, visitor),
+                SceneGraphChild::Model(mesh_id) => visitor.visit_model(*mesh_id, &world_transform),
+            }
+        }
+    }
+
+    /// World-space axis-aligned bounds of every model in this subtree
+    /// Returns None if the subtree holds no geometry
+    pub fn world_bounds(
+        &self,
+        parent_transform: &Transform,
+        meshes: &HashMap<MeshId, ModelEntry>
+    ) -> Option<(Point3, Point3)> {
+        let world_transform = self.transform.compose_with_parent(parent_transform);
+        let mut bounds: Option<(Point3, Point3)> = None;
+
+        for edge in &self.edges {
+            let child_bounds = match &edge.child {
+                SceneGraphChild::Node(child_node) => child_node.world_bounds(&world_transform, meshes),
+                SceneGraphChild::Model(mesh_id) => Self::model_world_bounds(mesh_id, &world_transform, meshes),
+            };
+
+            if let Some(child_bounds) = child_bounds {
+                bounds = Some(match bounds {
+                    None => child_bounds,
+                    Some(existing) => union_bounds(existing, child_bounds),
+                });
+            }
+        }
+
+        bounds
+    }
+
+    /// World-space axis-aligned bounds of a single model placed with the given world transform
+    pub fn model_world_bounds(
+        mesh_id: &MeshId,
+        world_transform: &Transform,
+        meshes: &HashMap<MeshId, ModelEntry>
+    ) -> Option<(Point3, Point3)> {
+        let (min, max) = meshes.get(mesh_id)?.model.get_mesh().bounding_box()?;
+        Some(transform_bounds(min, max, world_transform))
+    }
+
+    /// Bake every model in this subtree into a single world-space mesh
+    pub fn flatten_subtree_to_mesh(
+        &self,
+        parent_transform: &Transform,
+        meshes: &HashMap<MeshId, ModelEntry>
+    ) -> Mesh {
+        let world_transform = self.transform.compose_with_parent(parent_transform);
+        let mut flattened = Mesh::new();
+
+        for edge in &self.edges {
+            match &edge.child {
+                SceneGraphChild::Node(child_node) => {
+                    flattened.append(&child_node.flatten_subtree_to_mesh(&world_transform, meshes));
+                }
+                SceneGraphChild::Model(mesh_id) => {
+                    if let Some(entry) = meshes.get(mesh_id) {
+                        let mut mesh = entry.model.get_mesh().clone();
+                        mesh.apply_transform(&world_transform);
+                        flattened.append(&mesh);
+                    }
+                }
+            }
+        }
+
+        flattened
+    }
+
     /// Perform raycast against this node and all children
     /// Returns the closest hit in world coordinates
+    #[allow(clippy::too_many_arguments)]
     pub fn raycast_closest_hit(
         &self, 
         ray: Ray3, 
         parent_transform: &Transform, 
         object_id: &mut usize, 
         meshes: &HashMap<MeshId, ModelEntry>,
-        current_path: &mut Vec<EdgeId>
+        current_path: &mut Vec<EdgeId>,
+        options: &RaycastOptions,
+        max_depth: usize
     ) -> Option<WorldHitResponse> {
+        let mut closest: Option<WorldHitResponse> = None;
+        self.raycast_each_model(ray, parent_transform, object_id, meshes, current_path, options, max_depth, &mut |hit| {
+            let should_replace = match &closest {
+                None => true,
+                Some(existing) => hit.distance < existing.distance,
+            };
+            if should_replace {
+                closest = Some(hit);
+            }
+        });
+        closest
+    }
+
+    /// Perform raycast against this node and all children, reporting the closest hit on
+    /// every model the ray hits (in traversal order, not by distance).
+    /// Branches nested deeper than `max_depth` edges are skipped.
+    #[allow(clippy::too_many_arguments)]
+    pub fn raycast_each_model(
+        &self,
+        ray: Ray3,
+        parent_transform: &Transform,
+        object_id: &mut usize,
+        meshes: &HashMap<MeshId, ModelEntry>,
+        current_path: &mut Vec<EdgeId>,
+        options: &RaycastOptions,
+        max_depth: usize,
+        on_hit: &mut dyn FnMut(WorldHitResponse)
+    ) {
+        if current_path.len() >= max_depth {
+            crate::console_log!("Scene graph deeper than {} levels, raycast skips the rest of this branch", max_depth);
+            return;
+        }
+
         // Compose this node's transform with the parent's
         let world_transform = self.transform.compose_with_parent(parent_transform);
-        
-        let mut closest: Option<WorldHitResponse> = None;
 
         // Check all children
         for edge in &self.edges {
             current_path.push(edge.edge_id);
             
             match &edge.child {
+                // Hidden and locked subtrees can't be picked
+                SceneGraphChild::Node(child_node) if !child_node.visible => {}
+                SceneGraphChild::Node(child_node) if child_node.locked => {
+                    // Locked models are still rendered, so they keep their object ids
+                    *object_id += child_node.visible_model_count();
+                }
                 SceneGraphChild::Node(child_node) => {
                     // Recursively check child nodes
-                    if let Some(hit) = child_node.raycast_closest_hit(ray, &world_transform, object_id, meshes, current_path) {
-                        let should_replace = match &closest {
-                            None => true,
-                            Some(existing) => hit.distance < existing.distance,
-                        };
-                        if should_replace {
-                            closest = Some(hit);
-                        }
-                    }
+                    child_node.raycast_each_model(ray, &world_transform, object_id, meshes, current_path, options, max_depth, on_hit);
                 }
                 SceneGraphChild::Model(mesh_id) => {
-                    // Check ray intersection with this model
-                    if let Some(entry) = meshes.get(mesh_id) {
-                        if let Some(mut hit) = Self::raycast_model(ray, &entry.model, &world_transform, *object_id) {
-                            let should_replace = match &closest {
-                                None => true,
-                                Some(existing) => hit.distance < existing.distance,
-                            };
-                            if should_replace {
-                                hit.selection_path = current_path.clone();
-                                closest = Some(hit);
-                            }
+                    // Check ray intersection with this model, unless it is on the ignore list
+                    let ignored = options.ignore.contains(object_id);
+                    if let Some(entry) = meshes.get(mesh_id).filter(|_| !ignored) {
+                        if let Some(mut hit) = Self::raycast_model(ray, &entry.model, &world_transform, *object_id, options) {
+                            hit.selection_path = current_path.clone();
+                            on_hit(hit);
                         }
                     }
                     *object_id += 1;
@@ -195,33 +358,48 @@ impl SceneGraphNode {
             
             current_path.pop();
         }
-
-        closest
     }
 
     /// Raycast against a single model with a given world transform
-    fn raycast_model(ray: Ray3, model: &ModelVariant, world_transform: &Transform, object_id: usize) -> Option<WorldHitResponse> {
+    pub(crate) fn raycast_model(ray: Ray3, model: &ModelVariant, world_transform: &Transform, object_id: usize, options: &RaycastOptions) -> Option<WorldHitResponse> {
         let mesh = model.get_mesh();
         let transformed_ray = ray.inverse_transform(world_transform);
         let mut closest: Option<WorldHitResponse> = None;
 
-        // Go through each triangle and perform ray intersection
+        // Test the triangles four at a time; the last packet may be partly empty
         let vert_coords = &mesh.vertex_coords;
-        let mut chunks = mesh.face_indices.chunks_exact(3);
-        for tri in &mut chunks {
-            let i0 = tri[0] as usize;
-            let i1 = tri[1] as usize;
-            let i2 = tri[2] as usize;
+        let p = |i: u32| {
+            let i = i as usize;
+            Point3::new(vert_coords[3 * i], vert_coords[3 * i + 1], vert_coords[3 * i + 2])
+        };
+        let triangle_indices = &mesh.face_indices[..mesh.face_indices.len() - mesh.face_indices.len() % 3];
+        let local_direction = transformed_ray.direction().vec3;
+        for (packet_index, group) in triangle_indices.chunks(12).enumerate() {
+            let mut packet = TrianglePacket::default();
+            for (lane, tri) in group.chunks_exact(3).enumerate() {
+                packet.set_lane(lane, p(tri[0]), p(tri[1]), p(tri[2]));
+            }
+
+            let distances = moller_trumbore_packet(transformed_ray, &packet);
+            for (lane, &t) in distances.iter().enumerate() {
+                if t == f32::INFINITY {
+                    continue;
+                }
+                let this_hit = HitResponse {
+                    hit_position: transformed_ray.at(t),
+                    hit_direction: Direction3 { vec3: local_direction * t },
+                };
 
-            let p = |i: usize| Point3::new(vert_coords[3 * i], vert_coords[3 * i + 1], vert_coords[3 * i + 2]);
-            
-            if let Some(this_hit)
-                = moller_trumbore_intersection_exterior_algebra(transformed_ray, p(i0), p(i1), p(i2)) {
-                
                 // The hit response was in local coordinates. Transform to world coordinates.
                 let world_hit = this_hit.transform(world_transform);
 
-                let this_world_distance = world_hit.hit_direction.length();
+                // Measure in world space: under non-uniform scale the local `t` is stretched
+                // differently per direction, so it can't be compared across triangles or objects
+                let this_world_distance = (world_hit.hit_position - ray.origin).length();
+                if this_world_distance < options.t_min {
+                    continue;
+                }
+
                 let should_update = match &closest {
                     None => true,
                     Some(existing) =>
@@ -229,20 +407,109 @@ impl SceneGraphNode {
                 };
 
                 if should_update {
+                    let triangle_index = 4 * packet_index + lane;
+                    let local_normal = Self::surface_normal(mesh, triangle_index, this_hit.hit_position);
+                    let normal = world_transform.matrix().inverse().transpose()
+                        .transform_vector3(local_normal)
+                        .normalize_or_zero();
                     closest = Some(WorldHitResponse {
                         hit_response: world_hit,
                         distance: this_world_distance,
                         object_id,
                         selection_path: Vec::new(),  // Will be set by caller
+                        triangle_index,
+                        normal: Direction3 { vec3: normal.into() },
                     });
                 }
             }
         }
 
-        if !chunks.remainder().is_empty() {
+        if !mesh.face_indices.len().is_multiple_of(3) {
             crate::console_log!("Mesh indices not a multiple of 3. Trailing mesh indices ignored.");
         }
 
         closest
     }
-}
\ No newline at end of file
+
+    /// Local-space normal at a point on a triangle: the vertex normals blended by the point's
+    /// barycentric coordinates when the mesh has them, otherwise the flat face normal
+    fn surface_normal(mesh: &Mesh, triangle_index: usize, point: Point3) -> glam::Vec3 {
+        let corners = &mesh.face_indices[3 * triangle_index..3 * triangle_index + 3];
+        let position = |i: u32| {
+            let i = 3 * i as usize;
+            glam::Vec3::new(mesh.vertex_coords[i], mesh.vertex_coords[i + 1], mesh.vertex_coords[i + 2])
+        };
+        let [a, b, c] = [0, 1, 2].map(|k| position(corners[k]));
+        let n = (b - a).cross(c - a);
+        let face_normal = n.normalize_or_zero();
+        let Some(normals) = &mesh.normals else {
+            return face_normal;
+        };
+        let area = n.length_squared();
+        if area <= f32::EPSILON * f32::EPSILON {
+            return face_normal;
+        }
+
+        // Barycentric weights from the sub-triangles opposite each corner
+        let p = glam::Vec3::from(point);
+        let weight_b = (p - a).cross(c - a).dot(n) / area;
+        let weight_c = (b - a).cross(p - a).dot(n) / area;
+        let weights = [1.0 - weight_b - weight_c, weight_b, weight_c];
+
+        let blended = corners.iter().zip(weights)
+            .map(|(&corner, weight)| {
+                let i = 3 * corner as usize;
+                glam::Vec3::new(normals[i], normals[i + 1], normals[i + 2]) * weight
+            })
+            .sum::<glam::Vec3>();
+        if blended.length_squared() > f32::EPSILON { blended.normalize() } else { face_normal }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Vec3;
+
+    /// Graph of one node per transform, each holding the same unit cube
+    fn cubes_under(transforms: &[Transform]) -> (SceneGraphNode, HashMap<MeshId, ModelEntry>) {
+        let mesh_id = MeshId::new();
+        let mut meshes = HashMap::new();
+        meshes.insert(mesh_id, ModelEntry { model: ModelVariant::Mesh(Mesh::create_cube(1.0)), name: "cube".into() });
+        let mut root = SceneGraphNode::new();
+        for transform in transforms {
+            let mut node = SceneGraphNode::with_transform(transform.clone());
+            node.add_child(SceneGraphChild::Model(mesh_id));
+            root.add_child(SceneGraphChild::Node(Box::new(node)));
+        }
+        (root, meshes)
+    }
+
+    fn closest_hit(root: &SceneGraphNode, meshes: &HashMap<MeshId, ModelEntry>, origin: Vec3, direction: Vec3) -> WorldHitResponse {
+        let ray = Ray3::new(Point3 { vec3: origin }, Direction3 { vec3: direction });
+        root.raycast_closest_hit(ray, &Transform::identity(), &mut 0, meshes, &mut Vec::new(), &RaycastOptions::default(), 256)
+            .expect("ray should hit")
+    }
+
+    #[test]
+    fn closest_hit_under_non_uniform_scale() {
+        // A is wide and flat, its front face at z = 4.05; B is stretched along z to a front face at z = 5
+        let flat = Transform::from_position_rotation_scale([0.0, 0.0, 4.0], [0.0, 0.0, 0.0, 1.0], [20.0, 1.0, 0.1]);
+        let deep = Transform::from_scale([1.0, 1.0, 10.0]);
+        let (root, meshes) = cubes_under(&[flat, deep]);
+
+        // Straight down the z axis, and with an unnormalized direction
+        for direction in [Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 0.0, -2.0)] {
+            let hit = closest_hit(&root, &meshes, Vec3::new(0.3, 0.0, 20.0), direction);
+            assert_eq!(hit.object_id, 1);
+            assert!((hit.distance - 15.0).abs() < 1e-3, "{}", hit.distance);
+        }
+
+        // An oblique ray reports the true world-space distance to its hit
+        let origin = Vec3::new(3.0, 0.0, 20.0);
+        let hit = closest_hit(&root, &meshes, origin, Vec3::new(-0.1, 0.0, -1.0));
+        let distance = (hit.hit_response.hit_position.vec3 - origin).length();
+        assert!((hit.distance - distance).abs() < 1e-3, "{} vs {distance}", hit.distance);
+    }
+}
@@ -1,5 +1,9 @@
 use serde::{Serialize, Serializer};
-use glam::{Mat4, Vec3 as GlamVec3, Quat};
+use glam::{EulerRot, Mat3, Mat4, Vec3 as GlamVec3, Quat};
+use crate::Point3;
+
+/// Orbiting stops this short of straight up or down (radians), so the view never flips
+const ORBIT_PITCH_LIMIT: f32 = std::f32::consts::FRAC_PI_2 - 0.01;
 
 #[derive(Clone)]
 pub struct Transform {
@@ -74,6 +78,49 @@ impl Transform {
         }
     }
 
+    /// Create a transform that scales equally along every axis
+    pub fn from_uniform_scale(scale: f32) -> Self {
+        Transform {
+            matrix: Mat4::from_scale(GlamVec3::splat(scale)),
+        }
+    }
+
+    /// Whether the three axis scale factors (lengths of the basis vectors) agree within `eps`.
+    /// Uniformly scaled transforms preserve angles, so normals and distances scale simply.
+    pub fn is_uniform_scale(&self, eps: f32) -> bool {
+        let sx = self.matrix.x_axis.truncate().length();
+        let sy = self.matrix.y_axis.truncate().length();
+        let sz = self.matrix.z_axis.truncate().length();
+        (sx - sy).abs() <= eps && (sy - sz).abs() <= eps && (sx - sz).abs() <= eps
+    }
+
+    /// Mirror across the plane through `plane_point` with normal `plane_normal`.
+    /// Returns None for a zero normal. The result has a negative determinant.
+    pub fn from_reflection(plane_point: [f32; 3], plane_normal: [f32; 3]) -> Option<Self> {
+        let n = GlamVec3::from_array(plane_normal).normalize_or_zero();
+        if n == GlamVec3::ZERO {
+            return None;
+        }
+
+        // x' = x - 2 n (n . (x - p)), i.e. (I - 2 n n^T) x + 2 (n . p) n
+        let linear = Mat3::IDENTITY - 2.0 * Mat3::from_cols(n * n.x, n * n.y, n * n.z);
+        let mut matrix = Mat4::from_mat3(linear);
+        matrix.w_axis = (2.0 * n.dot(GlamVec3::from_array(plane_point)) * n).extend(1.0);
+        Some(Transform { matrix })
+    }
+
+    /// Create a transform from a column-major 4x4 matrix
+    pub fn from_cols_array(cols: [f32; 16]) -> Self {
+        Transform {
+            matrix: Mat4::from_cols_array(&cols),
+        }
+    }
+
+    /// The underlying matrix as a column-major array
+    pub fn to_cols_array(&self) -> [f32; 16] {
+        self.matrix.to_cols_array()
+    }
+
     /// Get the underlying matrix
     pub fn matrix(&self) -> Mat4 {
         self.matrix
@@ -94,6 +141,94 @@ impl Transform {
         }
     }
 
+    /// Compose a chain of transforms ordered root to leaf, as met walking down a path.
+    /// The result is `chain[0] * chain[1] * ... * chain[n - 1]`: the leaf applies first,
+    /// the same as folding `compose_with_parent` down the chain. An empty chain is the identity.
+    pub fn compose_chain(chain: &[Transform]) -> Transform {
+        Transform {
+            matrix: chain.iter().fold(Mat4::IDENTITY, |composed, transform| composed * transform.matrix),
+        }
+    }
+
+    /// Swing this transform's position around `pivot` on its current sphere, by `yaw` radians
+    /// about the world Y axis (from +Z towards +X) and `pitch` radians up, then aim its -Z axis
+    /// (the camera forward direction) at the pivot with world Y up. Pitch is clamped short of
+    /// the poles. Scale is kept; a transform sitting on the pivot is returned unchanged.
+    pub fn orbit_around(&self, pivot: Point3, yaw: f32, pitch: f32) -> Transform {
+        let (scale, _, translation) = self.matrix.to_scale_rotation_translation();
+        let pivot = GlamVec3::from(pivot);
+        let offset = translation - pivot;
+        let radius = offset.length();
+        if radius <= f32::EPSILON {
+            return self.clone();
+        }
+
+        let yaw = offset.x.atan2(offset.z) + yaw;
+        let pitch = ((offset.y / radius).asin() + pitch).clamp(-ORBIT_PITCH_LIMIT, ORBIT_PITCH_LIMIT);
+        let position = pivot + radius * GlamVec3::new(pitch.cos() * yaw.sin(), pitch.sin(), pitch.cos() * yaw.cos());
+
+        let forward = (pivot - position).normalize();
+        let right = forward.cross(GlamVec3::Y).normalize();
+        let up = right.cross(forward);
+        let rotation = Quat::from_mat3(&Mat3::from_cols(right, up, -forward));
+
+        Transform {
+            matrix: Mat4::from_scale_rotation_translation(scale, rotation, position),
+        }
+    }
+
+    /// Round translation, rotation (as XYZ Euler angles) and scale to the nearest multiple of
+    /// their step and recompose. A step of 0 leaves that channel untouched. Scale never
+    /// rounds to 0, which would collapse the object: it stays at least one step, keeping its
+    /// sign. Snapping an already-snapped transform is a no-op.
+    pub fn snap(&self, translate_step: f32, rotate_step_radians: f32, scale_step: f32) -> Transform {
+        fn snap_value(value: f32, step: f32) -> f32 {
+            if step > 0.0 { (value / step).round() * step } else { value }
+        }
+        fn snap_scale(value: f32, step: f32) -> f32 {
+            match snap_value(value, step) {
+                0.0 if step > 0.0 => step.copysign(value),
+                snapped => snapped,
+            }
+        }
+
+        let (scale, rotation, translation) = self.matrix.to_scale_rotation_translation();
+
+        let translation = translation.to_array().map(|v| snap_value(v, translate_step));
+        let scale = scale.to_array().map(|v| snap_scale(v, scale_step));
+        let rotation = if rotate_step_radians > 0.0 {
+            let (x, y, z) = rotation.to_euler(EulerRot::XYZ);
+            Quat::from_euler(
+                EulerRot::XYZ,
+                snap_value(x, rotate_step_radians),
+                snap_value(y, rotate_step_radians),
+                snap_value(z, rotate_step_radians),
+            )
+        } else {
+            rotation
+        };
+
+        Transform {
+            matrix: Mat4::from_scale_rotation_translation(
+                GlamVec3::from_array(scale),
+                rotation,
+                GlamVec3::from_array(translation),
+            ),
+        }
+    }
+
+    /// Round the translation to the nearest multiple of `grid`, keeping rotation and scale.
+    /// A grid of 0 means no snapping.
+    pub fn snap_translation(&self, grid: f32) -> Transform {
+        self.snap(grid, 0.0, 0.0)
+    }
+
+    /// Quantize the rotation to multiples of `step_radians` per Euler axis, keeping
+    /// translation and scale. A step of 0 means no snapping.
+    pub fn snap_rotation(&self, step_radians: f32) -> Transform {
+        self.snap(0.0, step_radians, 0.0)
+    }
+
     /// Transform a point (applies translation)
     pub fn transform_point(&self, point: GlamVec3) -> GlamVec3 {
         self.matrix.transform_point3(point)
@@ -104,3 +239,81 @@ impl Transform {
         self.matrix.transform_vector3(vector)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_is_idempotent() {
+        let rotation = Quat::from_euler(EulerRot::XYZ, 0.4, -1.1, 2.3).to_array();
+        let transform = Transform::from_position_rotation_scale([1.3, -0.2, 7.77], rotation, [0.8, 2.6, 1.1]);
+        let step = 15f32.to_radians();
+
+        let once = transform.snap(0.5, step, 0.25);
+        let twice = once.snap(0.5, step, 0.25);
+
+        assert!(once.matrix().abs_diff_eq(twice.matrix(), 1e-5));
+        assert!(!once.matrix().abs_diff_eq(transform.matrix(), 1e-3));
+    }
+
+    #[test]
+    fn snap_keeps_small_scale_invertible() {
+        let transform = Transform::from_position_rotation_scale([0.0; 3], [0.0, 0.0, 0.0, 1.0], [0.1, 0.3, 1.0]);
+
+        let snapped = transform.snap(0.0, 0.0, 1.0);
+
+        let (scale, _, _) = snapped.matrix().to_scale_rotation_translation();
+        assert!(scale.abs_diff_eq(GlamVec3::ONE, 1e-6), "{scale}");
+        assert!(snapped.matrix().determinant() != 0.0);
+
+        // A mirrored scale stays mirrored
+        let mirrored = Transform::from_scale([-0.1, 1.0, 1.0]).snap(0.0, 0.0, 1.0);
+        assert!((mirrored.matrix().determinant() + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn uniform_scale_detection() {
+        assert!(Transform::from_uniform_scale(2.0).is_uniform_scale(1e-5));
+        assert!(Transform::from_scale([2.0, 2.0, 2.0]).is_uniform_scale(1e-5));
+        assert!(!Transform::from_scale([2.0, 1.0, 1.0]).is_uniform_scale(1e-5));
+
+        // Rotation and translation don't affect it
+        let rotation = Quat::from_euler(EulerRot::XYZ, 0.3, 0.2, 0.1).to_array();
+        let transform = Transform::from_position_rotation_scale([1.0, 2.0, 3.0], rotation, [3.0, 3.0, 3.0]);
+        assert!(transform.is_uniform_scale(1e-4));
+    }
+
+    #[test]
+    fn compose_chain_sums_translations() {
+        let chain = [
+            Transform::from_position([1.0, 0.0, 0.0]),
+            Transform::from_position([0.0, 2.0, 0.0]),
+            Transform::from_position([0.0, 0.0, 3.0]),
+        ];
+        assert_eq!(Transform::compose_chain(&chain).transform_point(GlamVec3::ZERO), GlamVec3::new(1.0, 2.0, 3.0));
+
+        // Root first: the same as composing the leaf with its parent
+        let (parent, child) = (Transform::from_position([1.0, 0.0, 0.0]), Transform::from_scale([2.0, 2.0, 2.0]));
+        let composed = Transform::compose_chain(&[parent.clone(), child.clone()]);
+        assert_eq!(composed.matrix(), child.compose_with_parent(&parent).matrix());
+
+        assert_eq!(Transform::compose_chain(&[]).matrix(), Mat4::IDENTITY);
+    }
+
+    #[test]
+    fn orbit_quarter_turn_keeps_facing_pivot() {
+        let camera = Transform::from_position([0.0, 0.0, 5.0]);
+
+        let orbited = camera.orbit_around(Point3::new(0.0, 0.0, 0.0), std::f32::consts::FRAC_PI_2, 0.0);
+
+        let position = orbited.transform_point(GlamVec3::ZERO);
+        let forward = orbited.transform_vector(GlamVec3::NEG_Z);
+        assert!(position.abs_diff_eq(GlamVec3::new(5.0, 0.0, 0.0), 1e-4), "{position}");
+        assert!(forward.abs_diff_eq(GlamVec3::NEG_X, 1e-4), "{forward}");
+
+        // Pitch stops short of the pole
+        let position = camera.orbit_around(Point3::new(0.0, 0.0, 0.0), 0.0, 3.0).transform_point(GlamVec3::ZERO);
+        assert!(position.y > 4.99 && position.y < 5.0 && position.z > 0.0, "{position}");
+    }
+}
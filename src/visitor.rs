@@ -1,5 +1,5 @@
-use std::{collections::VecDeque, future::Future};
-use crate::{HalfEdgeMesh, VertexIndex};
+use std::{collections::{HashSet, VecDeque}, future::Future, hash::Hash};
+use crate::{FaceIndex, HalfEdgeIndex, HalfEdgeMesh, MeshId, SceneGraphNode, Transform, VertexIndex};
 
 // Trait for asynchronous visits on type T
 pub trait AsyncVisitor<T> {
@@ -24,6 +24,82 @@ impl AsyncVisitor<VertexIndex> for PrintVisitor {
     }
 }
 
+// Mesh element types that can be reached from each other by a traversal
+pub trait Adjacency: Copy + Eq + Hash {
+    fn neighbors(self, mesh: &HalfEdgeMesh) -> Vec<Self>;
+}
+
+// Vertices are adjacent when they share an edge
+impl Adjacency for VertexIndex {
+    fn neighbors(self, mesh: &HalfEdgeMesh) -> Vec<Self> {
+        mesh.vertex_neighbors(self).into_iter().collect()
+    }
+}
+
+// Half-edges are adjacent to the next and previous half-edges round their face and to their twin
+impl Adjacency for HalfEdgeIndex {
+    fn neighbors(self, mesh: &HalfEdgeMesh) -> Vec<Self> {
+        let he = mesh.half_edge(self);
+        [Some(he.next_edge), Some(he.prev_edge), he.twin_index].into_iter().flatten().collect()
+    }
+}
+
+// Faces are adjacent when they share an edge
+impl Adjacency for FaceIndex {
+    fn neighbors(self, mesh: &HalfEdgeMesh) -> Vec<Self> {
+        mesh.face_half_edges(self).into_iter()
+            .filter_map(|idx| mesh.half_edge(idx).twin_index)
+            .filter_map(|twin| mesh.half_edge(twin).face_index)
+            .collect()
+    }
+}
+
+// Order in which a traversal visits the elements it reaches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Traversal {
+    BreadthFirst,
+    DepthFirst,
+}
+
+// Traversal over any element type of a half-edge mesh, visiting each reachable element once
+pub async fn half_edge_mesh_traverse<T, V>(
+    mesh: &HalfEdgeMesh,
+    start: T,
+    order: Traversal,
+    visitor: &mut V
+)
+where
+    T: Adjacency,
+    V: AsyncVisitor<T>,
+{
+    let mut visited = HashSet::new();
+    let mut pending = VecDeque::new();
+
+    pending.push_back(start);
+
+    loop {
+        let element = match order {
+            Traversal::BreadthFirst => pending.pop_front(),
+            Traversal::DepthFirst => pending.pop_back(),
+        };
+        let Some(element) = element else { break };
+
+        // An element can be queued by several neighbors before it is reached
+        if !visited.insert(element) {
+            continue;
+        }
+
+        // Async call to visitor with mesh and element
+        visitor.visit(mesh, element).await;
+
+        for neighbor in element.neighbors(mesh) {
+            if !visited.contains(&neighbor) {
+                pending.push_back(neighbor);
+            }
+        }
+    }
+}
+
 // BFS traversal starting from a vertex, using half-edge mesh structure
 pub async fn half_edge_mesh_bfs<V>(
     mesh: &HalfEdgeMesh,
@@ -33,44 +109,54 @@ pub async fn half_edge_mesh_bfs<V>(
 where
     V: AsyncVisitor<VertexIndex>,
 {
-    use std::collections::HashSet;
-    
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-
-    queue.push_back(start);
-    visited.insert(start);
-
-    while let Some(vertex_idx) = queue.pop_front() {
-        // Async call to visitor with mesh and vertex index
-        visitor.visit(mesh, vertex_idx).await;
-
-        // Find neighbors by walking around the vertex via half-edges
-        if let Some(seed_he) = mesh.vertex(vertex_idx).seed_half_edge {
-            let mut current_he = seed_he;
-            
-            loop {
-                let he = mesh.half_edge(current_he);
-                let neighbor = he.target_vertex_index;
-                
-                // Add neighbor to queue if not visited
-                if visited.insert(neighbor) {
-                    queue.push_back(neighbor);
-                }
-                
-                // Move to next half-edge around this vertex
-                if let Some(twin) = he.twin_index {
-                    current_he = mesh.half_edge(twin).next_edge;
-                    
-                    // Stop when we've completed the loop
-                    if current_he == seed_he {
-                        break;
-                    }
-                } else {
-                    // Hit a boundary edge
-                    break;
-                }
-            }
+    half_edge_mesh_traverse(mesh, start, Traversal::BreadthFirst, visitor).await
+}
+
+// Trait for depth-first visits over the scene graph
+// World transforms are already composed with every ancestor's transform
+pub trait SceneVisitor {
+    fn visit_node(&mut self, _node: &SceneGraphNode, _world_transform: &Transform) {}
+    fn visit_model(&mut self, _mesh_id: MeshId, _world_transform: &Transform) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Records every element it is handed
+    struct Collect<T>(Vec<T>);
+
+    impl<T: Copy> AsyncVisitor<T> for Collect<T> {
+        async fn visit(&mut self, _mesh: &HalfEdgeMesh, element: T) {
+            self.0.push(element)
+        }
+    }
+
+    // The visitors here never wait on anything, so one poll finishes the traversal
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+        match future.as_mut().poll(&mut context) {
+            std::task::Poll::Ready(output) => output,
+            std::task::Poll::Pending => panic!("traversal didn't complete"),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn traversals_reach_every_cube_element() {
+        let cube = HalfEdgeMesh::create_cube(1.0);
+
+        let mut vertices = Collect(Vec::new());
+        block_on(half_edge_mesh_bfs(&cube, VertexIndex(0), &mut vertices));
+        assert_eq!(vertices.0.len(), cube.vertices.len());
+        assert_eq!(vertices.0.iter().collect::<HashSet<_>>().len(), cube.vertices.len());
+
+        let mut faces = Collect(Vec::new());
+        block_on(half_edge_mesh_traverse(&cube, FaceIndex(0), Traversal::DepthFirst, &mut faces));
+        assert_eq!(faces.0.len(), cube.faces.len());
+
+        let mut half_edges = Collect(Vec::new());
+        block_on(half_edge_mesh_traverse(&cube, HalfEdgeIndex(0), Traversal::BreadthFirst, &mut half_edges));
+        assert_eq!(half_edges.0.len(), cube.half_edges.len());
+    }
+}
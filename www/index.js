@@ -18,7 +18,7 @@ class DeltaBrush {
         this.renderer = null;
         this.controls = null;
         this.rustScene = null;
-        this.threeObjects = new Map(); // Maps Rust object IDs to Three.js objects
+        this.threeObjects = new Map(); // Maps Rust stable object IDs to Three.js objects
         this.wasmInitialized = false;
         this.meshCache = new Map(); // mesh_id -> THREE.BufferGeometry
         
@@ -811,9 +811,9 @@ class DeltaBrush {
 
         // Update or create Three.js objects
         for (const obj of sceneData) {
-            currentIds.add(obj.id);
+            currentIds.add(obj.stable_id);
 
-            if (!this.threeObjects.has(obj.id)) {
+            if (!this.threeObjects.has(obj.stable_id)) {
                 // Create new Three.js object
                 this.createThreeObject(obj);
             } else {
@@ -847,16 +847,19 @@ class DeltaBrush {
     // Get or create geometry for a mesh_id
     getGeometry(meshId) {
         if (!this.meshCache.has(meshId)) {
-            // Request mesh data from Rust
-            const meshData = this.rustScene.get_mesh_data(meshId);
-            
+            // Request mesh buffers from Rust as typed-array copies, skipping the serde round trip
             const geometry = new THREE.BufferGeometry();
-            const vertices = new Float32Array(meshData.vertex_coords);
-            const indices = new Uint32Array(meshData.face_indices);
+            const vertices = this.rustScene.get_mesh_positions(meshId);
+            const indices = this.rustScene.get_mesh_indices(meshId);
             
             geometry.setAttribute('position', new THREE.BufferAttribute(vertices, 3));
             geometry.setIndex(new THREE.BufferAttribute(indices, 1));
-            geometry.computeVertexNormals();
+            const normals = this.rustScene.get_mesh_normals(meshId);
+            if (normals) {
+                geometry.setAttribute('normal', new THREE.BufferAttribute(normals, 3));
+            } else {
+                geometry.computeVertexNormals();
+            }
             
             this.meshCache.set(meshId, geometry);
         }
@@ -898,15 +901,32 @@ class DeltaBrush {
         
         group.add(frontMesh);
         group.add(backMesh);
-        
+
+        // Display-only line and point helpers reuse the mesh's positions
+        const positions = geometry.getAttribute('position');
+        const lineIndices = this.rustScene.get_mesh_line_indices(renderInstance.mesh_id);
+        if (lineIndices && lineIndices.length > 0) {
+            const lineGeometry = new THREE.BufferGeometry();
+            lineGeometry.setAttribute('position', positions);
+            lineGeometry.setIndex(new THREE.BufferAttribute(lineIndices, 1));
+            group.add(new THREE.LineSegments(lineGeometry, new THREE.LineBasicMaterial({ color: 0xffaa00 })));
+        }
+        const pointIndices = this.rustScene.get_mesh_point_indices(renderInstance.mesh_id);
+        if (pointIndices && pointIndices.length > 0) {
+            const pointGeometry = new THREE.BufferGeometry();
+            pointGeometry.setAttribute('position', positions);
+            pointGeometry.setIndex(new THREE.BufferAttribute(pointIndices, 1));
+            group.add(new THREE.Points(pointGeometry, new THREE.PointsMaterial({ color: 0xffaa00, size: 6, sizeAttenuation: false })));
+        }
+
         this.updateThreeObjectTransform(group, renderInstance.transform);
 
         this.scene.add(group);
-        this.threeObjects.set(renderInstance.id, group);
+        this.threeObjects.set(renderInstance.stable_id, group);
     }
 
     updateThreeObject(renderInstance) {
-        const threeObj = this.threeObjects.get(renderInstance.id);
+        const threeObj = this.threeObjects.get(renderInstance.stable_id);
         if (threeObj) {
             this.updateThreeObjectTransform(threeObj, renderInstance.transform);
         }
@@ -917,7 +937,7 @@ class DeltaBrush {
         const selectedMeshes = [];
         
         for (const renderInstance of sceneData) {
-            const group = this.threeObjects.get(renderInstance.id);
+            const group = this.threeObjects.get(renderInstance.stable_id);
             if (group && renderInstance.is_selected) {
                 // Add both front and back meshes to selection
                 selectedMeshes.push(group.children[0]); // front mesh